@@ -0,0 +1,48 @@
+use json::Element;
+
+/// Returns Rust source which builds the element
+pub fn element(element: &Element) -> String {
+    match element {
+        Element::JsonObject(o) => {
+            if o.is_empty() {
+                return "::json::Element::JsonObject(::std::collections::HashMap::new())".into();
+            }
+            // Sorted so that the expansion is reproducible
+            let mut members = o.iter().collect::<Vec<_>>();
+            members.sort_by_key(|&(k, _)| k);
+            let members = members
+                .into_iter()
+                .map(|(k, v)| format!("({}, {})", string(k), self::element(v)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("::json::Element::JsonObject(::std::collections::HashMap::from([{members}]))")
+        }
+        Element::JsonList(l) => {
+            let elements = l.iter().map(self::element).collect::<Vec<_>>().join(", ");
+            format!("::json::Element::JsonList(::std::vec![{elements}])")
+        }
+        Element::JsonString(s) => format!("::json::Element::JsonString({})", string(s)),
+        Element::JsonInt(i) => format!("::json::Element::JsonInt({})", int(*i)),
+        Element::JsonFloat(f) => format!("::json::Element::JsonFloat({})", float(*f)),
+        Element::JsonBool(b) => format!("::json::Element::JsonBool({b})"),
+        Element::JsonNull => "::json::Element::JsonNull".into(),
+    }
+}
+
+pub fn string(s: &str) -> String {
+    format!("::std::string::String::from({s:?})")
+}
+
+pub fn int(i: i64) -> String {
+    format!("{i}i64")
+}
+
+pub fn float(f: f64) -> String {
+    if f.is_finite() {
+        format!("{f:?}f64")
+    } else if f > 0.0 {
+        "::core::f64::INFINITY".into()
+    } else {
+        "::core::f64::NEG_INFINITY".into()
+    }
+}
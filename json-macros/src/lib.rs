@@ -0,0 +1,165 @@
+//! Macros which parse JSON files at compile time, so that malformed files
+//! fail the build instead of failing at runtime. The expansions refer to the
+//! `json` crate, which must be a dependency of the invoking crate.
+
+mod codegen;
+mod shape;
+
+use std::{fs, path::PathBuf};
+
+use json::{unmarshal::Position, Element, UnmarshalError};
+use proc_macro::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+
+use shape::Shape;
+
+/// Parses a JSON file at compile time and expands to an expression building
+/// the `Element`. The path is relative to the invoking file, like
+/// `include_str!`. Invalid JSON fails compilation with an error like
+/// "data/config.json:3:9: unexpected character '}'".
+#[proc_macro]
+pub fn include_json(input: TokenStream) -> TokenStream {
+    let tokens = flatten(input);
+    let [path] = &tokens[..] else {
+        return error(Span::call_site(), "expected a path string literal");
+    };
+    expand(path, |element, _, _| Ok(codegen::element(element)))
+}
+
+/// Parses a JSON file at compile time and checks it against a type, then
+/// expands to an expression building a value of that type.
+///
+/// The type is made of `i64`, `f64`, `bool`, `String`, `Element`, `RawJson`,
+/// `Vec`, `Option`, and `HashMap` or `BTreeMap` with `String` keys, so that
+/// the whole file is checked at compile time. Other types are rejected; use
+/// `include_json!` and convert the `Element` instead. A mismatch fails
+/// compilation with an error like
+/// "data/config.json:2:13: expected an integer, found a string".
+#[proc_macro]
+pub fn include_json_as(input: TokenStream) -> TokenStream {
+    let mut tokens = flatten(input);
+    let path = tokens.pop();
+    let comma = tokens.pop();
+    let (Some(path), Some(TokenTree::Punct(comma))) = (path, comma) else {
+        return error(Span::call_site(), "expected a type and a path");
+    };
+    if comma.as_char() != ',' || tokens.is_empty() {
+        return error(comma.span(), "expected a type and a path");
+    }
+    let shape = match Shape::parse(&tokens) {
+        Ok(shape) => shape,
+        Err((span, message)) => return error(span, &message),
+    };
+    expand(&path, |element, spans, source| {
+        shape.check(element, spans, "")?;
+        Ok(shape.build(element, spans, source, ""))
+    })
+}
+
+/// Reads and parses the file named by the literal, then builds the expansion.
+/// The file is also included as bytes so that the invoking crate is rebuilt
+/// when it changes.
+fn expand<F>(path: &TokenTree, build: F) -> TokenStream
+where
+    F: FnOnce(&Element, &json::unmarshal::Spans, &str) -> Result<String, (String, Position)>,
+{
+    let span = path.span();
+    let Some(relative) = string_literal(path) else {
+        return error(span, "expected a path string literal");
+    };
+    let resolved = resolve(&relative);
+    let display = resolved.display().to_string();
+    let contents = match fs::read_to_string(&resolved) {
+        Ok(contents) => contents,
+        Err(e) => return error(span, &format!("couldn't read {display}: {e}")),
+    };
+    let (element, spans) = match Element::unmarshal_json_spanned(contents.chars()) {
+        Ok(parsed) => parsed,
+        Err(e) => return error(span, &format!("{display}:{}", describe_error(e, &contents))),
+    };
+    let absolute = fs::canonicalize(&resolved).unwrap_or(resolved);
+    match build(&element, &spans, &contents) {
+        Ok(expr) => format!(
+            "{{ const _: &[u8] = ::std::include_bytes!({:?}); {expr} }}",
+            absolute.display().to_string()
+        )
+        .parse()
+        .unwrap(),
+        Err((message, position)) => error(
+            span,
+            &format!("{display}:{}:{}: {message}", position.row, position.col),
+        ),
+    }
+}
+
+/// Formats an error as "row:col: message"
+fn describe_error(e: UnmarshalError, contents: &str) -> String {
+    match e {
+        UnmarshalError::UnexpectedChar { c, row, col } => {
+            format!("{row}:{col}: unexpected character {c:?}")
+        }
+        UnmarshalError::InvalidUtf8 { row, col } => format!("{row}:{col}: invalid UTF-8"),
+        _ => {
+            let row = contents.lines().count().max(1);
+            let col = contents.lines().last().map_or(0, |l| l.chars().count()) + 1;
+            format!("{row}:{col}: unexpected end of file")
+        }
+    }
+}
+
+/// Resolves a path relative to the directory of the invoking file
+fn resolve(relative: &str) -> PathBuf {
+    let dir = Span::call_site()
+        .local_file()
+        .and_then(|file| file.parent().map(PathBuf::from))
+        .or_else(|| std::env::var_os("CARGO_MANIFEST_DIR").map(PathBuf::from))
+        .unwrap_or_default();
+    dir.join(relative)
+}
+
+/// Unwraps invisible groups, which macro_rules adds around fragments
+fn flatten(input: TokenStream) -> Vec<TokenTree> {
+    let mut tokens = Vec::new();
+    for token in input {
+        match token {
+            TokenTree::Group(g) if g.delimiter() == Delimiter::None => {
+                tokens.extend(flatten(g.stream()))
+            }
+            token => tokens.push(token),
+        }
+    }
+    tokens
+}
+
+/// Returns the value of a plain or raw string literal without escapes
+fn string_literal(token: &TokenTree) -> Option<String> {
+    let TokenTree::Literal(literal) = token else {
+        return None;
+    };
+    let s = literal.to_string();
+    let inner = match s.strip_prefix('r') {
+        Some(raw) => {
+            let hashes = raw.len() - raw.trim_start_matches('#').len();
+            raw.get(hashes + 1..raw.len() - hashes - 1)?
+        }
+        None if !s.contains('\\') => s.strip_prefix('"')?.strip_suffix('"')?,
+        None => return None,
+    };
+    Some(inner.to_owned())
+}
+
+/// Expands to `compile_error!`, pointing at the span
+fn error(span: Span, message: &str) -> TokenStream {
+    let mut message = TokenTree::Literal(Literal::string(message));
+    message.set_span(span);
+    let mut bang = TokenTree::Punct(Punct::new('!', Spacing::Alone));
+    bang.set_span(span);
+    let mut group = TokenTree::Group(Group::new(Delimiter::Brace, message.into()));
+    group.set_span(span);
+    [
+        TokenTree::Ident(Ident::new("compile_error", span)),
+        bang,
+        group,
+    ]
+    .into_iter()
+    .collect()
+}
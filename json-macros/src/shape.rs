@@ -0,0 +1,221 @@
+use json::{
+    unmarshal::{Position, Spans},
+    Element,
+};
+use proc_macro::{Span, TokenTree};
+
+use crate::codegen;
+
+/// The expected shape of a JSON value, from a type
+pub enum Shape {
+    Int,
+    Float,
+    Bool,
+    String,
+    Element,
+    List(Box<Shape>),
+    Option(Box<Shape>),
+    /// A map with `String` keys, and whether it's a `BTreeMap`
+    Map(Box<Shape>, bool),
+    /// A `RawJson`, built from the source text of any value
+    Raw,
+}
+
+/// The types `include_json_as!` can check, for error messages
+const SUPPORTED: &str = "i64, f64, bool, String, Element, RawJson, Vec, Option, \
+                         or HashMap or BTreeMap with String keys";
+
+impl Shape {
+    /// Parses a type, returning the span of the offending token and a
+    /// message on error
+    pub fn parse(tokens: &[TokenTree]) -> Result<Shape, (Span, String)> {
+        let mut pos = 0;
+        let shape = parse_type(tokens, &mut pos)?;
+        match tokens.get(pos) {
+            None => Ok(shape),
+            Some(token) => Err((token.span(), "expected a type".into())),
+        }
+    }
+
+    /// Checks an element, returning a message and position on mismatch
+    pub fn check(
+        &self,
+        element: &Element,
+        spans: &Spans,
+        pointer: &str,
+    ) -> Result<(), (String, Position)> {
+        let ok = match (self, element) {
+            (Shape::Element | Shape::Raw, _)
+            | (Shape::Int, Element::JsonInt(_))
+            | (Shape::Float, Element::JsonInt(_) | Element::JsonFloat(_))
+            | (Shape::Bool, Element::JsonBool(_))
+            | (Shape::String, Element::JsonString(_))
+            | (Shape::Option(_), Element::JsonNull) => true,
+            (Shape::Option(inner), _) => return inner.check(element, spans, pointer),
+            (Shape::List(inner), Element::JsonList(l)) => {
+                for (i, e) in l.iter().enumerate() {
+                    inner.check(e, spans, &format!("{pointer}/{i}"))?;
+                }
+                true
+            }
+            (Shape::Map(inner, _), Element::JsonObject(o)) => {
+                for (k, v) in o {
+                    inner.check(v, spans, &format!("{pointer}/{}", escape(k)))?;
+                }
+                true
+            }
+            _ => false,
+        };
+        if ok {
+            return Ok(());
+        }
+        // Every value is recorded, so this only fails on a bug in `Spans`
+        let position = spans.value(pointer).expect("value has a span").start;
+        let message = format!("expected {}, found {}", self.describe(), found(element));
+        Err((message, position))
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Shape::Int => "an integer".into(),
+            Shape::Float => "a number".into(),
+            Shape::Bool => "a boolean".into(),
+            Shape::String => "a string".into(),
+            Shape::List(_) => "an array".into(),
+            Shape::Option(inner) => format!("null or {}", inner.describe()),
+            Shape::Map(..) => "an object".into(),
+            Shape::Element | Shape::Raw => "any value".into(),
+        }
+    }
+
+    /// Returns Rust source which builds a checked element as this type. Raw
+    /// values are sliced from the source text using their spans.
+    pub fn build(&self, element: &Element, spans: &Spans, source: &str, pointer: &str) -> String {
+        match (self, element) {
+            (Shape::Raw, _) => {
+                let span = spans.value(pointer).expect("value has a span");
+                let text = &source[span.start.offset..span.end.offset];
+                format!("::json::RawJson::new_unchecked({})", codegen::string(text))
+            }
+            (Shape::Int, Element::JsonInt(i)) => codegen::int(*i),
+            (Shape::Float, Element::JsonInt(i)) => codegen::float(*i as f64),
+            (Shape::Float, Element::JsonFloat(f)) => codegen::float(*f),
+            (Shape::Bool, Element::JsonBool(b)) => b.to_string(),
+            (Shape::String, Element::JsonString(s)) => codegen::string(s),
+            (Shape::Option(_), Element::JsonNull) => "::std::option::Option::None".into(),
+            (Shape::Option(inner), _) => {
+                let inner = inner.build(element, spans, source, pointer);
+                format!("::std::option::Option::Some({inner})")
+            }
+            (Shape::List(inner), Element::JsonList(l)) => {
+                let elements = l
+                    .iter()
+                    .enumerate()
+                    .map(|(i, e)| inner.build(e, spans, source, &format!("{pointer}/{i}")))
+                    .collect::<Vec<_>>();
+                format!("::std::vec![{}]", elements.join(", "))
+            }
+            (Shape::Map(inner, btree), Element::JsonObject(o)) => {
+                let mut members = o.iter().collect::<Vec<_>>();
+                members.sort_by_key(|&(k, _)| k);
+                let members = members
+                    .into_iter()
+                    .map(|(k, v)| {
+                        let pointer = format!("{pointer}/{}", escape(k));
+                        let value = inner.build(v, spans, source, &pointer);
+                        format!("({}, {value})", codegen::string(k))
+                    })
+                    .collect::<Vec<_>>();
+                let map = if *btree { "BTreeMap" } else { "HashMap" };
+                format!(
+                    "<::std::collections::{map}<_, _> as ::std::iter::FromIterator<_>>::from_iter([{}])",
+                    members.join(", ")
+                )
+            }
+            _ => codegen::element(element),
+        }
+    }
+}
+
+fn found(element: &Element) -> &'static str {
+    match element {
+        Element::JsonObject(_) => "an object",
+        Element::JsonList(_) => "an array",
+        Element::JsonString(_) => "a string",
+        Element::JsonInt(_) => "an integer",
+        Element::JsonFloat(_) => "a float",
+        Element::JsonBool(_) => "a boolean",
+        Element::JsonNull => "null",
+    }
+}
+
+/// Escapes an object key as a JSON Pointer token
+fn escape(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+fn is_punct(token: Option<&TokenTree>, c: char) -> bool {
+    matches!(token, Some(TokenTree::Punct(p)) if p.as_char() == c)
+}
+
+/// Parses a path like `std::vec::Vec<T>`, using its last segment and
+/// generic arguments to pick the shape. Types which can't be checked at
+/// compile time are rejected.
+fn parse_type(tokens: &[TokenTree], pos: &mut usize) -> Result<Shape, (Span, String)> {
+    let unexpected = |pos: usize| {
+        let span = tokens
+            .get(pos)
+            .or(tokens.last())
+            .map_or_else(Span::call_site, TokenTree::span);
+        (span, "expected a type".to_owned())
+    };
+    if is_punct(tokens.get(*pos), ':') {
+        *pos += 2;
+    }
+    let (name, span) = loop {
+        let Some(TokenTree::Ident(ident)) = tokens.get(*pos) else {
+            return Err(unexpected(*pos));
+        };
+        *pos += 1;
+        if is_punct(tokens.get(*pos), ':') && is_punct(tokens.get(*pos + 1), ':') {
+            *pos += 2;
+        } else {
+            break (ident.to_string(), ident.span());
+        }
+    };
+    let mut args = Vec::new();
+    if is_punct(tokens.get(*pos), '<') {
+        *pos += 1;
+        loop {
+            args.push(parse_type(tokens, pos)?);
+            if is_punct(tokens.get(*pos), ',') {
+                *pos += 1;
+            }
+            if is_punct(tokens.get(*pos), '>') {
+                *pos += 1;
+                break;
+            }
+            if tokens.get(*pos).is_none() {
+                return Err(unexpected(*pos));
+            }
+        }
+    }
+    let mut args = args.into_iter();
+    match (name.as_str(), args.next(), args.next(), args.next()) {
+        ("i64", None, ..) => Ok(Shape::Int),
+        ("f64", None, ..) => Ok(Shape::Float),
+        ("bool", None, ..) => Ok(Shape::Bool),
+        ("String", None, ..) => Ok(Shape::String),
+        ("Element", None, ..) => Ok(Shape::Element),
+        ("RawJson", None, ..) => Ok(Shape::Raw),
+        ("Vec", Some(inner), None, _) => Ok(Shape::List(Box::new(inner))),
+        ("Option", Some(inner), None, _) => Ok(Shape::Option(Box::new(inner))),
+        ("HashMap" | "BTreeMap", Some(Shape::String), Some(inner), None) => {
+            Ok(Shape::Map(Box::new(inner), name == "BTreeMap"))
+        }
+        _ => Err((
+            span,
+            format!("unsupported type `{name}`, expected {SUPPORTED}"),
+        )),
+    }
+}
@@ -0,0 +1,5 @@
+#[test]
+fn compile_fail_test() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
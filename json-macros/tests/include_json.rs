@@ -0,0 +1,102 @@
+use std::collections::{BTreeMap, HashMap};
+
+use json::{Element, Unmarshalable};
+use json_macros::{include_json, include_json_as};
+
+#[test]
+fn include_json_test() {
+    let config = include_json!("data/config.json");
+    let expected = Element::unmarshal_json(include_str!("data/config.json").chars()).unwrap();
+    assert_eq!(config, expected);
+    assert_eq!(config["escaped"].as_str(), Some("quote \" and é and \n"));
+
+    let numbers = include_json!(r"data/numbers.json");
+    assert_eq!(
+        numbers,
+        Element::JsonList(vec![
+            Element::JsonInt(1),
+            Element::JsonFloat(-2.5),
+            Element::JsonFloat(1000.0),
+            Element::JsonInt(-i64::MAX),
+            Element::JsonNull,
+        ])
+    );
+}
+
+/// Macros pass literals as invisible groups
+macro_rules! include_fixture {
+    ($path:literal) => {
+        include_json!($path)
+    };
+}
+
+#[test]
+fn include_json_through_macro_test() {
+    assert_eq!(
+        include_fixture!("data/ports.json"),
+        include_json!("data/ports.json")
+    );
+}
+
+#[test]
+fn include_json_as_test() {
+    let ports = include_json_as!(HashMap<String, Vec<i64>>, "data/ports.json");
+    assert_eq!(
+        ports,
+        HashMap::from([
+            ("http".to_owned(), vec![80, 8080]),
+            ("https".to_owned(), vec![443]),
+            ("none".to_owned(), vec![]),
+        ])
+    );
+    let ports = include_json_as!(std::collections::BTreeMap<String, Vec<f64>>, "data/ports.json");
+    assert_eq!(ports["http"], vec![80.0, 8080.0]);
+    assert!(ports.keys().eq(["http", "https", "none"]));
+
+    let numbers = include_json_as!(Vec<Option<f64>>, "data/numbers.json");
+    assert_eq!(
+        numbers,
+        vec![
+            Some(1.0),
+            Some(-2.5),
+            Some(1000.0),
+            Some(-i64::MAX as f64),
+            None
+        ]
+    );
+
+    let config = include_json_as!(HashMap<String, json::Element>, "data/config.json");
+    assert_eq!(config["port"], Element::JsonInt(8080));
+    assert_eq!(config.len(), 8);
+}
+
+#[test]
+fn include_json_as_raw_test() {
+    // Raw values keep their source text
+    let limits: BTreeMap<_, _> =
+        include_json_as!(BTreeMap<String, json::RawJson>, "data/config.json");
+    assert_eq!(limits["limits"].as_str(), r#"{"min": 1, "max": 10}"#);
+    let tags = include_json_as!(Vec<json::RawJson>, "data/numbers.json");
+    assert_eq!(tags.len(), 5);
+    assert_eq!(tags[2].as_str(), "1e3");
+}
+
+/// Expansions mustn't resolve `f64` to whatever is in scope at the call site
+mod shadowed_f64 {
+    use json::Element;
+    use json_macros::include_json;
+
+    #[allow(non_camel_case_types, dead_code)]
+    struct f64;
+
+    #[test]
+    fn include_json_infinite_test() {
+        assert_eq!(
+            include_json!("data/infinite.json"),
+            Element::JsonList(vec![
+                Element::JsonFloat(::core::primitive::f64::INFINITY),
+                Element::JsonFloat(::core::primitive::f64::NEG_INFINITY),
+            ])
+        );
+    }
+}
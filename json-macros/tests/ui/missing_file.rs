@@ -0,0 +1,5 @@
+use json_macros::include_json;
+
+fn main() {
+    let _ = include_json!("data/missing.json");
+}
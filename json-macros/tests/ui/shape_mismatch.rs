@@ -0,0 +1,5 @@
+use json_macros::include_json_as;
+
+fn main() {
+    let _ = include_json_as!(std::collections::HashMap<String, i64>, "data/port.json");
+}
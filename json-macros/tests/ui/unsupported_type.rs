@@ -0,0 +1,9 @@
+use json_macros::include_json_as;
+
+struct Config {
+    port: i64,
+}
+
+fn main() {
+    let _: Vec<Config> = include_json_as!(Vec<Config>, "data/port.json");
+}
@@ -0,0 +1,37 @@
+use std::{borrow::Cow, collections::HashMap};
+
+use crate::Element;
+
+#[derive(Debug, PartialEq)]
+/// An unknown JSON element like `Element`, but with strings and keys
+/// borrowed from the unmarshaled input when they don't contain escapes.
+pub enum BorrowedElement<'a> {
+    JsonObject(HashMap<Cow<'a, str>, BorrowedElement<'a>>),
+    JsonList(Vec<BorrowedElement<'a>>),
+    JsonString(Cow<'a, str>),
+    JsonInt(i64),
+    JsonFloat(f64),
+    JsonBool(bool),
+    JsonNull,
+}
+
+impl<'a> BorrowedElement<'a> {
+    /// Copies any borrowed strings to make an owned `Element`
+    pub fn into_owned(self) -> Element {
+        match self {
+            BorrowedElement::JsonObject(o) => Element::JsonObject(
+                o.into_iter()
+                    .map(|(k, v)| (k.into_owned(), v.into_owned()))
+                    .collect(),
+            ),
+            BorrowedElement::JsonList(l) => {
+                Element::JsonList(l.into_iter().map(BorrowedElement::into_owned).collect())
+            }
+            BorrowedElement::JsonString(s) => Element::JsonString(s.into_owned()),
+            BorrowedElement::JsonInt(i) => Element::JsonInt(i),
+            BorrowedElement::JsonFloat(f) => Element::JsonFloat(f),
+            BorrowedElement::JsonBool(b) => Element::JsonBool(b),
+            BorrowedElement::JsonNull => Element::JsonNull,
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use std::fmt;
+
+use crate::{
+    marshal::{string::marshal_str, MarshalOptions},
+    Element, Marshalable,
+};
+
+/// Limits on how much of an element is shown by `Element::debug_bounded`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DebugLimits {
+    /// The number of characters shown from each string
+    pub string_chars: usize,
+    /// The number of members or elements shown from each object or array
+    pub items: usize,
+    /// The depth below which objects and arrays are elided
+    pub depth: usize,
+}
+
+impl Default for DebugLimits {
+    fn default() -> Self {
+        DebugLimits {
+            string_chars: 64,
+            items: 16,
+            depth: 8,
+        }
+    }
+}
+
+/// Formats an element as JSON, eliding anything past the limits with '…'.
+/// Object members are sorted by key so the output is stable.
+pub struct BoundedDebug<'a> {
+    element: &'a Element,
+    limits: DebugLimits,
+}
+
+impl Element {
+    /// Returns a wrapper for logging the element without flooding the output
+    pub fn debug_bounded(&self, limits: DebugLimits) -> BoundedDebug<'_> {
+        BoundedDebug {
+            element: self,
+            limits,
+        }
+    }
+}
+
+impl fmt::Debug for BoundedDebug<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut s = String::new();
+        self.write(self.element, &mut s, 0);
+        f.write_str(&s)
+    }
+}
+
+impl BoundedDebug<'_> {
+    fn write(&self, e: &Element, s: &mut String, depth: usize) {
+        match e {
+            Element::JsonObject(o) if !o.is_empty() => {
+                if depth >= self.limits.depth {
+                    s.push_str("{…}");
+                    return;
+                }
+                let mut members = o.iter().collect::<Vec<_>>();
+                members.sort_by_key(|&(k, _)| k);
+                s.push('{');
+                for (i, (k, v)) in members.into_iter().enumerate() {
+                    if i > 0 {
+                        s.push_str(", ");
+                    }
+                    if i == self.limits.items {
+                        s.push('…');
+                        break;
+                    }
+                    self.write_str(k, s);
+                    s.push_str(": ");
+                    self.write(v, s, depth + 1);
+                }
+                s.push('}');
+            }
+            Element::JsonList(l) if !l.is_empty() => {
+                if depth >= self.limits.depth {
+                    s.push_str("[…]");
+                    return;
+                }
+                s.push('[');
+                for (i, v) in l.iter().enumerate() {
+                    if i > 0 {
+                        s.push_str(", ");
+                    }
+                    if i == self.limits.items {
+                        s.push('…');
+                        break;
+                    }
+                    self.write(v, s, depth + 1);
+                }
+                s.push(']');
+            }
+            Element::JsonString(string) => self.write_str(string, s),
+            _ => e.marshal_json_into(s),
+        }
+    }
+
+    /// Writes a quoted string, with '…' inside the quotes if it was
+    /// truncated
+    fn write_str(&self, string: &str, s: &mut String) {
+        match string.char_indices().nth(self.limits.string_chars) {
+            Some((end, _)) => {
+                marshal_str(&string[..end], s, &MarshalOptions::default());
+                s.insert(s.len() - 1, '…');
+            }
+            None => marshal_str(string, s, &MarshalOptions::default()),
+        }
+    }
+}
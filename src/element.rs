@@ -1,6 +1,13 @@
-use std::collections::HashMap;
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    hash::{Hash, Hasher},
+    ops::{Index, IndexMut},
+};
 
-#[derive(Debug, PartialEq)]
+use crate::{FromElementError, Unmarshalable};
+
+#[derive(Debug, Clone, Default)]
 /// An enum describing an unknown JSON element for unmarshaling JSON where the
 /// layout is not known ahead of time.
 pub enum Element {
@@ -10,5 +17,489 @@ pub enum Element {
     JsonInt(i64),
     JsonFloat(f64),
     JsonBool(bool),
+    #[default]
     JsonNull,
 }
+
+/// Returned when indexing an element misses, so that lookups can be chained
+/// like `element["a"][0]["b"]`.
+static NULL: Element = Element::JsonNull;
+
+impl Element {
+    /// Looks up a key in an object or an index in an array
+    pub fn get<I: ElementIndex>(&self, index: I) -> Option<&Element> {
+        index.index_into(self)
+    }
+
+    pub fn get_mut<I: ElementIndex>(&mut self, index: I) -> Option<&mut Element> {
+        index.index_into_mut(self)
+    }
+
+    pub fn is_object(&self) -> bool {
+        matches!(self, Element::JsonObject(_))
+    }
+
+    pub fn is_array(&self) -> bool {
+        matches!(self, Element::JsonList(_))
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, Element::JsonNull)
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Element::JsonString(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Element::JsonInt(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    /// Returns floats, or ints converted to floats
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Element::JsonFloat(f) => Some(*f),
+            Element::JsonInt(i) => Some(*i as f64),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Element::JsonBool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&Vec<Element>> {
+        match self {
+            Element::JsonList(l) => Some(l),
+            _ => None,
+        }
+    }
+
+    pub fn as_array_mut(&mut self) -> Option<&mut Vec<Element>> {
+        match self {
+            Element::JsonList(l) => Some(l),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&HashMap<String, Element>> {
+        match self {
+            Element::JsonObject(o) => Some(o),
+            _ => None,
+        }
+    }
+
+    pub fn as_object_mut(&mut self) -> Option<&mut HashMap<String, Element>> {
+        match self {
+            Element::JsonObject(o) => Some(o),
+            _ => None,
+        }
+    }
+
+    /// Takes the value out, leaving null in its place
+    pub fn take(&mut self) -> Element {
+        std::mem::take(self)
+    }
+
+    /// Returns the number of members or elements of an object or array
+    pub fn len(&self) -> Option<usize> {
+        match self {
+            Element::JsonObject(o) => Some(o.len()),
+            Element::JsonList(l) => Some(l.len()),
+            _ => None,
+        }
+    }
+
+    /// Returns whether an object or array has no members or elements
+    pub fn is_empty(&self) -> Option<bool> {
+        self.len().map(|len| len == 0)
+    }
+
+    /// Iterates over the members of an object, or nothing for other values
+    pub fn members(&self) -> impl Iterator<Item = (&str, &Element)> {
+        self.as_object()
+            .into_iter()
+            .flatten()
+            .map(|(k, v)| (k.as_str(), v))
+    }
+
+    pub fn members_mut(&mut self) -> impl Iterator<Item = (&str, &mut Element)> {
+        self.as_object_mut()
+            .into_iter()
+            .flatten()
+            .map(|(k, v)| (k.as_str(), v))
+    }
+
+    /// Iterates over the elements of an array, or nothing for other values
+    pub fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.as_array().into_iter().flatten()
+    }
+
+    pub fn elements_mut(&mut self) -> impl Iterator<Item = &mut Element> {
+        self.as_array_mut().into_iter().flatten()
+    }
+
+    /// Compares elements deeply like JSON numbers, so ints and floats with
+    /// the same value are equal. Floats are compared like `f64`, so
+    /// `0.0 == -0.0` and NaN is never equal. `==` treats each of these as
+    /// different values.
+    pub fn eq_numeric(&self, other: &Element) -> bool {
+        match (self, other) {
+            (Element::JsonObject(l), Element::JsonObject(r)) => {
+                l.len() == r.len()
+                    && l.iter()
+                        .all(|(k, v)| r.get(k).is_some_and(|r| v.eq_numeric(r)))
+            }
+            (Element::JsonList(l), Element::JsonList(r)) => {
+                l.len() == r.len() && l.iter().zip(r).all(|(l, r)| l.eq_numeric(r))
+            }
+            (Element::JsonInt(l), Element::JsonFloat(r))
+            | (Element::JsonFloat(r), Element::JsonInt(l)) => {
+                !r.is_nan() && cmp_int_float(*l, *r) == Ordering::Equal
+            }
+            (Element::JsonFloat(l), Element::JsonFloat(r)) => l == r,
+            _ => self == other,
+        }
+    }
+
+    /// The position of the type in the ordering of elements
+    fn type_order(&self) -> u8 {
+        match self {
+            Element::JsonNull => 0,
+            Element::JsonBool(_) => 1,
+            Element::JsonInt(_) | Element::JsonFloat(_) => 2,
+            Element::JsonString(_) => 3,
+            Element::JsonList(_) => 4,
+            Element::JsonObject(_) => 5,
+        }
+    }
+}
+
+/// Returns the members of an object sorted by key, so that objects can be
+/// compared and hashed independently of the order of their members
+fn sorted_members(o: &HashMap<String, Element>) -> Vec<(&String, &Element)> {
+    let mut members = o.iter().collect::<Vec<_>>();
+    members.sort_unstable_by_key(|&(k, _)| k);
+    members
+}
+
+/// Compares an int with a float exactly by value. NaN is ordered like in
+/// `f64::total_cmp`, above or below every other value depending on its sign.
+fn cmp_int_float(i: i64, f: f64) -> Ordering {
+    // The bounds of the i64 range, which are exactly representable
+    const MAX: f64 = 9223372036854775808.0;
+    if f.is_nan() {
+        return if f.is_sign_negative() {
+            Ordering::Greater
+        } else {
+            Ordering::Less
+        };
+    } else if f >= MAX {
+        return Ordering::Less;
+    } else if f < -MAX {
+        return Ordering::Greater;
+    }
+    let whole = f.trunc();
+    i.cmp(&(whole as i64))
+        .then_with(|| 0.0.partial_cmp(&(f - whole)).unwrap_or(Ordering::Equal))
+}
+
+/// Elements are equal when they have the same type and value, with floats
+/// compared by `f64::total_cmp`. Ints never equal floats, and NaN equals
+/// itself. See `eq_numeric` for comparing like JSON numbers.
+impl PartialEq for Element {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Element::JsonObject(l), Element::JsonObject(r)) => l == r,
+            (Element::JsonList(l), Element::JsonList(r)) => l == r,
+            (Element::JsonString(l), Element::JsonString(r)) => l == r,
+            (Element::JsonInt(l), Element::JsonInt(r)) => l == r,
+            (Element::JsonFloat(l), Element::JsonFloat(r)) => l.total_cmp(r) == Ordering::Equal,
+            (Element::JsonBool(l), Element::JsonBool(r)) => l == r,
+            (Element::JsonNull, Element::JsonNull) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Element {}
+
+impl PartialOrd for Element {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Orders elements by type: null, booleans, numbers, strings, arrays then
+/// objects. Numbers are ordered by value, with ints before floats of the same
+/// value and floats ordered by `f64::total_cmp`. Arrays are ordered
+/// lexicographically, and objects by their members sorted by key.
+impl Ord for Element {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Element::JsonObject(l), Element::JsonObject(r)) => {
+                sorted_members(l).cmp(&sorted_members(r))
+            }
+            (Element::JsonList(l), Element::JsonList(r)) => l.cmp(r),
+            (Element::JsonString(l), Element::JsonString(r)) => l.cmp(r),
+            (Element::JsonInt(l), Element::JsonInt(r)) => l.cmp(r),
+            (Element::JsonFloat(l), Element::JsonFloat(r)) => l.total_cmp(r),
+            (Element::JsonInt(i), Element::JsonFloat(f)) => {
+                cmp_int_float(*i, *f).then(Ordering::Less)
+            }
+            (Element::JsonFloat(f), Element::JsonInt(i)) => {
+                cmp_int_float(*i, *f).reverse().then(Ordering::Greater)
+            }
+            (Element::JsonBool(l), Element::JsonBool(r)) => l.cmp(r),
+            _ => self.type_order().cmp(&other.type_order()),
+        }
+    }
+}
+
+/// Hashes objects independently of the order of their members
+impl Hash for Element {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Element::JsonObject(o) => {
+                state.write_usize(o.len());
+                for (k, v) in sorted_members(o) {
+                    k.hash(state);
+                    v.hash(state);
+                }
+            }
+            Element::JsonList(l) => l.hash(state),
+            Element::JsonString(s) => s.hash(state),
+            Element::JsonInt(i) => i.hash(state),
+            Element::JsonFloat(f) => f.to_bits().hash(state),
+            Element::JsonBool(b) => b.hash(state),
+            Element::JsonNull => (),
+        }
+    }
+}
+
+mod private {
+    pub trait Sealed {}
+    impl Sealed for usize {}
+    impl Sealed for str {}
+    impl Sealed for String {}
+    impl<T: Sealed + ?Sized> Sealed for &T {}
+}
+
+/// A key or array index which can be used to look up a value in an
+/// `Element`, with `get` or the index operators.
+pub trait ElementIndex: private::Sealed {
+    #[doc(hidden)]
+    fn index_into<'a>(&self, element: &'a Element) -> Option<&'a Element>;
+    #[doc(hidden)]
+    fn index_into_mut<'a>(&self, element: &'a mut Element) -> Option<&'a mut Element>;
+    /// Returns the value to assign to, inserting null for a missing key.
+    /// Panics if the element can't be indexed this way.
+    #[doc(hidden)]
+    fn index_or_insert<'a>(&self, element: &'a mut Element) -> &'a mut Element;
+}
+
+impl ElementIndex for usize {
+    fn index_into<'a>(&self, element: &'a Element) -> Option<&'a Element> {
+        element.as_array()?.get(*self)
+    }
+
+    fn index_into_mut<'a>(&self, element: &'a mut Element) -> Option<&'a mut Element> {
+        element.as_array_mut()?.get_mut(*self)
+    }
+
+    fn index_or_insert<'a>(&self, element: &'a mut Element) -> &'a mut Element {
+        match element {
+            Element::JsonList(l) => {
+                let len = l.len();
+                l.get_mut(*self).unwrap_or_else(|| {
+                    panic!("index {self} out of bounds for an array of length {len}")
+                })
+            }
+            _ => panic!("cannot index into a non-array with {self}"),
+        }
+    }
+}
+
+impl ElementIndex for str {
+    fn index_into<'a>(&self, element: &'a Element) -> Option<&'a Element> {
+        element.as_object()?.get(self)
+    }
+
+    fn index_into_mut<'a>(&self, element: &'a mut Element) -> Option<&'a mut Element> {
+        element.as_object_mut()?.get_mut(self)
+    }
+
+    /// Null becomes an empty object, so documents can be built up with
+    /// `element["a"]["b"] = value`
+    fn index_or_insert<'a>(&self, element: &'a mut Element) -> &'a mut Element {
+        if element.is_null() {
+            *element = Element::JsonObject(HashMap::new());
+        }
+        match element {
+            Element::JsonObject(o) => o.entry(self.to_owned()).or_default(),
+            _ => panic!("cannot index into a non-object with {self:?}"),
+        }
+    }
+}
+
+impl ElementIndex for String {
+    fn index_into<'a>(&self, element: &'a Element) -> Option<&'a Element> {
+        self.as_str().index_into(element)
+    }
+
+    fn index_into_mut<'a>(&self, element: &'a mut Element) -> Option<&'a mut Element> {
+        self.as_str().index_into_mut(element)
+    }
+
+    fn index_or_insert<'a>(&self, element: &'a mut Element) -> &'a mut Element {
+        self.as_str().index_or_insert(element)
+    }
+}
+
+impl<T: ElementIndex + ?Sized> ElementIndex for &T {
+    fn index_into<'a>(&self, element: &'a Element) -> Option<&'a Element> {
+        (**self).index_into(element)
+    }
+
+    fn index_into_mut<'a>(&self, element: &'a mut Element) -> Option<&'a mut Element> {
+        (**self).index_into_mut(element)
+    }
+
+    fn index_or_insert<'a>(&self, element: &'a mut Element) -> &'a mut Element {
+        (**self).index_or_insert(element)
+    }
+}
+
+/// Returns null for missing keys and indices, and for values which aren't
+/// objects or arrays.
+impl<I: ElementIndex> Index<I> for Element {
+    type Output = Element;
+
+    fn index(&self, index: I) -> &Element {
+        index.index_into(self).unwrap_or(&NULL)
+    }
+}
+
+/// Inserts null for missing keys, turning null into an empty object first.
+/// Panics for indices out of bounds and values which can't be indexed.
+impl<I: ElementIndex> IndexMut<I> for Element {
+    fn index_mut(&mut self, index: I) -> &mut Element {
+        index.index_or_insert(self)
+    }
+}
+
+impl From<i64> for Element {
+    fn from(i: i64) -> Self {
+        Element::JsonInt(i)
+    }
+}
+
+/// Non-finite values become null
+impl From<f64> for Element {
+    fn from(f: f64) -> Self {
+        if f.is_finite() {
+            Element::JsonFloat(f)
+        } else {
+            Element::JsonNull
+        }
+    }
+}
+
+impl From<bool> for Element {
+    fn from(b: bool) -> Self {
+        Element::JsonBool(b)
+    }
+}
+
+impl From<String> for Element {
+    fn from(s: String) -> Self {
+        Element::JsonString(s)
+    }
+}
+
+impl From<&str> for Element {
+    fn from(s: &str) -> Self {
+        Element::JsonString(s.to_owned())
+    }
+}
+
+impl<T: Into<Element>> From<Vec<T>> for Element {
+    fn from(l: Vec<T>) -> Self {
+        Element::JsonList(l.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<T: Into<Element>> From<HashMap<String, T>> for Element {
+    fn from(o: HashMap<String, T>) -> Self {
+        Element::JsonObject(o.into_iter().map(|(k, v)| (k, v.into())).collect())
+    }
+}
+
+/// `None` becomes null
+impl<T: Into<Element>> From<Option<T>> for Element {
+    fn from(o: Option<T>) -> Self {
+        o.map_or(Element::JsonNull, Into::into)
+    }
+}
+
+/// Collects into an array
+impl<T: Into<Element>> FromIterator<T> for Element {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Element::JsonList(iter.into_iter().map(Into::into).collect())
+    }
+}
+
+/// Collects key value pairs into an object
+impl<K: Into<String>, V: Into<Element>> FromIterator<(K, V)> for Element {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        Element::JsonObject(
+            iter.into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+        )
+    }
+}
+
+macro_rules! impl_try_from {
+    ($($t:ty),*) => {
+        $(
+            impl TryFrom<Element> for $t {
+                type Error = FromElementError;
+
+                fn try_from(e: Element) -> Result<Self, Self::Error> {
+                    <$t>::from_element(e)
+                }
+            }
+
+            impl TryFrom<&Element> for $t {
+                type Error = FromElementError;
+
+                fn try_from(e: &Element) -> Result<Self, Self::Error> {
+                    <$t>::from_element_ref(e)
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from!(i64, f64, bool, String);
+
+/// Borrows the string without copying it
+impl<'a> TryFrom<&'a Element> for &'a str {
+    type Error = FromElementError;
+
+    fn try_from(e: &'a Element) -> Result<Self, Self::Error> {
+        e.as_str()
+            .ok_or_else(|| FromElementError::invalid_type("a string", e))
+    }
+}
@@ -0,0 +1,414 @@
+use std::{borrow::Cow, cmp::Ordering, collections::HashMap};
+
+use crate::{
+    marshal::{string::marshal_str, MarshalOptions},
+    Element, Marshalable, Unmarshalable,
+};
+
+use super::{
+    interpreter::{compare_numbers, elements, eval, null, Value},
+    parser::Ast,
+    JmesError,
+};
+
+/// The built-in functions from the JMESPath specification.
+#[derive(Debug, Clone, Copy)]
+pub(super) enum Function {
+    Abs,
+    Avg,
+    Ceil,
+    Contains,
+    EndsWith,
+    Floor,
+    Join,
+    Keys,
+    Length,
+    Map,
+    Max,
+    MaxBy,
+    Merge,
+    Min,
+    MinBy,
+    NotNull,
+    Reverse,
+    Sort,
+    SortBy,
+    StartsWith,
+    Sum,
+    ToArray,
+    ToNumber,
+    ToString,
+    Type,
+    Values,
+}
+
+impl Function {
+    /// Looks up a function, checking the number of arguments
+    pub fn new(name: &str, args: usize) -> Result<Function, JmesError> {
+        // The number of arguments, and whether more are allowed
+        let (function, arity, variadic) = match name {
+            "abs" => (Function::Abs, 1, false),
+            "avg" => (Function::Avg, 1, false),
+            "ceil" => (Function::Ceil, 1, false),
+            "contains" => (Function::Contains, 2, false),
+            "ends_with" => (Function::EndsWith, 2, false),
+            "floor" => (Function::Floor, 1, false),
+            "join" => (Function::Join, 2, false),
+            "keys" => (Function::Keys, 1, false),
+            "length" => (Function::Length, 1, false),
+            "map" => (Function::Map, 2, false),
+            "max" => (Function::Max, 1, false),
+            "max_by" => (Function::MaxBy, 2, false),
+            "merge" => (Function::Merge, 1, true),
+            "min" => (Function::Min, 1, false),
+            "min_by" => (Function::MinBy, 2, false),
+            "not_null" => (Function::NotNull, 1, true),
+            "reverse" => (Function::Reverse, 1, false),
+            "sort" => (Function::Sort, 1, false),
+            "sort_by" => (Function::SortBy, 2, false),
+            "starts_with" => (Function::StartsWith, 2, false),
+            "sum" => (Function::Sum, 1, false),
+            "to_array" => (Function::ToArray, 1, false),
+            "to_number" => (Function::ToNumber, 1, false),
+            "to_string" => (Function::ToString, 1, false),
+            "type" => (Function::Type, 1, false),
+            "values" => (Function::Values, 1, false),
+            _ => {
+                return Err(JmesError::UnknownFunction {
+                    name: name.to_owned(),
+                })
+            }
+        };
+        if args < arity || (args > arity && !variadic) {
+            return Err(JmesError::InvalidArity {
+                name: name.to_owned(),
+            });
+        }
+        Ok(function)
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Function::Abs => "abs",
+            Function::Avg => "avg",
+            Function::Ceil => "ceil",
+            Function::Contains => "contains",
+            Function::EndsWith => "ends_with",
+            Function::Floor => "floor",
+            Function::Join => "join",
+            Function::Keys => "keys",
+            Function::Length => "length",
+            Function::Map => "map",
+            Function::Max => "max",
+            Function::MaxBy => "max_by",
+            Function::Merge => "merge",
+            Function::Min => "min",
+            Function::MinBy => "min_by",
+            Function::NotNull => "not_null",
+            Function::Reverse => "reverse",
+            Function::Sort => "sort",
+            Function::SortBy => "sort_by",
+            Function::StartsWith => "starts_with",
+            Function::Sum => "sum",
+            Function::ToArray => "to_array",
+            Function::ToNumber => "to_number",
+            Function::ToString => "to_string",
+            Function::Type => "type",
+            Function::Values => "values",
+        }
+    }
+
+    fn invalid_type(self) -> JmesError {
+        JmesError::InvalidType {
+            name: self.name().to_owned(),
+        }
+    }
+}
+
+fn owned<'a>(e: Element) -> Value<'a> {
+    Cow::Owned(e)
+}
+
+fn as_f64(e: &Element) -> Option<f64> {
+    match e {
+        Element::JsonInt(i) => Some(*i as f64),
+        Element::JsonFloat(f) => Some(*f),
+        _ => None,
+    }
+}
+
+pub(super) fn call<'a>(
+    function: Function,
+    args: &[Ast],
+    current: Value<'a>,
+) -> Result<Value<'a>, JmesError> {
+    let invalid = || function.invalid_type();
+    // Expression arguments are passed unevaluated
+    let expref = |i: usize| match &args[i] {
+        Ast::ExpRef(ast) => Ok(&**ast),
+        _ => Err(invalid()),
+    };
+    let arg = |i: usize| eval(&args[i], current.clone());
+    let array = |i: usize| elements(arg(i)?).ok_or_else(invalid);
+    let string = |i: usize| match arg(i)?.into_owned() {
+        Element::JsonString(s) => Ok(s),
+        _ => Err(invalid()),
+    };
+
+    Ok(match function {
+        Function::Abs => match *arg(0)? {
+            Element::JsonInt(i) => owned(match i.checked_abs() {
+                Some(abs) => Element::JsonInt(abs),
+                None => Element::JsonFloat(-(i as f64)),
+            }),
+            Element::JsonFloat(f) => owned(Element::JsonFloat(f.abs())),
+            _ => return Err(invalid()),
+        },
+        Function::Avg => {
+            let numbers = numbers(function, array(0)?)?;
+            match numbers.len() {
+                0 => null(),
+                len => owned(Element::JsonFloat(numbers.iter().sum::<f64>() / len as f64)),
+            }
+        }
+        Function::Ceil | Function::Floor => match *arg(0)? {
+            Element::JsonInt(i) => owned(Element::JsonInt(i)),
+            Element::JsonFloat(f) => owned(Element::JsonFloat(match function {
+                Function::Ceil => f.ceil(),
+                _ => f.floor(),
+            })),
+            _ => return Err(invalid()),
+        },
+        Function::Contains => {
+            let search = arg(1)?;
+            match &*arg(0)? {
+                Element::JsonList(l) => {
+                    owned(Element::JsonBool(l.iter().any(|e| e.eq_numeric(&search))))
+                }
+                Element::JsonString(s) => owned(Element::JsonBool(
+                    matches!(&*search, Element::JsonString(search) if s.contains(search.as_str())),
+                )),
+                _ => return Err(invalid()),
+            }
+        }
+        Function::EndsWith => owned(Element::JsonBool(string(0)?.ends_with(&string(1)?))),
+        Function::StartsWith => owned(Element::JsonBool(string(0)?.starts_with(&string(1)?))),
+        Function::Join => {
+            let glue = string(0)?;
+            let strings = array(1)?
+                .into_iter()
+                .map(|e| match e.into_owned() {
+                    Element::JsonString(s) => Ok(s),
+                    _ => Err(invalid()),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            owned(Element::JsonString(strings.join(&glue)))
+        }
+        Function::Keys => match arg(0)?.into_owned() {
+            Element::JsonObject(o) => owned(Element::JsonList(
+                o.into_keys().map(Element::JsonString).collect(),
+            )),
+            _ => return Err(invalid()),
+        },
+        Function::Values => match arg(0)?.into_owned() {
+            Element::JsonObject(o) => owned(Element::JsonList(o.into_values().collect())),
+            _ => return Err(invalid()),
+        },
+        Function::Length => {
+            let len = match &*arg(0)? {
+                Element::JsonString(s) => s.chars().count(),
+                Element::JsonList(l) => l.len(),
+                Element::JsonObject(o) => o.len(),
+                _ => return Err(invalid()),
+            };
+            owned(Element::JsonInt(len as i64))
+        }
+        Function::Map => {
+            let ast = expref(0)?;
+            let mut mapped = Vec::new();
+            for item in array(1)? {
+                mapped.push(eval(ast, item)?.into_owned());
+            }
+            owned(Element::JsonList(mapped))
+        }
+        Function::Max | Function::Min => {
+            let items = array(0)?;
+            check_sortable(function, &items)?;
+            let best =
+                items
+                    .into_iter()
+                    .reduce(|best, item| match (compare(&item, &best), function) {
+                        (Ordering::Greater, Function::Max) | (Ordering::Less, Function::Min) => {
+                            item
+                        }
+                        _ => best,
+                    });
+            best.unwrap_or_else(null)
+        }
+        Function::MaxBy | Function::MinBy | Function::SortBy => {
+            let items = array(0)?;
+            let ast = expref(1)?;
+            let mut keyed = Vec::with_capacity(items.len());
+            for item in items {
+                keyed.push((eval(ast, item.clone())?, item));
+            }
+            let keys = keyed.iter().map(|(key, _)| key.clone()).collect::<Vec<_>>();
+            check_sortable(function, &keys)?;
+            match function {
+                Function::SortBy => {
+                    keyed.sort_by(|(a, _), (b, _)| compare(a, b));
+                    owned(Element::JsonList(
+                        keyed
+                            .into_iter()
+                            .map(|(_, item)| item.into_owned())
+                            .collect(),
+                    ))
+                }
+                _ => keyed
+                    .into_iter()
+                    .reduce(|best, item| match (compare(&item.0, &best.0), function) {
+                        (Ordering::Greater, Function::MaxBy)
+                        | (Ordering::Less, Function::MinBy) => item,
+                        _ => best,
+                    })
+                    .map_or_else(null, |(_, item)| item),
+            }
+        }
+        Function::Merge => {
+            let mut merged = HashMap::new();
+            for i in 0..args.len() {
+                match arg(i)?.into_owned() {
+                    Element::JsonObject(o) => merged.extend(o),
+                    _ => return Err(invalid()),
+                }
+            }
+            owned(Element::JsonObject(merged))
+        }
+        Function::NotNull => {
+            for i in 0..args.len() {
+                let value = arg(i)?;
+                if *value != Element::JsonNull {
+                    return Ok(value);
+                }
+            }
+            null()
+        }
+        Function::Reverse => match arg(0)?.into_owned() {
+            Element::JsonString(s) => owned(Element::JsonString(s.chars().rev().collect())),
+            Element::JsonList(mut l) => {
+                l.reverse();
+                owned(Element::JsonList(l))
+            }
+            _ => return Err(invalid()),
+        },
+        Function::Sort => {
+            let mut items = array(0)?;
+            check_sortable(function, &items)?;
+            items.sort_by(|a, b| compare(a, b));
+            owned(Element::JsonList(
+                items.into_iter().map(Cow::into_owned).collect(),
+            ))
+        }
+        Function::Sum => {
+            let items = array(0)?;
+            let ints = items
+                .iter()
+                .map(|e| match **e {
+                    Element::JsonInt(i) => Some(i),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>();
+            // Sums of ints which overflow are summed as floats instead
+            match ints.and_then(|ints| ints.iter().try_fold(0i64, |sum, &i| sum.checked_add(i))) {
+                Some(sum) => owned(Element::JsonInt(sum)),
+                None => owned(Element::JsonFloat(numbers(function, items)?.iter().sum())),
+            }
+        }
+        Function::ToArray => match arg(0)?.into_owned() {
+            list @ Element::JsonList(_) => owned(list),
+            other => owned(Element::JsonList(vec![other])),
+        },
+        Function::ToNumber => match arg(0)?.into_owned() {
+            number @ (Element::JsonInt(_) | Element::JsonFloat(_)) => owned(number),
+            Element::JsonString(s) => match Element::unmarshal_json(s.chars()) {
+                Ok(number @ (Element::JsonInt(_) | Element::JsonFloat(_))) => owned(number),
+                _ => null(),
+            },
+            _ => null(),
+        },
+        Function::ToString => match arg(0)?.into_owned() {
+            string @ Element::JsonString(_) => owned(string),
+            other => {
+                let mut s = String::new();
+                to_compact_json(&other, &mut s);
+                owned(Element::JsonString(s))
+            }
+        },
+        Function::Type => owned(Element::JsonString(
+            match *arg(0)? {
+                Element::JsonObject(_) => "object",
+                Element::JsonList(_) => "array",
+                Element::JsonString(_) => "string",
+                Element::JsonInt(_) | Element::JsonFloat(_) => "number",
+                Element::JsonBool(_) => "boolean",
+                Element::JsonNull => "null",
+            }
+            .to_owned(),
+        )),
+    })
+}
+
+fn numbers(function: Function, items: Vec<Value>) -> Result<Vec<f64>, JmesError> {
+    items
+        .iter()
+        .map(|e| as_f64(e).ok_or_else(|| function.invalid_type()))
+        .collect()
+}
+
+/// Checks that the values are all numbers or all strings
+fn check_sortable(function: Function, items: &[Value]) -> Result<(), JmesError> {
+    let all_numbers = items.iter().all(|e| as_f64(e).is_some());
+    let all_strings = items.iter().all(|e| matches!(**e, Element::JsonString(_)));
+    if all_numbers || all_strings {
+        Ok(())
+    } else {
+        Err(function.invalid_type())
+    }
+}
+
+/// Orders two numbers or two strings
+fn compare(a: &Element, b: &Element) -> Ordering {
+    match (a, b) {
+        (Element::JsonString(a), Element::JsonString(b)) => a.cmp(b),
+        _ => compare_numbers(a, b).unwrap_or(Ordering::Equal),
+    }
+}
+
+/// Marshals without spaces between tokens, for `to_string`
+fn to_compact_json(e: &Element, s: &mut String) {
+    match e {
+        Element::JsonObject(o) => {
+            s.push('{');
+            for (i, (k, v)) in o.iter().enumerate() {
+                if i > 0 {
+                    s.push(',');
+                }
+                marshal_str(k, s, &MarshalOptions::default());
+                s.push(':');
+                to_compact_json(v, s);
+            }
+            s.push('}');
+        }
+        Element::JsonList(l) => {
+            s.push('[');
+            for (i, v) in l.iter().enumerate() {
+                if i > 0 {
+                    s.push(',');
+                }
+                to_compact_json(v, s);
+            }
+            s.push(']');
+        }
+        _ => e.marshal_json_into(s),
+    }
+}
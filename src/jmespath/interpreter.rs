@@ -0,0 +1,234 @@
+use std::{borrow::Cow, cmp::Ordering, collections::HashMap};
+
+use crate::Element;
+
+use super::{functions, lexer::Comparator, parser::Ast, JmesError};
+
+/// A value being searched, borrowed from the input where possible
+pub(super) type Value<'a> = Cow<'a, Element>;
+
+pub(super) fn null<'a>() -> Value<'a> {
+    Cow::Owned(Element::JsonNull)
+}
+
+/// Returns whether a value counts as true in conditions. Empty strings,
+/// arrays and objects are false.
+pub(super) fn is_truthy(value: &Element) -> bool {
+    match value {
+        Element::JsonObject(o) => !o.is_empty(),
+        Element::JsonList(l) => !l.is_empty(),
+        Element::JsonString(s) => !s.is_empty(),
+        Element::JsonBool(b) => *b,
+        Element::JsonNull => false,
+        Element::JsonInt(_) | Element::JsonFloat(_) => true,
+    }
+}
+
+/// Splits an array into its elements, or returns `None` for other values
+pub(super) fn elements(value: Value) -> Option<Vec<Value>> {
+    match value {
+        Cow::Borrowed(Element::JsonList(l)) => Some(l.iter().map(Cow::Borrowed).collect()),
+        Cow::Owned(Element::JsonList(l)) => Some(l.into_iter().map(Cow::Owned).collect()),
+        _ => None,
+    }
+}
+
+fn object_values(value: Value) -> Option<Vec<Value>> {
+    match value {
+        Cow::Borrowed(Element::JsonObject(o)) => Some(o.values().map(Cow::Borrowed).collect()),
+        Cow::Owned(Element::JsonObject(o)) => Some(o.into_values().map(Cow::Owned).collect()),
+        _ => None,
+    }
+}
+
+fn field<'a>(value: Value<'a>, name: &str) -> Value<'a> {
+    match value {
+        Cow::Borrowed(Element::JsonObject(o)) => o.get(name).map_or_else(null, Cow::Borrowed),
+        Cow::Owned(Element::JsonObject(mut o)) => o.remove(name).map_or_else(null, Cow::Owned),
+        _ => null(),
+    }
+}
+
+fn list(values: Vec<Value>) -> Value<'static> {
+    Cow::Owned(Element::JsonList(
+        values.into_iter().map(Cow::into_owned).collect(),
+    ))
+}
+
+pub(super) fn eval<'a>(ast: &Ast, value: Value<'a>) -> Result<Value<'a>, JmesError> {
+    Ok(match ast {
+        Ast::Identity => value,
+        Ast::Field(name) => field(value, name),
+        Ast::Subexpression(left, right) => eval(right, eval(left, value)?)?,
+        Ast::Index(i) => match elements(value) {
+            Some(mut items) => {
+                let i = if *i < 0 { items.len() as i64 + i } else { *i };
+                match usize::try_from(i) {
+                    Ok(i) if i < items.len() => items.swap_remove(i),
+                    _ => null(),
+                }
+            }
+            None => null(),
+        },
+        Ast::Slice(start, end, step) => match elements(value) {
+            Some(mut items) => {
+                let indices = slice_indices(items.len() as i64, *start, *end, step.unwrap_or(1));
+                let mut taken = Vec::with_capacity(indices.len());
+                for i in indices {
+                    taken.push(std::mem::replace(&mut items[i], null()));
+                }
+                list(taken)
+            }
+            None => null(),
+        },
+        Ast::Projection(left, right) => match elements(eval(left, value)?) {
+            Some(items) => project(items, right)?,
+            None => null(),
+        },
+        Ast::ValueProjection(left, right) => match object_values(eval(left, value)?) {
+            Some(items) => project(items, right)?,
+            None => null(),
+        },
+        Ast::FilterProjection(left, right, condition) => match elements(eval(left, value)?) {
+            Some(items) => {
+                let mut kept = Vec::new();
+                for item in items {
+                    let passed = eval(condition, item.clone())?;
+                    if is_truthy(&passed) {
+                        kept.push(item);
+                    }
+                }
+                project(kept, right)?
+            }
+            None => null(),
+        },
+        Ast::Flatten(inner) => match elements(eval(inner, value)?) {
+            Some(items) => {
+                let mut flat = Vec::new();
+                for item in items {
+                    if matches!(*item, Element::JsonList(_)) {
+                        flat.extend(elements(item).unwrap_or_default());
+                    } else {
+                        flat.push(item);
+                    }
+                }
+                list(flat)
+            }
+            None => null(),
+        },
+        _ if matches!(ast, Ast::MultiSelectList(_) | Ast::MultiSelectHash(_))
+            && *value == Element::JsonNull =>
+        {
+            null()
+        }
+        Ast::MultiSelectList(items) => {
+            let mut values = Vec::with_capacity(items.len());
+            for item in items {
+                values.push(eval(item, value.clone())?);
+            }
+            list(values)
+        }
+        Ast::MultiSelectHash(items) => {
+            let mut object = HashMap::with_capacity(items.len());
+            for (key, item) in items {
+                object.insert(key.clone(), eval(item, value.clone())?.into_owned());
+            }
+            Cow::Owned(Element::JsonObject(object))
+        }
+        Ast::Literal(l) => Cow::Owned(l.clone()),
+        Ast::Or(left, right) => {
+            let left = eval(left, value.clone())?;
+            if is_truthy(&left) {
+                left
+            } else {
+                eval(right, value)?
+            }
+        }
+        Ast::And(left, right) => {
+            let left = eval(left, value.clone())?;
+            if is_truthy(&left) {
+                eval(right, value)?
+            } else {
+                left
+            }
+        }
+        Ast::Not(inner) => {
+            let inner = eval(inner, value)?;
+            Cow::Owned(Element::JsonBool(!is_truthy(&inner)))
+        }
+        Ast::Compare(comparator, left, right) => {
+            let left = eval(left, value.clone())?;
+            let right = eval(right, value)?;
+            compare(*comparator, &left, &right)
+                .map_or_else(null, |b| Cow::Owned(Element::JsonBool(b)))
+        }
+        Ast::Pipe(left, right) => eval(right, eval(left, value)?)?,
+        Ast::Function(function, args) => functions::call(*function, args, value)?,
+        Ast::ExpRef(_) => null(),
+    })
+}
+
+/// Applies the right side of a projection to each item, dropping nulls
+fn project<'a>(items: Vec<Value<'a>>, right: &Ast) -> Result<Value<'a>, JmesError> {
+    let mut projected = Vec::with_capacity(items.len());
+    for item in items {
+        let value = eval(right, item)?;
+        if *value != Element::JsonNull {
+            projected.push(value);
+        }
+    }
+    Ok(list(projected))
+}
+
+/// Compares two values, returning `None` when ordering values which aren't
+/// both numbers
+fn compare(comparator: Comparator, left: &Element, right: &Element) -> Option<bool> {
+    let ordering = match comparator {
+        Comparator::Eq => return Some(left.eq_numeric(right)),
+        Comparator::Ne => return Some(!left.eq_numeric(right)),
+        _ => compare_numbers(left, right)?,
+    };
+    Some(match comparator {
+        Comparator::Lt => ordering == Ordering::Less,
+        Comparator::Le => ordering != Ordering::Greater,
+        Comparator::Gt => ordering == Ordering::Greater,
+        _ => ordering != Ordering::Less,
+    })
+}
+
+pub(super) fn compare_numbers(left: &Element, right: &Element) -> Option<Ordering> {
+    match (left, right) {
+        (Element::JsonInt(l), Element::JsonInt(r)) => Some(l.cmp(r)),
+        (Element::JsonInt(l), Element::JsonFloat(r)) => (*l as f64).partial_cmp(r),
+        (Element::JsonFloat(l), Element::JsonInt(r)) => l.partial_cmp(&(*r as f64)),
+        (Element::JsonFloat(l), Element::JsonFloat(r)) => l.partial_cmp(r),
+        _ => None,
+    }
+}
+
+/// Returns the indices selected by a slice, like slicing in Python
+fn slice_indices(len: i64, start: Option<i64>, end: Option<i64>, step: i64) -> Vec<usize> {
+    let adjust = |i: i64| {
+        if i < 0 {
+            (len + i).max(if step < 0 { -1 } else { 0 })
+        } else {
+            i.min(if step < 0 { len - 1 } else { len })
+        }
+    };
+    let (start, end) = if step < 0 {
+        (start.map_or(len - 1, adjust), end.map_or(-1, adjust))
+    } else {
+        (start.map_or(0, adjust), end.map_or(len, adjust))
+    };
+    let mut indices = Vec::new();
+    let mut i = start;
+    while (step > 0 && i < end) || (step < 0 && i > end) {
+        indices.push(i as usize);
+        // A step past the end of the range may overflow
+        match i.checked_add(step) {
+            Some(next) => i = next,
+            None => break,
+        }
+    }
+    indices
+}
@@ -0,0 +1,169 @@
+use crate::{Element, Unmarshalable};
+
+use super::JmesError;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Token {
+    Identifier(String),
+    QuotedIdentifier(String),
+    Number(i64),
+    Literal(Element),
+    Dot,
+    Star,
+    /// "[]"
+    Flatten,
+    /// "[?"
+    Filter,
+    LBracket,
+    RBracket,
+    LBrace,
+    RBrace,
+    LParen,
+    RParen,
+    Comma,
+    Colon,
+    Pipe,
+    Or,
+    And,
+    Not,
+    Compare(Comparator),
+    At,
+    Ampersand,
+    Eof,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum Comparator {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Token {
+    /// The binding power of the token when it follows an expression
+    pub fn binding_power(&self) -> u8 {
+        match self {
+            Token::Pipe => 1,
+            Token::Or => 2,
+            Token::And => 3,
+            Token::Compare(_) => 5,
+            Token::Flatten => 9,
+            Token::Star => 20,
+            Token::Filter => 21,
+            Token::Dot => 40,
+            Token::Not => 45,
+            Token::LBrace => 50,
+            Token::LBracket => 55,
+            Token::LParen => 60,
+            _ => 0,
+        }
+    }
+}
+
+/// Splits an expression into tokens, each with its byte offset
+pub(super) fn tokenize(input: &str) -> Result<Vec<(Token, usize)>, JmesError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+    while let Some((offset, c)) = chars.next() {
+        let mut next_is = |expected: char| chars.next_if(|&(_, c)| c == expected).is_some();
+        let token = match c {
+            ' ' | '\t' | '\n' | '\r' => continue,
+            '.' => Token::Dot,
+            '*' => Token::Star,
+            ']' => Token::RBracket,
+            '{' => Token::LBrace,
+            '}' => Token::RBrace,
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            ',' => Token::Comma,
+            ':' => Token::Colon,
+            '@' => Token::At,
+            '[' if next_is(']') => Token::Flatten,
+            '[' if next_is('?') => Token::Filter,
+            '[' => Token::LBracket,
+            '|' if next_is('|') => Token::Or,
+            '|' => Token::Pipe,
+            '&' if next_is('&') => Token::And,
+            '&' => Token::Ampersand,
+            '!' if next_is('=') => Token::Compare(Comparator::Ne),
+            '!' => Token::Not,
+            '=' if next_is('=') => Token::Compare(Comparator::Eq),
+            '<' if next_is('=') => Token::Compare(Comparator::Le),
+            '<' => Token::Compare(Comparator::Lt),
+            '>' if next_is('=') => Token::Compare(Comparator::Ge),
+            '>' => Token::Compare(Comparator::Gt),
+            'a'..='z' | 'A'..='Z' | '_' => {
+                let mut end = offset + 1;
+                while let Some((i, c)) =
+                    chars.next_if(|&(_, c)| c.is_ascii_alphanumeric() || c == '_')
+                {
+                    end = i + c.len_utf8();
+                }
+                Token::Identifier(input[offset..end].to_owned())
+            }
+            '-' | '0'..='9' => {
+                let mut end = offset + 1;
+                while let Some((i, _)) = chars.next_if(|&(_, c)| c.is_ascii_digit()) {
+                    end = i + 1;
+                }
+                input[offset..end]
+                    .parse()
+                    .map(Token::Number)
+                    .map_err(|_| JmesError::Syntax { offset })?
+            }
+            '"' => {
+                let end = delimited(&mut chars, '"').ok_or(JmesError::Syntax { offset })?;
+                String::unmarshal_json(input[offset..end].chars())
+                    .map(Token::QuotedIdentifier)
+                    .map_err(|_| JmesError::Syntax { offset })?
+            }
+            '\'' => {
+                let end = delimited(&mut chars, '\'').ok_or(JmesError::Syntax { offset })?;
+                Token::Literal(Element::JsonString(raw_string(&input[offset + 1..end - 1])))
+            }
+            '`' => {
+                let end = delimited(&mut chars, '`').ok_or(JmesError::Syntax { offset })?;
+                let json = input[offset + 1..end - 1].replace("\\`", "`");
+                Element::unmarshal_json(json.chars())
+                    .map(Token::Literal)
+                    .map_err(|_| JmesError::Syntax { offset })?
+            }
+            _ => return Err(JmesError::Syntax { offset }),
+        };
+        tokens.push((token, offset));
+    }
+    tokens.push((Token::Eof, input.len()));
+    Ok(tokens)
+}
+
+/// Skips to after the closing delimiter, returning its end offset. A
+/// backslash escapes the next character.
+fn delimited(chars: &mut impl Iterator<Item = (usize, char)>, delimiter: char) -> Option<usize> {
+    while let Some((i, c)) = chars.next() {
+        if c == delimiter {
+            return Some(i + 1);
+        } else if c == '\\' {
+            chars.next()?;
+        }
+    }
+    None
+}
+
+/// Unescapes a raw string, where only quotes and backslashes are escaped
+fn raw_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some(escaped @ ('\'' | '\\'))) => {
+                out.push(escaped);
+                chars.next();
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
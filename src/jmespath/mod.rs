@@ -0,0 +1,48 @@
+mod functions;
+mod interpreter;
+mod lexer;
+mod parser;
+
+use std::borrow::Cow;
+
+use crate::Element;
+
+/// An error from compiling or evaluating a JMESPath expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JmesError {
+    /// The expression is invalid at a byte offset
+    Syntax {
+        offset: usize,
+    },
+    UnknownFunction {
+        name: String,
+    },
+    /// A function was called with the wrong number of arguments
+    InvalidArity {
+        name: String,
+    },
+    /// A function was called with an argument of the wrong type
+    InvalidType {
+        name: String,
+    },
+    /// A slice has a step of zero
+    InvalidValue,
+}
+
+/// A JMESPath expression, compiled once so that it can be evaluated against
+/// many documents.
+#[derive(Debug)]
+pub struct Expression {
+    ast: parser::Ast,
+}
+
+impl Expression {
+    pub fn compile(expression: &str) -> Result<Expression, JmesError> {
+        parser::parse(expression).map(|ast| Expression { ast })
+    }
+
+    /// Evaluates the expression, returning the result as a new element
+    pub fn search(&self, data: &Element) -> Result<Element, JmesError> {
+        interpreter::eval(&self.ast, Cow::Borrowed(data)).map(Cow::into_owned)
+    }
+}
@@ -0,0 +1,314 @@
+use crate::Element;
+
+use super::{
+    functions::Function,
+    lexer::{tokenize, Comparator, Token},
+    JmesError,
+};
+
+#[derive(Debug)]
+pub(super) enum Ast {
+    /// The current node, for '@' and the left of projections
+    Identity,
+    Field(String),
+    Subexpression(Box<Ast>, Box<Ast>),
+    Index(i64),
+    Slice(Option<i64>, Option<i64>, Option<i64>),
+    /// Applies the right side to each element of the array on the left
+    Projection(Box<Ast>, Box<Ast>),
+    /// Applies the right side to each value of the object on the left
+    ValueProjection(Box<Ast>, Box<Ast>),
+    /// Applies the right side to each element which passes the condition
+    FilterProjection(Box<Ast>, Box<Ast>, Box<Ast>),
+    Flatten(Box<Ast>),
+    MultiSelectList(Vec<Ast>),
+    MultiSelectHash(Vec<(String, Ast)>),
+    Literal(Element),
+    Or(Box<Ast>, Box<Ast>),
+    And(Box<Ast>, Box<Ast>),
+    Not(Box<Ast>),
+    Compare(Comparator, Box<Ast>, Box<Ast>),
+    Pipe(Box<Ast>, Box<Ast>),
+    Function(Function, Vec<Ast>),
+    /// An expression passed to a function unevaluated, for '&'
+    ExpRef(Box<Ast>),
+}
+
+/// Tokens with a binding power below this end a projection
+const PROJECTION_STOP: u8 = 10;
+
+pub(super) fn parse(input: &str) -> Result<Ast, JmesError> {
+    let mut p = Parser {
+        tokens: tokenize(input)?,
+        pos: 0,
+    };
+    let ast = p.expression(0)?;
+    match p.peek() {
+        Token::Eof => Ok(ast),
+        _ => Err(p.error()),
+    }
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos].0
+    }
+
+    fn peek_at(&self, n: usize) -> &Token {
+        let i = (self.pos + n).min(self.tokens.len() - 1);
+        &self.tokens[i].0
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.tokens[self.pos].0.clone();
+        if self.pos + 1 < self.tokens.len() {
+            self.pos += 1;
+        }
+        token
+    }
+
+    /// Returns an error for the next token
+    fn error(&self) -> JmesError {
+        JmesError::Syntax {
+            offset: self.tokens[self.pos].1,
+        }
+    }
+
+    /// Returns an error for the token that was just read
+    fn error_before(&self) -> JmesError {
+        JmesError::Syntax {
+            offset: self.tokens[self.pos.saturating_sub(1)].1,
+        }
+    }
+
+    fn expect(&mut self, token: Token) -> Result<(), JmesError> {
+        if *self.peek() == token {
+            self.advance();
+            Ok(())
+        } else {
+            Err(self.error())
+        }
+    }
+
+    fn expression(&mut self, binding_power: u8) -> Result<Ast, JmesError> {
+        let token = self.advance();
+        let mut left = self.prefix(token)?;
+        while binding_power < self.peek().binding_power() {
+            let token = self.advance();
+            left = self.infix(token, left)?;
+        }
+        Ok(left)
+    }
+
+    /// Parses an expression starting with the token
+    fn prefix(&mut self, token: Token) -> Result<Ast, JmesError> {
+        Ok(match token {
+            Token::Literal(l) => Ast::Literal(l),
+            Token::Identifier(name) => Ast::Field(name),
+            Token::QuotedIdentifier(name) => {
+                // Function names can't be quoted
+                if *self.peek() == Token::LParen {
+                    return Err(self.error());
+                }
+                Ast::Field(name)
+            }
+            Token::Star => Ast::ValueProjection(
+                Box::new(Ast::Identity),
+                Box::new(self.projection_rhs(Token::Star.binding_power())?),
+            ),
+            Token::Filter => self.filter(Ast::Identity)?,
+            Token::LBrace => self.multi_select_hash()?,
+            Token::LParen => {
+                let ast = self.expression(0)?;
+                self.expect(Token::RParen)?;
+                ast
+            }
+            Token::Flatten => Ast::Projection(
+                Box::new(Ast::Flatten(Box::new(Ast::Identity))),
+                Box::new(self.projection_rhs(Token::Flatten.binding_power())?),
+            ),
+            Token::Not => Ast::Not(Box::new(self.expression(Token::Not.binding_power())?)),
+            Token::LBracket => match (self.peek(), self.peek_at(1)) {
+                (Token::Number(_) | Token::Colon, _) => self.index(Ast::Identity)?,
+                (Token::Star, Token::RBracket) => {
+                    self.advance();
+                    self.advance();
+                    Ast::Projection(
+                        Box::new(Ast::Identity),
+                        Box::new(self.projection_rhs(Token::Star.binding_power())?),
+                    )
+                }
+                _ => self.multi_select_list()?,
+            },
+            Token::At => Ast::Identity,
+            Token::Ampersand => Ast::ExpRef(Box::new(self.expression(0)?)),
+            _ => return Err(self.error_before()),
+        })
+    }
+
+    /// Parses an expression continuing from the left side
+    fn infix(&mut self, token: Token, left: Ast) -> Result<Ast, JmesError> {
+        let binding_power = token.binding_power();
+        let left = Box::new(left);
+        Ok(match token {
+            Token::Dot => {
+                if *self.peek() == Token::Star {
+                    self.advance();
+                    Ast::ValueProjection(left, Box::new(self.projection_rhs(binding_power)?))
+                } else {
+                    Ast::Subexpression(left, Box::new(self.dot_rhs(binding_power)?))
+                }
+            }
+            Token::Pipe => Ast::Pipe(left, Box::new(self.expression(binding_power)?)),
+            Token::Or => Ast::Or(left, Box::new(self.expression(binding_power)?)),
+            Token::And => Ast::And(left, Box::new(self.expression(binding_power)?)),
+            Token::Compare(c) => Ast::Compare(c, left, Box::new(self.expression(binding_power)?)),
+            Token::LParen => {
+                let Ast::Field(name) = *left else {
+                    return Err(self.error_before());
+                };
+                let mut args = Vec::new();
+                while *self.peek() != Token::RParen {
+                    args.push(self.expression(0)?);
+                    if *self.peek() == Token::Comma {
+                        self.advance();
+                        if *self.peek() == Token::RParen {
+                            return Err(self.error());
+                        }
+                    } else if *self.peek() != Token::RParen {
+                        return Err(self.error());
+                    }
+                }
+                self.advance();
+                let function = Function::new(&name, args.len())?;
+                Ast::Function(function, args)
+            }
+            Token::Filter => self.filter(*left)?,
+            Token::Flatten => Ast::Projection(
+                Box::new(Ast::Flatten(left)),
+                Box::new(self.projection_rhs(binding_power)?),
+            ),
+            Token::LBracket => match self.peek() {
+                Token::Number(_) | Token::Colon => self.index(*left)?,
+                _ => {
+                    self.expect(Token::Star)?;
+                    self.expect(Token::RBracket)?;
+                    Ast::Projection(
+                        left,
+                        Box::new(self.projection_rhs(Token::Star.binding_power())?),
+                    )
+                }
+            },
+            _ => return Err(self.error_before()),
+        })
+    }
+
+    /// Parses an index or slice after the opening bracket. Slices are
+    /// projections.
+    fn index(&mut self, left: Ast) -> Result<Ast, JmesError> {
+        if let (Token::Number(i), Token::RBracket) = (self.peek().clone(), self.peek_at(1)) {
+            self.advance();
+            self.advance();
+            return Ok(Ast::Subexpression(Box::new(left), Box::new(Ast::Index(i))));
+        }
+        let mut parts = [None; 3];
+        let mut part = 0;
+        loop {
+            match self.advance() {
+                Token::RBracket => break,
+                Token::Colon if part < 2 => part += 1,
+                Token::Number(i) if parts[part].is_none() => parts[part] = Some(i),
+                _ => return Err(self.error_before()),
+            }
+        }
+        if parts[2] == Some(0) {
+            return Err(JmesError::InvalidValue);
+        }
+        let slice = Ast::Slice(parts[0], parts[1], parts[2]);
+        Ok(Ast::Projection(
+            Box::new(Ast::Subexpression(Box::new(left), Box::new(slice))),
+            Box::new(self.projection_rhs(Token::Star.binding_power())?),
+        ))
+    }
+
+    fn filter(&mut self, left: Ast) -> Result<Ast, JmesError> {
+        let condition = self.expression(0)?;
+        self.expect(Token::RBracket)?;
+        let right = match self.peek() {
+            Token::Flatten => Ast::Identity,
+            _ => self.projection_rhs(Token::Filter.binding_power())?,
+        };
+        Ok(Ast::FilterProjection(
+            Box::new(left),
+            Box::new(right),
+            Box::new(condition),
+        ))
+    }
+
+    /// Parses what a projection applies to each element
+    fn projection_rhs(&mut self, binding_power: u8) -> Result<Ast, JmesError> {
+        match self.peek() {
+            t if t.binding_power() < PROJECTION_STOP => Ok(Ast::Identity),
+            Token::LBracket | Token::Filter => self.expression(binding_power),
+            Token::Dot => {
+                self.advance();
+                self.dot_rhs(binding_power)
+            }
+            _ => Err(self.error()),
+        }
+    }
+
+    /// Parses what follows a dot
+    fn dot_rhs(&mut self, binding_power: u8) -> Result<Ast, JmesError> {
+        match self.peek() {
+            Token::Identifier(_) | Token::QuotedIdentifier(_) | Token::Star => {
+                self.expression(binding_power)
+            }
+            Token::LBracket => {
+                self.advance();
+                self.multi_select_list()
+            }
+            Token::LBrace => {
+                self.advance();
+                self.multi_select_hash()
+            }
+            _ => Err(self.error()),
+        }
+    }
+
+    /// Parses a list after the opening bracket
+    fn multi_select_list(&mut self) -> Result<Ast, JmesError> {
+        let mut items = Vec::new();
+        loop {
+            items.push(self.expression(0)?);
+            match self.advance() {
+                Token::Comma => (),
+                Token::RBracket => return Ok(Ast::MultiSelectList(items)),
+                _ => return Err(self.error_before()),
+            }
+        }
+    }
+
+    /// Parses a hash after the opening brace
+    fn multi_select_hash(&mut self) -> Result<Ast, JmesError> {
+        let mut items = Vec::new();
+        loop {
+            let key = match self.advance() {
+                Token::Identifier(key) | Token::QuotedIdentifier(key) => key,
+                _ => return Err(self.error_before()),
+            };
+            self.expect(Token::Colon)?;
+            items.push((key, self.expression(0)?));
+            match self.advance() {
+                Token::Comma => (),
+                Token::RBrace => return Ok(Ast::MultiSelectHash(items)),
+                _ => return Err(self.error_before()),
+            }
+        }
+    }
+}
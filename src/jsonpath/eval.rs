@@ -0,0 +1,257 @@
+use crate::{unmarshal::Number, Element};
+
+use super::{
+    iregexp::Regex,
+    parser::{CompareOp, Expr, Function, Query, Segment, Selector},
+    PathSegment,
+};
+
+type Node<'a> = (Vec<PathSegment>, &'a Element);
+
+/// Runs a query from the root or current node
+pub(super) fn select<'a>(query: &Query, root: &'a Element, current: &'a Element) -> Vec<Node<'a>> {
+    let start = if query.relative { current } else { root };
+    let mut nodes = vec![(Vec::new(), start)];
+    for segment in &query.segments {
+        let mut selected = Vec::new();
+        for (path, value) in nodes {
+            if segment.descendant {
+                descend(segment, root, path, value, &mut selected);
+            } else {
+                apply(&segment.selectors, root, &path, value, &mut selected);
+            }
+        }
+        nodes = selected;
+    }
+    nodes
+}
+
+/// Applies the selectors to a value and then each of its descendants
+fn descend<'a>(
+    segment: &Segment,
+    root: &'a Element,
+    path: Vec<PathSegment>,
+    value: &'a Element,
+    out: &mut Vec<Node<'a>>,
+) {
+    apply(&segment.selectors, root, &path, value, out);
+    for (child_path, child) in children(&path, value) {
+        descend(segment, root, child_path, child, out);
+    }
+}
+
+fn child_path(path: &[PathSegment], segment: PathSegment) -> Vec<PathSegment> {
+    let mut path = path.to_vec();
+    path.push(segment);
+    path
+}
+
+fn children<'a>(path: &[PathSegment], value: &'a Element) -> Vec<Node<'a>> {
+    match value {
+        Element::JsonObject(o) => o
+            .iter()
+            .map(|(k, v)| (child_path(path, PathSegment::Name(k.clone())), v))
+            .collect(),
+        Element::JsonList(l) => l
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (child_path(path, PathSegment::Index(i)), v))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn apply<'a>(
+    selectors: &[Selector],
+    root: &'a Element,
+    path: &[PathSegment],
+    value: &'a Element,
+    out: &mut Vec<Node<'a>>,
+) {
+    for selector in selectors {
+        match (selector, value) {
+            (Selector::Name(name), Element::JsonObject(o)) => {
+                if let Some(child) = o.get(name) {
+                    out.push((child_path(path, PathSegment::Name(name.clone())), child));
+                }
+            }
+            (Selector::Wildcard, _) => out.extend(children(path, value)),
+            (Selector::Index(i), Element::JsonList(l)) => {
+                let i = if *i < 0 { l.len() as i64 + i } else { *i };
+                if let Some(child) = usize::try_from(i).ok().and_then(|i| l.get(i)) {
+                    out.push((child_path(path, PathSegment::Index(i as usize)), child));
+                }
+            }
+            (Selector::Slice { start, end, step }, Element::JsonList(l)) => {
+                for i in slice_indices(l.len() as i64, *start, *end, step.unwrap_or(1)) {
+                    out.push((child_path(path, PathSegment::Index(i)), &l[i]));
+                }
+            }
+            (Selector::Filter(expr), _) => out.extend(
+                children(path, value)
+                    .into_iter()
+                    .filter(|&(_, child)| test(expr, root, child)),
+            ),
+            _ => (),
+        }
+    }
+}
+
+/// Returns the indices selected by a slice, following RFC 9535
+fn slice_indices(len: i64, start: Option<i64>, end: Option<i64>, step: i64) -> Vec<usize> {
+    let normalize = |i: i64| if i < 0 { len + i } else { i };
+    let mut indices = Vec::new();
+    if step > 0 {
+        let lower = normalize(start.unwrap_or(0)).clamp(0, len);
+        let upper = normalize(end.unwrap_or(len)).clamp(0, len);
+        let mut i = lower;
+        while i < upper {
+            indices.push(i as usize);
+            i += step;
+        }
+    } else if step < 0 {
+        let upper = normalize(start.unwrap_or(len - 1)).clamp(-1, len - 1);
+        let lower = normalize(end.unwrap_or(-len - 1)).clamp(-1, len - 1);
+        let mut i = upper;
+        while lower < i {
+            indices.push(i as usize);
+            i += step;
+        }
+    }
+    indices
+}
+
+/// A value compared in a filter, or `Nothing` for a query which selected
+/// no value
+enum Value<'a> {
+    Nothing,
+    Element(&'a Element),
+    Int(i64),
+}
+
+impl Value<'_> {
+    fn number(&self) -> Option<Number> {
+        match self {
+            Value::Int(i) | Value::Element(Element::JsonInt(i)) => Some(Number::Int(*i)),
+            Value::Element(Element::JsonFloat(f)) => Some(Number::Float(*f)),
+            _ => None,
+        }
+    }
+}
+
+/// Evaluates a logical expression for the current node
+fn test(expr: &Expr, root: &Element, current: &Element) -> bool {
+    match expr {
+        Expr::Or(operands) => operands.iter().any(|e| test(e, root, current)),
+        Expr::And(operands) => operands.iter().all(|e| test(e, root, current)),
+        Expr::Not(e) => !test(e, root, current),
+        Expr::Compare(left, op, right) => compare(
+            &value(left, root, current),
+            *op,
+            &value(right, root, current),
+        ),
+        Expr::Query(q) => !select(q, root, current).is_empty(),
+        Expr::Function(Function::Match | Function::Search, args) => {
+            let Value::Element(Element::JsonString(s)) = value(&args[0], root, current) else {
+                return false;
+            };
+            // Patterns which aren't literals are compiled for each node
+            let compiled;
+            let re = match &args[1] {
+                Expr::Regex(re) => re.as_ref(),
+                arg => match value(arg, root, current) {
+                    Value::Element(Element::JsonString(re)) => {
+                        compiled = Regex::new(re);
+                        compiled.as_ref()
+                    }
+                    _ => return false,
+                },
+            };
+            match (re, expr) {
+                (Some(re), Expr::Function(Function::Match, _)) => re.is_match(s),
+                (Some(re), _) => re.search(s),
+                (None, _) => false,
+            }
+        }
+        Expr::Function(..) | Expr::Literal(_) | Expr::Regex(_) => false,
+    }
+}
+
+/// Evaluates a comparable expression for the current node
+fn value<'a>(expr: &'a Expr, root: &'a Element, current: &'a Element) -> Value<'a> {
+    match expr {
+        Expr::Literal(l) => Value::Element(l),
+        Expr::Query(q) => single(select(q, root, current)),
+        Expr::Function(Function::Length, args) => match value(&args[0], root, current) {
+            Value::Element(Element::JsonString(s)) => Value::Int(s.chars().count() as i64),
+            Value::Element(Element::JsonList(l)) => Value::Int(l.len() as i64),
+            Value::Element(Element::JsonObject(o)) => Value::Int(o.len() as i64),
+            _ => Value::Nothing,
+        },
+        Expr::Function(Function::Count, args) => match &args[0] {
+            Expr::Query(q) => Value::Int(select(q, root, current).len() as i64),
+            _ => Value::Nothing,
+        },
+        Expr::Function(Function::Value, args) => match &args[0] {
+            Expr::Query(q) => single(select(q, root, current)),
+            _ => Value::Nothing,
+        },
+        _ => Value::Nothing,
+    }
+}
+
+fn single<'a>(nodes: Vec<Node<'a>>) -> Value<'a> {
+    match nodes[..] {
+        [(_, value)] => Value::Element(value),
+        _ => Value::Nothing,
+    }
+}
+
+fn compare(left: &Value, op: CompareOp, right: &Value) -> bool {
+    match op {
+        CompareOp::Eq => equal(left, right),
+        CompareOp::Ne => !equal(left, right),
+        CompareOp::Lt => less(left, right),
+        CompareOp::Le => less(left, right) || equal(left, right),
+        CompareOp::Gt => less(right, left),
+        CompareOp::Ge => less(right, left) || equal(left, right),
+    }
+}
+
+fn equal(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Nothing, Value::Nothing) => true,
+        (Value::Element(l), Value::Element(r)) => l.eq_numeric(r),
+        _ => match (left.number(), right.number()) {
+            (Some(l), Some(r)) => numbers_equal(l, r),
+            _ => false,
+        },
+    }
+}
+
+fn less(left: &Value, right: &Value) -> bool {
+    if let (Some(l), Some(r)) = (left.number(), right.number()) {
+        return match (l, r) {
+            (Number::Int(l), Number::Int(r)) => l < r,
+            _ => as_f64(l) < as_f64(r),
+        };
+    }
+    match (left, right) {
+        (Value::Element(Element::JsonString(l)), Value::Element(Element::JsonString(r))) => l < r,
+        _ => false,
+    }
+}
+
+fn as_f64(n: Number) -> f64 {
+    match n {
+        Number::Int(i) => i as f64,
+        Number::Float(f) => f,
+    }
+}
+
+fn numbers_equal(l: Number, r: Number) -> bool {
+    match (l, r) {
+        (Number::Int(l), Number::Int(r)) => l == r,
+        _ => as_f64(l) == as_f64(r),
+    }
+}
@@ -0,0 +1,442 @@
+//! A small engine for I-Regexp (RFC 9485), the regular expressions used by
+//! the `match` and `search` functions. Expressions are compiled to a program
+//! which is run on every possible path at once, so matching takes linear
+//! time without backtracking.
+//!
+//! Unicode categories are approximated with the character properties in
+//! `std`, and only L, Lu, Ll, N, Nd, P, S, Z, Zs, Zl, Zp, C and Cc are
+//! supported. P and S only match ASCII characters.
+
+/// Limits the size of programs, which grows with counted repetitions
+const MAX_PROGRAM: usize = 10_000;
+
+#[derive(Debug)]
+pub(super) struct Regex {
+    program: Vec<Inst>,
+}
+
+#[derive(Debug)]
+enum Inst {
+    Char(CharSet),
+    Split(usize, usize),
+    Jmp(usize),
+    Match,
+}
+
+#[derive(Debug, Clone)]
+enum CharSet {
+    /// Any character except line breaks, for '.'
+    Any,
+    Char(char),
+    Category(Category, bool),
+    Class {
+        negated: bool,
+        items: Vec<ClassItem>,
+    },
+}
+
+#[derive(Debug, Clone)]
+enum ClassItem {
+    Range(char, char),
+    Category(Category, bool),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Category {
+    Letter,
+    Uppercase,
+    Lowercase,
+    Number,
+    Punctuation,
+    Symbol,
+    Separator,
+    SpaceSeparator,
+    LineSeparator,
+    ParagraphSeparator,
+    Control,
+}
+
+impl Category {
+    fn contains(self, c: char) -> bool {
+        match self {
+            Category::Letter => c.is_alphabetic(),
+            Category::Uppercase => c.is_uppercase(),
+            Category::Lowercase => c.is_lowercase(),
+            Category::Number => c.is_numeric(),
+            Category::Punctuation => c.is_ascii_punctuation() && !"$+<=>^`|~".contains(c),
+            Category::Symbol => "$+<=>^`|~".contains(c),
+            Category::Separator => c.is_whitespace() && !c.is_control(),
+            Category::SpaceSeparator => {
+                c.is_whitespace() && !c.is_control() && !matches!(c, '\u{2028}' | '\u{2029}')
+            }
+            Category::LineSeparator => c == '\u{2028}',
+            Category::ParagraphSeparator => c == '\u{2029}',
+            Category::Control => c.is_control(),
+        }
+    }
+}
+
+impl CharSet {
+    fn contains(&self, c: char) -> bool {
+        match self {
+            CharSet::Any => !matches!(c, '\n' | '\r'),
+            CharSet::Char(x) => c == *x,
+            CharSet::Category(cat, negated) => cat.contains(c) != *negated,
+            CharSet::Class { negated, items } => {
+                let found = items.iter().any(|item| match item {
+                    ClassItem::Range(lo, hi) => (*lo..=*hi).contains(&c),
+                    ClassItem::Category(cat, negated) => cat.contains(c) != *negated,
+                });
+                found != *negated
+            }
+        }
+    }
+}
+
+enum Ast {
+    Set(CharSet),
+    Concat(Vec<Ast>),
+    Alt(Vec<Ast>),
+    Repeat(Box<Ast>, u32, Option<u32>),
+}
+
+impl Regex {
+    /// Compiles an expression, returning `None` if it's invalid or uses an
+    /// unsupported category
+    pub fn new(pattern: &str) -> Option<Regex> {
+        let mut p = Parser {
+            chars: pattern.chars().collect(),
+            pos: 0,
+        };
+        let ast = p.alt()?;
+        if p.pos != p.chars.len() {
+            return None;
+        }
+        let mut program = Vec::new();
+        emit(&ast, &mut program)?;
+        program.push(Inst::Match);
+        Some(Regex { program })
+    }
+
+    /// Returns whether the whole string matches
+    pub fn is_match(&self, s: &str) -> bool {
+        self.run(s, true)
+    }
+
+    /// Returns whether any substring matches
+    pub fn search(&self, s: &str) -> bool {
+        self.run(s, false)
+    }
+
+    fn run(&self, s: &str, anchored: bool) -> bool {
+        let mut current = Vec::new();
+        let mut next = Vec::new();
+        // The step each instruction was last added in, to avoid duplicates
+        let mut added = vec![usize::MAX; self.program.len()];
+        self.add(&mut current, 0, &mut added, 0);
+        for (step, c) in s.chars().enumerate() {
+            if !anchored && self.matched(&current) {
+                return true;
+            }
+            next.clear();
+            for &pc in &current {
+                if let Inst::Char(set) = &self.program[pc] {
+                    if set.contains(c) {
+                        self.add(&mut next, pc + 1, &mut added, step + 1);
+                    }
+                }
+            }
+            if !anchored {
+                self.add(&mut next, 0, &mut added, step + 1);
+            }
+            std::mem::swap(&mut current, &mut next);
+            if current.is_empty() {
+                return false;
+            }
+        }
+        self.matched(&current)
+    }
+
+    fn matched(&self, threads: &[usize]) -> bool {
+        threads
+            .iter()
+            .any(|&pc| matches!(self.program[pc], Inst::Match))
+    }
+
+    /// Adds a thread, following jumps and splits
+    fn add(&self, threads: &mut Vec<usize>, pc: usize, added: &mut [usize], step: usize) {
+        if added[pc] == step {
+            return;
+        }
+        added[pc] = step;
+        match self.program[pc] {
+            Inst::Jmp(to) => self.add(threads, to, added, step),
+            Inst::Split(a, b) => {
+                self.add(threads, a, added, step);
+                self.add(threads, b, added, step);
+            }
+            _ => threads.push(pc),
+        }
+    }
+}
+
+fn emit(ast: &Ast, program: &mut Vec<Inst>) -> Option<()> {
+    if program.len() > MAX_PROGRAM {
+        return None;
+    }
+    match ast {
+        Ast::Set(set) => program.push(Inst::Char(set.clone())),
+        Ast::Concat(items) => {
+            for item in items {
+                emit(item, program)?;
+            }
+        }
+        Ast::Alt(branches) => {
+            let mut jumps = Vec::new();
+            for (i, branch) in branches.iter().enumerate() {
+                if i + 1 == branches.len() {
+                    emit(branch, program)?;
+                } else {
+                    let split = program.len();
+                    program.push(Inst::Split(split + 1, 0));
+                    emit(branch, program)?;
+                    jumps.push(program.len());
+                    program.push(Inst::Jmp(0));
+                    program[split] = Inst::Split(split + 1, program.len());
+                }
+            }
+            let end = program.len();
+            for jump in jumps {
+                program[jump] = Inst::Jmp(end);
+            }
+        }
+        Ast::Repeat(item, min, max) => {
+            for _ in 0..*min {
+                emit(item, program)?;
+            }
+            match max {
+                None => {
+                    let split = program.len();
+                    program.push(Inst::Split(split + 1, 0));
+                    emit(item, program)?;
+                    program.push(Inst::Jmp(split));
+                    program[split] = Inst::Split(split + 1, program.len());
+                }
+                Some(max) => {
+                    let mut splits = Vec::new();
+                    for _ in *min..*max {
+                        splits.push(program.len());
+                        program.push(Inst::Split(0, 0));
+                        emit(item, program)?;
+                    }
+                    let end = program.len();
+                    for split in splits {
+                        program[split] = Inst::Split(split + 1, end);
+                    }
+                }
+            }
+        }
+    }
+    Some(())
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        Some(c)
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn alt(&mut self) -> Option<Ast> {
+        let mut branches = vec![self.concat()?];
+        while self.eat('|') {
+            branches.push(self.concat()?);
+        }
+        Some(match branches.len() {
+            1 => branches.pop()?,
+            _ => Ast::Alt(branches),
+        })
+    }
+
+    fn concat(&mut self) -> Option<Ast> {
+        let mut items = Vec::new();
+        while !matches!(self.peek(), None | Some('|' | ')')) {
+            let atom = self.atom()?;
+            items.push(self.quantifier(atom)?);
+        }
+        Some(Ast::Concat(items))
+    }
+
+    fn quantifier(&mut self, atom: Ast) -> Option<Ast> {
+        let (min, max) = match self.peek() {
+            Some('*') => (0, None),
+            Some('+') => (1, None),
+            Some('?') => (0, Some(1)),
+            Some('{') => {
+                self.bump();
+                let min = self.count()?;
+                let max = if self.eat(',') {
+                    match self.peek() {
+                        Some('}') => None,
+                        _ => Some(self.count()?),
+                    }
+                } else {
+                    Some(min)
+                };
+                if self.peek() != Some('}') || max.is_some_and(|max| max < min) {
+                    return None;
+                }
+                (min, max)
+            }
+            _ => return Some(atom),
+        };
+        self.bump();
+        Some(Ast::Repeat(Box::new(atom), min, max))
+    }
+
+    fn count(&mut self) -> Option<u32> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        let digits: String = self.chars[start..self.pos].iter().collect();
+        digits.parse().ok().filter(|&n| n as usize <= MAX_PROGRAM)
+    }
+
+    fn atom(&mut self) -> Option<Ast> {
+        let set = match self.bump()? {
+            '(' => {
+                let ast = self.alt()?;
+                return self.eat(')').then_some(ast);
+            }
+            '.' => CharSet::Any,
+            '[' => self.class()?,
+            '\\' => match self.peek()? {
+                'p' | 'P' => {
+                    let (cat, negated) = self.category()?;
+                    CharSet::Category(cat, negated)
+                }
+                _ => CharSet::Char(self.single_escape()?),
+            },
+            ')' | '*' | '+' | '?' | ']' | '{' | '|' | '}' => return None,
+            c => CharSet::Char(c),
+        };
+        Some(Ast::Set(set))
+    }
+
+    /// Reads the character after a backslash
+    fn single_escape(&mut self) -> Option<char> {
+        match self.bump()? {
+            'n' => Some('\n'),
+            'r' => Some('\r'),
+            't' => Some('\t'),
+            c @ ('(' | ')' | '*' | '+' | '-' | '.' | '?' | '[' | '\\' | ']' | '^' | '{' | '|'
+            | '}') => Some(c),
+            _ => None,
+        }
+    }
+
+    /// Reads a category escape like "p{Lu}" after the backslash, returning
+    /// whether it's negated
+    fn category(&mut self) -> Option<(Category, bool)> {
+        let negated = self.bump()? == 'P';
+        if !self.eat('{') {
+            return None;
+        }
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_alphabetic()) {
+            self.pos += 1;
+        }
+        let name: String = self.chars[start..self.pos].iter().collect();
+        if !self.eat('}') {
+            return None;
+        }
+        let cat = match name.as_str() {
+            "L" => Category::Letter,
+            "Lu" => Category::Uppercase,
+            "Ll" => Category::Lowercase,
+            "N" | "Nd" => Category::Number,
+            "P" => Category::Punctuation,
+            "S" => Category::Symbol,
+            "Z" => Category::Separator,
+            "Zs" => Category::SpaceSeparator,
+            "Zl" => Category::LineSeparator,
+            "Zp" => Category::ParagraphSeparator,
+            "C" | "Cc" => Category::Control,
+            _ => return None,
+        };
+        Some((cat, negated))
+    }
+
+    /// Reads a character class after the opening bracket
+    fn class(&mut self) -> Option<CharSet> {
+        let negated = self.eat('^');
+        let mut items = Vec::new();
+        if self.eat('-') {
+            items.push(ClassItem::Range('-', '-'));
+        }
+        loop {
+            match self.peek()? {
+                ']' if !items.is_empty() => {
+                    self.bump();
+                    return Some(CharSet::Class { negated, items });
+                }
+                '-' => {
+                    // A dash is only allowed unescaped at the ends
+                    self.bump();
+                    if self.bump()? != ']' {
+                        return None;
+                    }
+                    items.push(ClassItem::Range('-', '-'));
+                    return Some(CharSet::Class { negated, items });
+                }
+                '\\' if matches!(self.chars.get(self.pos + 1), Some('p' | 'P')) => {
+                    self.bump();
+                    let (cat, negated) = self.category()?;
+                    items.push(ClassItem::Category(cat, negated));
+                }
+                _ => {
+                    let lo = self.class_char()?;
+                    let is_range = self.peek() == Some('-')
+                        && !matches!(self.chars.get(self.pos + 1), Some(']') | None);
+                    let hi = if is_range {
+                        self.bump();
+                        self.class_char()?
+                    } else {
+                        lo
+                    };
+                    if hi < lo {
+                        return None;
+                    }
+                    items.push(ClassItem::Range(lo, hi));
+                }
+            }
+        }
+    }
+
+    fn class_char(&mut self) -> Option<char> {
+        match self.bump()? {
+            '\\' => self.single_escape(),
+            '[' | ']' | '-' => None,
+            c => Some(c),
+        }
+    }
+}
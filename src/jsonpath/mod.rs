@@ -0,0 +1,126 @@
+mod eval;
+mod iregexp;
+mod parser;
+
+use std::fmt;
+
+use crate::{pointer::push_pointer_token, Element};
+
+/// An error from compiling a JSONPath query. Offsets are in bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathError {
+    UnexpectedChar {
+        c: char,
+        offset: usize,
+    },
+    UnexpectedEnd,
+    /// An integer has leading zeros, or is outside the range that can be
+    /// exactly represented by a double
+    InvalidInteger {
+        offset: usize,
+    },
+    /// A string literal has an invalid escape or unpaired surrogate
+    InvalidEscape {
+        offset: usize,
+    },
+    UnknownFunction {
+        name: String,
+        offset: usize,
+    },
+    /// A function has the wrong number of arguments, or an argument of the
+    /// wrong type
+    InvalidArgument {
+        offset: usize,
+    },
+    /// An expression is used where its type isn't allowed, like a query
+    /// which can select many nodes being compared
+    TypeMismatch {
+        offset: usize,
+    },
+}
+
+/// A JSONPath query (RFC 9535), compiled once so that it can be run on many
+/// documents.
+#[derive(Debug)]
+pub struct JsonPath {
+    query: parser::Query,
+}
+
+impl JsonPath {
+    pub fn compile(path: &str) -> Result<JsonPath, PathError> {
+        parser::parse(path).map(|query| JsonPath { query })
+    }
+
+    /// Runs the query, returning each selected value with its normalized
+    /// path. The members of objects are visited in an unspecified order.
+    pub fn query<'a>(&self, root: &'a Element) -> Vec<QueryResult<'a>> {
+        eval::select(&self.query, root, root)
+            .into_iter()
+            .map(|(path, value)| QueryResult {
+                path: NormalizedPath(path),
+                value,
+            })
+            .collect()
+    }
+}
+
+/// A value selected by a `JsonPath`.
+#[derive(Debug, PartialEq)]
+pub struct QueryResult<'a> {
+    pub path: NormalizedPath,
+    pub value: &'a Element,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    Name(String),
+    Index(usize),
+}
+
+/// The unique path to a value, displayed in the normalized form from
+/// RFC 9535 like `$['store']['book'][0]`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct NormalizedPath(pub Vec<PathSegment>);
+
+impl NormalizedPath {
+    /// Converts the path to a JSON Pointer
+    pub fn to_pointer(&self) -> String {
+        let mut pointer = String::new();
+        for segment in &self.0 {
+            match segment {
+                PathSegment::Name(name) => push_pointer_token(&mut pointer, name),
+                PathSegment::Index(i) => push_pointer_token(&mut pointer, &i.to_string()),
+            }
+        }
+        pointer
+    }
+}
+
+impl fmt::Display for NormalizedPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("$")?;
+        for segment in &self.0 {
+            match segment {
+                PathSegment::Name(name) => {
+                    f.write_str("['")?;
+                    for c in name.chars() {
+                        match c {
+                            '\x08' => f.write_str("\\b")?,
+                            '\x0c' => f.write_str("\\f")?,
+                            '\n' => f.write_str("\\n")?,
+                            '\r' => f.write_str("\\r")?,
+                            '\t' => f.write_str("\\t")?,
+                            '\'' => f.write_str("\\'")?,
+                            '\\' => f.write_str("\\\\")?,
+                            c if c < ' ' => write!(f, "\\u{:04x}", c as u32)?,
+                            c => write!(f, "{c}")?,
+                        }
+                    }
+                    f.write_str("']")?;
+                }
+                PathSegment::Index(i) => write!(f, "[{i}]")?,
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,627 @@
+use crate::Element;
+
+use super::{iregexp::Regex, PathError};
+
+/// Integers in queries must be exactly representable as doubles
+const MAX_INT: i64 = (1 << 53) - 1;
+
+#[derive(Debug)]
+pub(super) struct Query {
+    /// Whether the query starts at the current node '@' instead of the root
+    pub relative: bool,
+    pub segments: Vec<Segment>,
+}
+
+impl Query {
+    /// Returns whether the query can select at most one node
+    fn is_singular(&self) -> bool {
+        self.segments.iter().all(|segment| {
+            !segment.descendant
+                && matches!(
+                    segment.selectors[..],
+                    [Selector::Name(_) | Selector::Index(_)]
+                )
+        })
+    }
+}
+
+#[derive(Debug)]
+pub(super) struct Segment {
+    /// Whether the selectors apply to every descendant, for '..'
+    pub descendant: bool,
+    pub selectors: Vec<Selector>,
+}
+
+#[derive(Debug)]
+pub(super) enum Selector {
+    Name(String),
+    Wildcard,
+    Index(i64),
+    Slice {
+        start: Option<i64>,
+        end: Option<i64>,
+        step: Option<i64>,
+    },
+    Filter(Expr),
+}
+
+#[derive(Debug)]
+pub(super) enum Expr {
+    Or(Vec<Expr>),
+    And(Vec<Expr>),
+    Not(Box<Expr>),
+    Compare(Box<Expr>, CompareOp, Box<Expr>),
+    Literal(Element),
+    Query(Query),
+    Function(Function, Vec<Expr>),
+    /// A literal pattern passed to `match` or `search`, compiled once when
+    /// the query is parsed. `None` if it's invalid, so nothing matches.
+    Regex(Option<Regex>),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(super) enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(super) enum Function {
+    Length,
+    Count,
+    Match,
+    Search,
+    Value,
+}
+
+/// The types of function parameters and results
+#[derive(Clone, Copy, PartialEq)]
+enum Type {
+    Value,
+    Logical,
+    Nodes,
+}
+
+impl Function {
+    fn params(self) -> &'static [Type] {
+        match self {
+            Function::Length => &[Type::Value],
+            Function::Count | Function::Value => &[Type::Nodes],
+            Function::Match | Function::Search => &[Type::Value, Type::Value],
+        }
+    }
+
+    fn result(self) -> Type {
+        match self {
+            Function::Length | Function::Count | Function::Value => Type::Value,
+            Function::Match | Function::Search => Type::Logical,
+        }
+    }
+}
+
+/// Returns whether the expression can be used as a test in a filter
+fn is_logical(e: &Expr) -> bool {
+    match e {
+        Expr::Or(_) | Expr::And(_) | Expr::Not(_) | Expr::Compare(..) | Expr::Query(_) => true,
+        Expr::Function(f, _) => f.result() != Type::Value,
+        Expr::Literal(_) | Expr::Regex(_) => false,
+    }
+}
+
+/// Returns whether the expression can be compared
+fn is_comparable(e: &Expr) -> bool {
+    match e {
+        Expr::Literal(_) | Expr::Regex(_) => true,
+        Expr::Query(q) => q.is_singular(),
+        Expr::Function(f, _) => f.result() == Type::Value,
+        _ => false,
+    }
+}
+
+fn has_type(e: &Expr, ty: Type) -> bool {
+    match ty {
+        Type::Value => is_comparable(e),
+        Type::Logical => is_logical(e),
+        Type::Nodes => match e {
+            Expr::Query(_) => true,
+            Expr::Function(f, _) => f.result() == Type::Nodes,
+            _ => false,
+        },
+    }
+}
+
+pub(super) fn parse(input: &str) -> Result<Query, PathError> {
+    let mut p = Parser { input, pos: 0 };
+    p.expect('$')?;
+    let segments = p.segments()?;
+    match p.peek() {
+        Some(_) => Err(p.unexpected()),
+        None => Ok(Query {
+            relative: false,
+            segments,
+        }),
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_str(&mut self, s: &str) -> bool {
+        if self.input[self.pos..].starts_with(s) {
+            self.pos += s.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), PathError> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.unexpected())
+        }
+    }
+
+    /// Returns an error for the next character
+    fn unexpected(&self) -> PathError {
+        match self.peek() {
+            Some(c) => PathError::UnexpectedChar {
+                c,
+                offset: self.pos,
+            },
+            None => PathError::UnexpectedEnd,
+        }
+    }
+
+    fn skip_blank(&mut self) {
+        while let Some(' ' | '\t' | '\n' | '\r') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn segments(&mut self) -> Result<Vec<Segment>, PathError> {
+        let mut segments = Vec::new();
+        loop {
+            let start = self.pos;
+            self.skip_blank();
+            match self.peek() {
+                Some('.' | '[') => segments.push(self.segment()?),
+                _ => {
+                    self.pos = start;
+                    return Ok(segments);
+                }
+            }
+        }
+    }
+
+    fn segment(&mut self) -> Result<Segment, PathError> {
+        if !self.eat('.') {
+            return Ok(Segment {
+                descendant: false,
+                selectors: self.bracketed()?,
+            });
+        }
+        let descendant = self.eat('.');
+        let selectors = if descendant && self.peek() == Some('[') {
+            self.bracketed()?
+        } else if self.eat('*') {
+            vec![Selector::Wildcard]
+        } else {
+            vec![Selector::Name(self.member_name()?)]
+        };
+        Ok(Segment {
+            descendant,
+            selectors,
+        })
+    }
+
+    fn bracketed(&mut self) -> Result<Vec<Selector>, PathError> {
+        self.expect('[')?;
+        let mut selectors = Vec::new();
+        loop {
+            self.skip_blank();
+            selectors.push(self.selector()?);
+            self.skip_blank();
+            if !self.eat(',') {
+                self.expect(']')?;
+                return Ok(selectors);
+            }
+        }
+    }
+
+    fn selector(&mut self) -> Result<Selector, PathError> {
+        match self.peek() {
+            Some('\'' | '"') => self.string().map(Selector::Name),
+            Some('*') => {
+                self.bump();
+                Ok(Selector::Wildcard)
+            }
+            Some('?') => {
+                self.bump();
+                self.skip_blank();
+                self.logical_or().map(Selector::Filter)
+            }
+            Some('-' | '0'..='9' | ':') => {
+                let start = match self.peek() {
+                    Some(':') => None,
+                    _ => Some(self.integer()?),
+                };
+                self.skip_blank();
+                if !self.eat(':') {
+                    return Ok(Selector::Index(start.unwrap_or_default()));
+                }
+                self.skip_blank();
+                let end = self.optional_integer()?;
+                self.skip_blank();
+                let step = if self.eat(':') {
+                    self.skip_blank();
+                    self.optional_integer()?
+                } else {
+                    None
+                };
+                Ok(Selector::Slice { start, end, step })
+            }
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    fn optional_integer(&mut self) -> Result<Option<i64>, PathError> {
+        match self.peek() {
+            Some('-' | '0'..='9') => self.integer().map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    fn integer(&mut self) -> Result<i64, PathError> {
+        let start = self.pos;
+        let negative = self.eat('-');
+        let text = self.digits()?;
+        let invalid = PathError::InvalidInteger { offset: start };
+        if (text.starts_with('0') && text.len() > 1) || (negative && text == "0") {
+            return Err(invalid);
+        }
+        match self.input[start..self.pos].parse::<i64>() {
+            Ok(i) if (-MAX_INT..=MAX_INT).contains(&i) => Ok(i),
+            _ => Err(invalid),
+        }
+    }
+
+    fn member_name(&mut self) -> Result<String, PathError> {
+        let start = self.pos;
+        match self.peek() {
+            Some(c) if c.is_ascii_alphabetic() || c == '_' || !c.is_ascii() => {}
+            _ => return Err(self.unexpected()),
+        }
+        while let Some(c) = self.peek() {
+            if !(c.is_ascii_alphanumeric() || c == '_' || !c.is_ascii()) {
+                break;
+            }
+            self.pos += c.len_utf8();
+        }
+        Ok(self.input[start..self.pos].to_owned())
+    }
+
+    /// Reads a string literal in single or double quotes
+    fn string(&mut self) -> Result<String, PathError> {
+        let quote = self.bump().ok_or(PathError::UnexpectedEnd)?;
+        let mut s = String::new();
+        loop {
+            let offset = self.pos;
+            match self.bump() {
+                None => return Err(PathError::UnexpectedEnd),
+                Some(c) if c == quote => return Ok(s),
+                Some('\\') => {
+                    let c = match self.bump() {
+                        Some('b') => '\x08',
+                        Some('f') => '\x0c',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some(c @ ('/' | '\\')) => c,
+                        Some(c) if c == quote => c,
+                        Some('u') => self.unicode_escape(offset)?,
+                        _ => return Err(PathError::InvalidEscape { offset }),
+                    };
+                    s.push(c);
+                }
+                Some(c) if c < ' ' => return Err(PathError::UnexpectedChar { c, offset }),
+                Some(c) => s.push(c),
+            }
+        }
+    }
+
+    /// Reads the hex digits after "\u", and a trailing surrogate if needed
+    fn unicode_escape(&mut self, offset: usize) -> Result<char, PathError> {
+        let invalid = PathError::InvalidEscape { offset };
+        let lead = self.hex4().ok_or(invalid.clone())?;
+        let n = match lead {
+            0xd800..=0xdbff => {
+                if !self.eat_str("\\u") {
+                    return Err(invalid);
+                }
+                match self.hex4() {
+                    Some(trail @ 0xdc00..=0xdfff) => {
+                        0x10000 + ((lead - 0xd800) << 10) + (trail - 0xdc00)
+                    }
+                    _ => return Err(invalid),
+                }
+            }
+            0xdc00..=0xdfff => return Err(invalid),
+            n => n,
+        };
+        char::from_u32(n).ok_or(invalid)
+    }
+
+    fn hex4(&mut self) -> Option<u32> {
+        let hex = self.input.get(self.pos..self.pos + 4)?;
+        if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        self.pos += 4;
+        u32::from_str_radix(hex, 16).ok()
+    }
+
+    /// Parses a logical expression, checking that it can be used as a test
+    fn logical_or(&mut self) -> Result<Expr, PathError> {
+        let mut operands = vec![self.logical_and()?];
+        loop {
+            let start = self.pos;
+            self.skip_blank();
+            if !self.eat_str("||") {
+                self.pos = start;
+                break;
+            }
+            self.skip_blank();
+            operands.push(self.logical_and()?);
+        }
+        Ok(match operands.len() {
+            1 => operands.pop().unwrap(),
+            _ => Expr::Or(operands),
+        })
+    }
+
+    fn logical_and(&mut self) -> Result<Expr, PathError> {
+        let mut operands = vec![self.basic()?];
+        loop {
+            let start = self.pos;
+            self.skip_blank();
+            if !self.eat_str("&&") {
+                self.pos = start;
+                break;
+            }
+            self.skip_blank();
+            operands.push(self.basic()?);
+        }
+        Ok(match operands.len() {
+            1 => operands.pop().unwrap(),
+            _ => Expr::And(operands),
+        })
+    }
+
+    /// Parses a negation, parenthesized expression, comparison or test
+    fn basic(&mut self) -> Result<Expr, PathError> {
+        let start = self.pos;
+        if self.eat('!') {
+            self.skip_blank();
+            let operand = self.pos;
+            let e = match self.peek() {
+                Some('(') => self.paren()?,
+                _ => self.primary()?,
+            };
+            if !is_logical(&e) {
+                return Err(PathError::TypeMismatch { offset: operand });
+            }
+            return Ok(Expr::Not(Box::new(e)));
+        }
+        if self.peek() == Some('(') {
+            return self.paren();
+        }
+
+        let left = self.primary()?;
+        let end = self.pos;
+        self.skip_blank();
+        let Some(op) = self.compare_op() else {
+            self.pos = end;
+            if !is_logical(&left) {
+                return Err(PathError::TypeMismatch { offset: start });
+            }
+            return Ok(left);
+        };
+        if !is_comparable(&left) {
+            return Err(PathError::TypeMismatch { offset: start });
+        }
+        self.skip_blank();
+        let right_start = self.pos;
+        let right = self.primary()?;
+        if !is_comparable(&right) {
+            return Err(PathError::TypeMismatch {
+                offset: right_start,
+            });
+        }
+        Ok(Expr::Compare(Box::new(left), op, Box::new(right)))
+    }
+
+    fn paren(&mut self) -> Result<Expr, PathError> {
+        self.expect('(')?;
+        self.skip_blank();
+        let e = self.logical_or()?;
+        self.skip_blank();
+        self.expect(')')?;
+        Ok(e)
+    }
+
+    fn compare_op(&mut self) -> Option<CompareOp> {
+        [
+            ("==", CompareOp::Eq),
+            ("!=", CompareOp::Ne),
+            ("<=", CompareOp::Le),
+            (">=", CompareOp::Ge),
+            ("<", CompareOp::Lt),
+            (">", CompareOp::Gt),
+        ]
+        .into_iter()
+        .find(|(s, _)| self.eat_str(s))
+        .map(|(_, op)| op)
+    }
+
+    /// Parses a literal, query or function call
+    fn primary(&mut self) -> Result<Expr, PathError> {
+        let start = self.pos;
+        match self.peek() {
+            Some(c @ ('@' | '$')) => {
+                self.bump();
+                Ok(Expr::Query(Query {
+                    relative: c == '@',
+                    segments: self.segments()?,
+                }))
+            }
+            Some('\'' | '"') => self.string().map(|s| Expr::Literal(Element::JsonString(s))),
+            Some('-' | '0'..='9') => self.number().map(Expr::Literal),
+            Some('a'..='z') => {
+                while let Some('a'..='z' | '0'..='9' | '_') = self.peek() {
+                    self.pos += 1;
+                }
+                let name = &self.input[start..self.pos];
+                if self.peek() == Some('(') {
+                    return self.function(name, start);
+                }
+                match name {
+                    "true" => Ok(Expr::Literal(Element::JsonBool(true))),
+                    "false" => Ok(Expr::Literal(Element::JsonBool(false))),
+                    "null" => Ok(Expr::Literal(Element::JsonNull)),
+                    _ => {
+                        self.pos = start;
+                        Err(self.unexpected())
+                    }
+                }
+            }
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    fn function(&mut self, name: &str, start: usize) -> Result<Expr, PathError> {
+        let function = match name {
+            "length" => Function::Length,
+            "count" => Function::Count,
+            "match" => Function::Match,
+            "search" => Function::Search,
+            "value" => Function::Value,
+            _ => {
+                return Err(PathError::UnknownFunction {
+                    name: name.to_owned(),
+                    offset: start,
+                })
+            }
+        };
+        self.expect('(')?;
+        self.skip_blank();
+        let params = function.params();
+        let mut args = Vec::new();
+        if !self.eat(')') {
+            loop {
+                let offset = self.pos;
+                let arg = self.argument()?;
+                match params.get(args.len()) {
+                    Some(&ty) if has_type(&arg, ty) => args.push(arg),
+                    _ => return Err(PathError::InvalidArgument { offset }),
+                }
+                self.skip_blank();
+                if !self.eat(',') {
+                    self.expect(')')?;
+                    break;
+                }
+                self.skip_blank();
+            }
+        }
+        if args.len() != params.len() {
+            return Err(PathError::InvalidArgument { offset: start });
+        }
+        if let (Function::Match | Function::Search, Some(Expr::Literal(Element::JsonString(re)))) =
+            (function, args.get(1))
+        {
+            args[1] = Expr::Regex(Regex::new(re));
+        }
+        Ok(Expr::Function(function, args))
+    }
+
+    /// Reads one or more digits
+    fn digits(&mut self) -> Result<&'a str, PathError> {
+        let start = self.pos;
+        while let Some('0'..='9') = self.peek() {
+            self.pos += 1;
+        }
+        if self.pos == start {
+            Err(self.unexpected())
+        } else {
+            Ok(&self.input[start..self.pos])
+        }
+    }
+
+    /// Parses a function argument, which can be a literal or a value as well
+    /// as a logical expression
+    fn argument(&mut self) -> Result<Expr, PathError> {
+        let start = self.pos;
+        if !matches!(self.peek(), Some('!' | '(')) {
+            let arg = self.primary()?;
+            self.skip_blank();
+            if matches!(self.peek(), Some(',' | ')')) {
+                return Ok(arg);
+            }
+            self.pos = start;
+        }
+        self.logical_or()
+    }
+
+    fn number(&mut self) -> Result<Element, PathError> {
+        let start = self.pos;
+        self.eat('-');
+        let int = self.digits()?;
+        if int.starts_with('0') && int.len() > 1 {
+            return Err(PathError::InvalidInteger { offset: start });
+        }
+        let mut float = false;
+        if self.eat('.') {
+            self.digits()?;
+            float = true;
+        }
+        if self.eat('e') || self.eat('E') {
+            if !self.eat('+') {
+                self.eat('-');
+            }
+            self.digits()?;
+            float = true;
+        }
+        let text = &self.input[start..self.pos];
+        Ok(match text.parse::<i64>() {
+            Ok(i) if !float => Element::JsonInt(i),
+            _ => Element::JsonFloat(text.parse().unwrap_or(f64::NAN)),
+        })
+    }
+}
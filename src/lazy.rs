@@ -0,0 +1,217 @@
+use std::borrow::Cow;
+
+use crate::{
+    unmarshal::{string::unmarshal_cow, Position, UnmarshalIter},
+    UnmarshalError, Unmarshalable, UnmarshalableBorrowed,
+};
+
+/// A value in some input which is only parsed when it's navigated to.
+/// Subtrees which aren't navigated into are skipped by matching brackets,
+/// so they are not fully validated.
+#[derive(Debug, Clone, Copy)]
+pub struct LazyValue<'a> {
+    input: &'a str,
+    /// Offset of the value in the input
+    offset: usize,
+}
+
+impl<'a> LazyValue<'a> {
+    /// Wraps the input without checking it
+    pub fn new(input: &'a str) -> LazyValue<'a> {
+        LazyValue { input, offset: 0 }
+    }
+
+    /// Wraps the input after checking that it's a single value with
+    /// balanced brackets and terminated strings
+    pub fn new_checked(input: &'a str) -> Result<LazyValue<'a>, UnmarshalError> {
+        let mut u = UnmarshalIter::new(input.chars());
+        u.skip_value()?;
+        u.check_finished()?;
+        Ok(LazyValue::new(input))
+    }
+
+    fn iter(&self) -> UnmarshalIter<'a> {
+        let mut u = UnmarshalIter::new(self.input.chars());
+        u.seek(self.offset);
+        u
+    }
+
+    /// Returns the position of the start of the value in the input
+    pub fn position(&self) -> Position {
+        let mut u = self.iter();
+        u.peek_non_whitespace();
+        u.position()
+    }
+
+    pub fn is_object(&self) -> bool {
+        self.iter().peek_non_whitespace() == Some(&'{')
+    }
+
+    pub fn is_array(&self) -> bool {
+        self.iter().peek_non_whitespace() == Some(&'[')
+    }
+
+    /// Parses the value
+    pub fn parse<T: Unmarshalable>(&self) -> Result<T, UnmarshalError> {
+        T::unmarshal_json_with_state(&mut self.iter())
+    }
+
+    /// Parses the value, borrowing from the input where possible
+    pub fn parse_borrowed<T: UnmarshalableBorrowed<'a>>(&self) -> Result<T, UnmarshalError> {
+        T::unmarshal_json_borrowed_with_state(&mut self.iter())
+    }
+
+    /// Returns the source text of the value
+    pub fn raw(&self) -> Result<&'a str, UnmarshalError> {
+        let mut u = self.iter();
+        u.peek_non_whitespace();
+        let start = u.offset();
+        u.skip_value()?;
+        Ok(u.str_since(start, 0))
+    }
+
+    /// Looks up the value for a key in an object, returning `None` if the
+    /// key is missing or this isn't an object. If the key appears more than
+    /// once the last value is returned, like when unmarshaling a `HashMap`.
+    pub fn get(&self, key: &str) -> Result<Option<LazyValue<'a>>, UnmarshalError> {
+        let mut found = None;
+        for member in self.members() {
+            let (k, v) = member?;
+            if k == key {
+                found = Some(v);
+            }
+        }
+        Ok(found)
+    }
+
+    /// Returns the element at an index of an array, or `None` if the index is
+    /// out of bounds or this isn't an array
+    pub fn index(&self, i: usize) -> Result<Option<LazyValue<'a>>, UnmarshalError> {
+        for (j, element) in self.elements().enumerate() {
+            let element = element?;
+            if j == i {
+                return Ok(Some(element));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Iterates over the members of an object, or nothing for other values
+    pub fn members(&self) -> LazyMembers<'a> {
+        LazyMembers {
+            inner: LazyIter::new(self, '{', '}'),
+        }
+    }
+
+    /// Iterates over the elements of an array, or nothing for other values
+    pub fn elements(&self) -> LazyElements<'a> {
+        LazyElements {
+            inner: LazyIter::new(self, '[', ']'),
+        }
+    }
+}
+
+/// Walks the children of an object or array, skipping each one before
+/// reading the next
+struct LazyIter<'a> {
+    input: &'a str,
+    u: UnmarshalIter<'a>,
+    close: char,
+    first: bool,
+    done: bool,
+}
+
+impl<'a> LazyIter<'a> {
+    fn new(value: &LazyValue<'a>, open: char, close: char) -> LazyIter<'a> {
+        let mut u = value.iter();
+        let done = u.peek_non_whitespace() != Some(&open);
+        if !done {
+            u.next();
+        }
+        LazyIter {
+            input: value.input,
+            u,
+            close,
+            first: true,
+            done,
+        }
+    }
+
+    /// Moves to the next child, returning false at the end
+    fn advance(&mut self) -> Result<bool, UnmarshalError> {
+        if self.first {
+            self.first = false;
+            if self.u.peek_non_whitespace() == Some(&self.close) {
+                self.u.next();
+                return Ok(false);
+            }
+            return Ok(true);
+        }
+        self.u.skip_value()?;
+        match self.u.next_non_whitespace() {
+            Some(',') => Ok(true),
+            Some(c) if c == self.close => Ok(false),
+            unexpected => Err(self.u.unexpected(unexpected)),
+        }
+    }
+
+    /// Returns the value that starts at the next non-whitespace character
+    fn value(&mut self) -> LazyValue<'a> {
+        self.u.peek_non_whitespace();
+        LazyValue {
+            input: self.input,
+            offset: self.u.offset(),
+        }
+    }
+
+    fn next_with<T>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<T, UnmarshalError>,
+    ) -> Option<Result<T, UnmarshalError>> {
+        if self.done {
+            return None;
+        }
+        let result = match self.advance() {
+            Ok(true) => f(self),
+            Ok(false) => {
+                self.done = true;
+                return None;
+            }
+            Err(e) => Err(e),
+        };
+        self.done = result.is_err();
+        Some(result)
+    }
+}
+
+/// An iterator over the members of a lazy object
+pub struct LazyMembers<'a> {
+    inner: LazyIter<'a>,
+}
+
+impl<'a> Iterator for LazyMembers<'a> {
+    type Item = Result<(Cow<'a, str>, LazyValue<'a>), UnmarshalError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next_with(|inner| {
+            let key = unmarshal_cow(&mut inner.u)?;
+            match inner.u.next_non_whitespace() {
+                Some(':') => Ok((key, inner.value())),
+                unexpected => Err(inner.u.unexpected(unexpected)),
+            }
+        })
+    }
+}
+
+/// An iterator over the elements of a lazy array
+pub struct LazyElements<'a> {
+    inner: LazyIter<'a>,
+}
+
+impl<'a> Iterator for LazyElements<'a> {
+    type Item = Result<LazyValue<'a>, UnmarshalError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next_with(|inner| Ok(inner.value()))
+    }
+}
@@ -1,7 +1,21 @@
+pub mod borrowed_element;
+pub mod debug;
 pub mod element;
+pub mod jmespath;
+pub mod jsonpath;
+pub mod lazy;
+#[doc(hidden)]
+pub mod macros;
 pub mod marshal;
+pub mod pointer;
+pub mod raw_json;
+pub mod tape;
 pub mod unmarshal;
 
+pub use borrowed_element::BorrowedElement;
 pub use element::Element;
 pub use marshal::Marshalable;
-pub use unmarshal::{unmarshalable::Unmarshalable, UnmarshalError};
+pub use raw_json::RawJson;
+pub use unmarshal::{
+    unmarshalable::Unmarshalable, FromElementError, UnmarshalError, UnmarshalableBorrowed,
+};
@@ -0,0 +1,174 @@
+/// Builds an `Element` from JSON-like syntax.
+///
+/// Values can be any Rust expression which converts into an `Element`, or
+/// which is `Marshalable`. Keys can be string literals, or any expression
+/// which converts into a `String` in parentheses.
+#[macro_export]
+macro_rules! json {
+    ($($json:tt)+) => {
+        $crate::json_internal!($($json)+)
+    };
+}
+
+// Based on the muncher in serde_json. Arrays accumulate their elements as
+// expressions, and objects parse a key up to the colon, then a value up to
+// the comma. The last parenthesized group in object rules is a copy of the
+// remaining tokens, used for pointing errors at the right token.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! json_internal {
+    (@array [$($elems:expr,)*]) => {
+        vec![$($elems,)*]
+    };
+    (@array [$($elems:expr),*]) => {
+        vec![$($elems),*]
+    };
+    (@array [$($elems:expr,)*] null $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elems,)* $crate::json_internal!(null)] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] true $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elems,)* $crate::json_internal!(true)] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] false $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elems,)* $crate::json_internal!(false)] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] [$($array:tt)*] $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elems,)* $crate::json_internal!([$($array)*])] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] {$($object:tt)*} $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elems,)* $crate::json_internal!({$($object)*})] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] $next:expr, $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elems,)* $crate::json_internal!($next),] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] $last:expr) => {
+        $crate::json_internal!(@array [$($elems,)* $crate::json_internal!($last)])
+    };
+    (@array [$($elems:expr),*] , $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elems,)*] $($rest)*)
+    };
+    (@array [$($elems:expr),*] $unexpected:tt $($rest:tt)*) => {
+        $crate::json_unexpected!($unexpected)
+    };
+
+    (@object $object:ident () () ()) => {};
+    (@object $object:ident [$($key:tt)+] ($value:expr) , $($rest:tt)*) => {
+        let _ = $object.insert(($($key)+).into(), $value);
+        $crate::json_internal!(@object $object () ($($rest)*) ($($rest)*));
+    };
+    (@object $object:ident [$($key:tt)+] ($value:expr) $unexpected:tt $($rest:tt)*) => {
+        $crate::json_unexpected!($unexpected);
+    };
+    (@object $object:ident [$($key:tt)+] ($value:expr)) => {
+        let _ = $object.insert(($($key)+).into(), $value);
+    };
+    (@object $object:ident ($($key:tt)+) (: null $($rest:tt)*) $copy:tt) => {
+        $crate::json_internal!(@object $object [$($key)+] ($crate::json_internal!(null)) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: true $($rest:tt)*) $copy:tt) => {
+        $crate::json_internal!(@object $object [$($key)+] ($crate::json_internal!(true)) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: false $($rest:tt)*) $copy:tt) => {
+        $crate::json_internal!(@object $object [$($key)+] ($crate::json_internal!(false)) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: [$($array:tt)*] $($rest:tt)*) $copy:tt) => {
+        $crate::json_internal!(@object $object [$($key)+] ($crate::json_internal!([$($array)*])) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: {$($inner:tt)*} $($rest:tt)*) $copy:tt) => {
+        $crate::json_internal!(@object $object [$($key)+] ($crate::json_internal!({$($inner)*})) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: $value:expr , $($rest:tt)*) $copy:tt) => {
+        $crate::json_internal!(@object $object [$($key)+] ($crate::json_internal!($value)) , $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: $value:expr) $copy:tt) => {
+        $crate::json_internal!(@object $object [$($key)+] ($crate::json_internal!($value)));
+    };
+    // A key without a value
+    (@object $object:ident ($($key:tt)+) (:) $copy:tt) => {
+        $crate::json_internal!();
+    };
+    (@object $object:ident ($($key:tt)+) () $copy:tt) => {
+        $crate::json_internal!();
+    };
+    (@object $object:ident () (: $($rest:tt)*) ($colon:tt $($copy:tt)*)) => {
+        $crate::json_unexpected!($colon);
+    };
+    (@object $object:ident ($($key:tt)*) (, $($rest:tt)*) ($comma:tt $($copy:tt)*)) => {
+        $crate::json_unexpected!($comma);
+    };
+    // Computed keys are parenthesized so they can be any expression
+    (@object $object:ident () (($key:expr) : $($rest:tt)*) $copy:tt) => {
+        $crate::json_internal!(@object $object ($key) (: $($rest)*) (: $($rest)*));
+    };
+    (@object $object:ident ($($key:tt)*) ($tt:tt $($rest:tt)*) $copy:tt) => {
+        $crate::json_internal!(@object $object ($($key)* $tt) ($($rest)*) ($($rest)*));
+    };
+
+    (null) => {
+        $crate::Element::JsonNull
+    };
+    (true) => {
+        $crate::Element::JsonBool(true)
+    };
+    (false) => {
+        $crate::Element::JsonBool(false)
+    };
+    ([]) => {
+        $crate::Element::JsonList(vec![])
+    };
+    ([ $($tt:tt)+ ]) => {
+        $crate::Element::JsonList($crate::json_internal!(@array [] $($tt)+))
+    };
+    ({}) => {
+        $crate::Element::JsonObject(::std::collections::HashMap::new())
+    };
+    ({ $($tt:tt)+ }) => {
+        $crate::Element::JsonObject({
+            let mut object = ::std::collections::HashMap::new();
+            $crate::json_internal!(@object object () ($($tt)+) ($($tt)+));
+            object
+        })
+    };
+    ($other:expr) => {{
+        #[allow(unused_imports)]
+        use $crate::macros::{FromInto as _, FromMarshal as _};
+        $crate::macros::Interpolated($other).to_element()
+    }};
+}
+
+/// Has no rules, so that the compiler points at the unexpected token
+#[macro_export]
+#[doc(hidden)]
+macro_rules! json_unexpected {
+    () => {};
+}
+
+use crate::{Element, Marshalable};
+
+/// Wraps a value interpolated into `json!`. Values which convert into an
+/// `Element` are converted directly, since method lookup tries the wrapper
+/// by value before by reference. Other values use `Marshalable::to_element`.
+#[doc(hidden)]
+pub struct Interpolated<T>(pub T);
+
+#[doc(hidden)]
+pub trait FromInto {
+    fn to_element(self) -> Element;
+}
+
+impl<T: Into<Element>> FromInto for Interpolated<T> {
+    fn to_element(self) -> Element {
+        self.0.into()
+    }
+}
+
+#[doc(hidden)]
+pub trait FromMarshal {
+    fn to_element(self) -> Element;
+}
+
+impl<T: Marshalable> FromMarshal for &Interpolated<T> {
+    fn to_element(self) -> Element {
+        self.0.to_element()
+    }
+}
@@ -1,4 +1,4 @@
-use crate::marshal::Marshalable;
+use crate::{marshal::Marshalable, Element};
 
 impl Marshalable for bool {
     fn marshal_json_into(&self, s: &mut String) {
@@ -7,4 +7,8 @@ impl Marshalable for bool {
             false => s.push_str("false"),
         }
     }
+
+    fn to_element(&self) -> Element {
+        Element::JsonBool(*self)
+    }
 }
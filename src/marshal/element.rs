@@ -1,15 +1,136 @@
-use crate::{Element, Element::*, Marshalable};
+use std::fmt;
+
+use crate::{
+    marshal::{list::marshal_list, string::marshal_str, MarshalOptions},
+    BorrowedElement, Element,
+    Element::*,
+    Marshalable,
+};
 
 impl Marshalable for Element {
     fn marshal_json_into(&self, s: &mut String) {
+        self.marshal_json_into_with(s, &MarshalOptions::default())
+    }
+
+    fn marshal_json_into_with(&self, s: &mut String, options: &MarshalOptions) {
         match self {
-            JsonObject(e) => e.marshal_json_into(s),
-            JsonList(e) => e.marshal_json_into(s),
-            JsonString(e) => e.marshal_json_into(s),
+            JsonObject(e) => e.marshal_json_into_with(s, options),
+            JsonList(e) => e.marshal_json_into_with(s, options),
+            JsonString(e) => e.marshal_json_into_with(s, options),
             JsonInt(e) => e.marshal_json_into(s),
             JsonFloat(e) => e.marshal_json_into(s),
             JsonBool(e) => e.marshal_json_into(s),
             JsonNull => s.push_str("null"),
         }
     }
+
+    fn to_element(&self) -> Element {
+        self.clone()
+    }
+}
+
+/// Formats as JSON, pretty printed with `{:#}`. The text parses back into
+/// an equal element, so whole floats keep a decimal part and non-finite
+/// floats are written as null.
+impl fmt::Display for Element {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut s = String::new();
+        marshal_display(self, &mut s, f.alternate().then_some(0));
+        f.write_str(&s)
+    }
+}
+
+/// Marshals for `Display`. When pretty printing, the depth is given and each
+/// member and element is on its own line, indented by two spaces for each
+/// level of nesting.
+fn marshal_display(e: &Element, s: &mut String, depth: Option<usize>) {
+    let separator = |s: &mut String, depth: Option<usize>| {
+        if let Some(depth) = depth {
+            s.push('\n');
+            s.extend(std::iter::repeat_n("  ", depth));
+        }
+    };
+    let inner = depth.map(|depth| depth + 1);
+    match e {
+        JsonObject(o) if !o.is_empty() => {
+            s.push('{');
+            for (i, (k, v)) in o.iter().enumerate() {
+                if i > 0 {
+                    s.push_str(if depth.is_some() { "," } else { ", " });
+                }
+                separator(s, inner);
+                marshal_str(k, s, &MarshalOptions::default());
+                s.push_str(": ");
+                marshal_display(v, s, inner);
+            }
+            separator(s, depth);
+            s.push('}');
+        }
+        JsonList(l) if !l.is_empty() => {
+            s.push('[');
+            for (i, v) in l.iter().enumerate() {
+                if i > 0 {
+                    s.push_str(if depth.is_some() { "," } else { ", " });
+                }
+                separator(s, inner);
+                marshal_display(v, s, inner);
+            }
+            separator(s, depth);
+            s.push(']');
+        }
+        JsonFloat(f) if !f.is_finite() => s.push_str("null"),
+        JsonFloat(f) => {
+            let start = s.len();
+            f.marshal_json_into(s);
+            if !s[start..].contains('.') {
+                s.push_str(".0");
+            }
+        }
+        _ => e.marshal_json_into(s),
+    }
+}
+
+impl<'a> Marshalable for BorrowedElement<'a> {
+    fn marshal_json_into(&self, s: &mut String) {
+        self.marshal_json_into_with(s, &MarshalOptions::default())
+    }
+
+    fn marshal_json_into_with(&self, s: &mut String, options: &MarshalOptions) {
+        match self {
+            BorrowedElement::JsonObject(e) => {
+                s.push('{');
+                for (i, (k, e)) in e.iter().enumerate() {
+                    if i > 0 {
+                        s.push_str(", ");
+                    }
+                    marshal_str(k, s, options);
+                    s.push_str(": ");
+                    e.marshal_json_into_with(s, options);
+                }
+                s.push('}');
+            }
+            BorrowedElement::JsonList(e) => marshal_list(e.iter(), s, options),
+            BorrowedElement::JsonString(e) => marshal_str(e, s, options),
+            BorrowedElement::JsonInt(e) => e.marshal_json_into(s),
+            BorrowedElement::JsonFloat(e) => e.marshal_json_into(s),
+            BorrowedElement::JsonBool(e) => e.marshal_json_into(s),
+            BorrowedElement::JsonNull => s.push_str("null"),
+        }
+    }
+
+    fn to_element(&self) -> Element {
+        match self {
+            BorrowedElement::JsonObject(o) => JsonObject(
+                o.iter()
+                    .map(|(k, v)| (k.to_string(), v.to_element()))
+                    .collect(),
+            ),
+            BorrowedElement::JsonList(l) => JsonList(l.iter().map(|e| e.to_element()).collect()),
+            BorrowedElement::JsonString(s) => JsonString(s.to_string()),
+            BorrowedElement::JsonInt(i) => JsonInt(*i),
+            BorrowedElement::JsonFloat(f) => JsonFloat(*f),
+            BorrowedElement::JsonBool(b) => JsonBool(*b),
+            BorrowedElement::JsonNull => JsonNull,
+        }
+    }
 }
@@ -1,7 +1,12 @@
-use crate::marshal::Marshalable;
+use crate::{marshal::Marshalable, Element};
 
 impl Marshalable for f64 {
     fn marshal_json_into(&self, s: &mut String) {
         s.push_str(&self.to_string())
     }
+
+    /// Non-finite values have no JSON representation, so they become null
+    fn to_element(&self) -> Element {
+        Element::from(*self)
+    }
 }
@@ -1,7 +1,11 @@
-use crate::marshal::Marshalable;
+use crate::{marshal::Marshalable, Element};
 
 impl Marshalable for i64 {
     fn marshal_json_into(&self, s: &mut String) {
         s.push_str(&self.to_string())
     }
+
+    fn to_element(&self) -> Element {
+        Element::JsonInt(*self)
+    }
 }
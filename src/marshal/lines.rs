@@ -0,0 +1,38 @@
+use std::io::{self, Write};
+
+use crate::{marshal::MarshalOptions, Marshalable};
+
+/// Writes values as newline-delimited JSON (JSON Lines), each marshaled on
+/// its own line.
+pub struct LinesWriter<W: Write> {
+    w: W,
+    buf: String,
+    options: MarshalOptions,
+}
+
+impl<W: Write> LinesWriter<W> {
+    pub fn new(w: W) -> LinesWriter<W> {
+        LinesWriter::with_options(w, MarshalOptions::default())
+    }
+
+    pub fn with_options(w: W, options: MarshalOptions) -> LinesWriter<W> {
+        LinesWriter {
+            w,
+            buf: String::new(),
+            options,
+        }
+    }
+
+    pub fn write<T: Marshalable + ?Sized>(&mut self, value: &T) -> io::Result<()> {
+        self.buf.clear();
+        value.marshal_json_into_with(&mut self.buf, &self.options);
+        self.buf.push('\n');
+        self.w.write_all(self.buf.as_bytes())
+    }
+
+    /// Flushes and returns the inner writer
+    pub fn into_inner(mut self) -> io::Result<W> {
+        self.w.flush()?;
+        Ok(self.w)
+    }
+}
@@ -1,29 +1,40 @@
 use std::collections::HashSet;
 
-use crate::marshal::Marshalable;
+use crate::{
+    marshal::{MarshalOptions, Marshalable},
+    Element,
+};
 
-pub fn marshal_list<'a, T, U>(mut iter: T, s: &mut String)
+pub fn marshal_list<'a, T, U>(mut iter: T, s: &mut String, options: &MarshalOptions)
 where
     T: Iterator<Item = &'a U>,
     U: Marshalable + 'a,
 {
     s.push('[');
     if let Some(e) = iter.next() {
-        e.marshal_json_into(s);
+        e.marshal_json_into_with(s, options);
         for e in iter {
             s.push_str(", ");
-            e.marshal_json_into(s);
+            e.marshal_json_into_with(s, options);
         }
     }
     s.push(']');
 }
 
-impl<T> Marshalable for &[T]
+impl<T> Marshalable for [T]
 where
     T: Marshalable,
 {
     fn marshal_json_into(&self, s: &mut String) {
-        marshal_list(self.iter(), s)
+        self.marshal_json_into_with(s, &MarshalOptions::default())
+    }
+
+    fn marshal_json_into_with(&self, s: &mut String, options: &MarshalOptions) {
+        marshal_list(self.iter(), s, options)
+    }
+
+    fn to_element(&self) -> Element {
+        Element::JsonList(self.iter().map(T::to_element).collect())
     }
 }
 
@@ -32,7 +43,15 @@ where
     T: Marshalable,
 {
     fn marshal_json_into(&self, s: &mut String) {
-        marshal_list(self.iter(), s)
+        self.marshal_json_into_with(s, &MarshalOptions::default())
+    }
+
+    fn marshal_json_into_with(&self, s: &mut String, options: &MarshalOptions) {
+        marshal_list(self.iter(), s, options)
+    }
+
+    fn to_element(&self) -> Element {
+        Element::JsonList(self.iter().map(T::to_element).collect())
     }
 }
 
@@ -41,6 +60,14 @@ where
     T: Marshalable,
 {
     fn marshal_json_into(&self, s: &mut String) {
-        marshal_list(self.iter(), s)
+        self.marshal_json_into_with(s, &MarshalOptions::default())
+    }
+
+    fn marshal_json_into_with(&self, s: &mut String, options: &MarshalOptions) {
+        marshal_list(self.iter(), s, options)
+    }
+
+    fn to_element(&self) -> Element {
+        Element::JsonList(self.iter().map(T::to_element).collect())
     }
 }
@@ -1,9 +1,44 @@
+use crate::Element;
+
+use super::options::MarshalOptions;
+
 pub trait Marshalable {
     fn marshal_json(&self) -> String {
+        self.marshal_json_with(&MarshalOptions::default())
+    }
+
+    fn marshal_json_with(&self, options: &MarshalOptions) -> String {
         let mut s = String::new();
-        self.marshal_json_into(&mut s);
+        self.marshal_json_into_with(&mut s, options);
         s
     }
 
     fn marshal_json_into(&self, s: &mut String);
+
+    /// Marshals the type using the options. Types which contain strings
+    /// should override this so the options are passed on to them, while
+    /// other types can ignore the options.
+    fn marshal_json_into_with(&self, s: &mut String, _options: &MarshalOptions) {
+        self.marshal_json_into(s)
+    }
+
+    /// Converts the type into an element directly, without marshaling it
+    fn to_element(&self) -> Element;
+}
+
+impl<T> Marshalable for &T
+where
+    T: Marshalable + ?Sized,
+{
+    fn marshal_json_into(&self, s: &mut String) {
+        (**self).marshal_json_into(s)
+    }
+
+    fn marshal_json_into_with(&self, s: &mut String, options: &MarshalOptions) {
+        (**self).marshal_json_into_with(s, options)
+    }
+
+    fn to_element(&self) -> Element {
+        (**self).to_element()
+    }
 }
@@ -2,10 +2,19 @@ pub mod bool;
 pub mod element;
 pub mod f64;
 pub mod i64;
+pub mod lines;
 pub mod list;
 pub mod marshalable;
 pub mod null;
 pub mod object;
+pub mod options;
+pub mod raw_json;
+pub mod sequence;
 pub mod string;
+pub mod writer;
 
+pub use lines::LinesWriter;
 pub use marshalable::Marshalable;
+pub use options::{Escaping, HexCase, MarshalOptions};
+pub use sequence::SequenceWriter;
+pub use writer::{JsonWriter, WriteError};
@@ -1,19 +1,34 @@
-use crate::marshal::Marshalable;
+use crate::{
+    marshal::{MarshalOptions, Marshalable},
+    Element,
+};
 
 impl<T> Marshalable for Option<T>
 where
     T: Marshalable,
 {
     fn marshal_json_into(&self, s: &mut String) {
+        self.marshal_json_into_with(s, &MarshalOptions::default())
+    }
+
+    fn marshal_json_into_with(&self, s: &mut String, options: &MarshalOptions) {
         match self {
-            Some(e) => e.marshal_json_into(s),
+            Some(e) => e.marshal_json_into_with(s, options),
             None => s.push_str("null"),
         }
     }
+
+    fn to_element(&self) -> Element {
+        self.as_ref().map_or(Element::JsonNull, T::to_element)
+    }
 }
 
 impl Marshalable for Option<()> {
     fn marshal_json_into(&self, s: &mut String) {
         s.push_str("null")
     }
+
+    fn to_element(&self) -> Element {
+        Element::JsonNull
+    }
 }
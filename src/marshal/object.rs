@@ -1,25 +1,40 @@
 use std::collections::HashMap;
 
-use crate::marshal::marshalable::Marshalable;
+use crate::{
+    marshal::{string::marshal_str, MarshalOptions, Marshalable},
+    Element,
+};
 
 impl<T> Marshalable for HashMap<String, T>
 where
     T: Marshalable,
 {
     fn marshal_json_into(&self, s: &mut String) {
+        self.marshal_json_into_with(s, &MarshalOptions::default())
+    }
+
+    fn marshal_json_into_with(&self, s: &mut String, options: &MarshalOptions) {
         s.push('{');
         let mut iter = self.iter();
         if let Some((k, e)) = iter.next() {
-            k.marshal_json_into(s);
+            marshal_str(k, s, options);
             s.push_str(": ");
-            e.marshal_json_into(s);
+            e.marshal_json_into_with(s, options);
             for (k, e) in iter {
                 s.push_str(", ");
-                k.marshal_json_into(s);
+                marshal_str(k, s, options);
                 s.push_str(": ");
-                e.marshal_json_into(s);
+                e.marshal_json_into_with(s, options);
             }
         }
         s.push('}');
     }
+
+    fn to_element(&self) -> Element {
+        Element::JsonObject(
+            self.iter()
+                .map(|(k, v)| (k.clone(), v.to_element()))
+                .collect(),
+        )
+    }
 }
@@ -0,0 +1,30 @@
+/// Which characters are escaped when marshaling strings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Escaping {
+    /// Output raw UTF-8, only escaping quotes, backslashes and control
+    /// characters
+    Minimal,
+    /// Also escape '/' and every non-ASCII character, so the output is
+    /// plain ASCII. Control characters without a short escape like "\n" are
+    /// output raw, as they always have been.
+    #[default]
+    Ascii,
+    /// Output raw UTF-8, but also escape '<', '>', '&', U+2028 and U+2029 so
+    /// the output can be embedded in HTML and scripts
+    HtmlSafe,
+}
+
+/// The case of hex digits in "\uXXXX" escapes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HexCase {
+    #[default]
+    Lower,
+    Upper,
+}
+
+/// Options for how values are marshaled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MarshalOptions {
+    pub escaping: Escaping,
+    pub hex_case: HexCase,
+}
@@ -0,0 +1,12 @@
+use crate::{raw_json::RawJson, Element, Marshalable, Unmarshalable};
+
+impl Marshalable for RawJson {
+    fn marshal_json_into(&self, s: &mut String) {
+        s.push_str(&self.0);
+    }
+
+    /// Parses the text, which was validated when the `RawJson` was made
+    fn to_element(&self) -> Element {
+        Element::unmarshal_json(self.0.chars()).expect("RawJson text is valid JSON")
+    }
+}
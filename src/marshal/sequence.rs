@@ -0,0 +1,39 @@
+use std::io::{self, Write};
+
+use crate::{marshal::MarshalOptions, unmarshal::sequence::RECORD_SEPARATOR, Marshalable};
+
+/// Writes values as an RFC 7464 JSON text sequence, each preceded by a record
+/// separator and followed by a newline.
+pub struct SequenceWriter<W: Write> {
+    w: W,
+    buf: String,
+    options: MarshalOptions,
+}
+
+impl<W: Write> SequenceWriter<W> {
+    pub fn new(w: W) -> SequenceWriter<W> {
+        SequenceWriter::with_options(w, MarshalOptions::default())
+    }
+
+    pub fn with_options(w: W, options: MarshalOptions) -> SequenceWriter<W> {
+        SequenceWriter {
+            w,
+            buf: String::new(),
+            options,
+        }
+    }
+
+    pub fn write<T: Marshalable + ?Sized>(&mut self, value: &T) -> io::Result<()> {
+        self.buf.clear();
+        self.buf.push(RECORD_SEPARATOR as char);
+        value.marshal_json_into_with(&mut self.buf, &self.options);
+        self.buf.push('\n');
+        self.w.write_all(self.buf.as_bytes())
+    }
+
+    /// Flushes and returns the inner writer
+    pub fn into_inner(mut self) -> io::Result<W> {
+        self.w.flush()?;
+        Ok(self.w)
+    }
+}
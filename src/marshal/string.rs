@@ -1,37 +1,124 @@
-use crate::marshal::Marshalable;
+use crate::{
+    marshal::{
+        options::{Escaping, HexCase},
+        MarshalOptions, Marshalable,
+    },
+    Element,
+};
 
 impl Marshalable for String {
     fn marshal_json_into(&self, s: &mut String) {
-        s.push('"');
-        for c in self.chars() {
-            match c {
-                needs_escape @ ('\\' | '"' | '/') => {
-                    s.push('\\');
-                    s.push(needs_escape);
-                }
-                '\x08' => s.push_str("\\b"), // Literal backspace
-                '\x0c' => s.push_str("\\f"), // Formfeed
-                '\n' => s.push_str("\\n"),
-                '\r' => s.push_str("\\r"),
-                '\t' => s.push_str("\\t"),
-                _ if !c.is_ascii() => {
-                    // Escape unicode value
-                    let code_point = c as u32;
-                    if code_point < 0xFFFF {
-                        // No surrogates necessary
-                        s.push_str(&format!("\\u{:04x}", code_point));
-                    } else {
-                        // https://datacadamia.com/data/type/text/surrogate#from_character_code_to_surrogate_pair1
-                        const LEAD_OFFSET: u32 = 0xD800 - (0x10000 >> 10);
-                        let lead = LEAD_OFFSET + (code_point >> 10);
-                        let trail = 0xDC00 + (code_point & 0x3FF);
-
-                        s.push_str(&format!("\\u{:04x}\\u{:04x}", lead, trail));
-                    }
+        marshal_str(self, s, &MarshalOptions::default())
+    }
+
+    fn marshal_json_into_with(&self, s: &mut String, options: &MarshalOptions) {
+        marshal_str(self, s, options)
+    }
+
+    fn to_element(&self) -> Element {
+        Element::JsonString(self.to_string())
+    }
+}
+
+impl Marshalable for str {
+    fn marshal_json_into(&self, s: &mut String) {
+        marshal_str(self, s, &MarshalOptions::default())
+    }
+
+    fn marshal_json_into_with(&self, s: &mut String, options: &MarshalOptions) {
+        marshal_str(self, s, options)
+    }
+
+    fn to_element(&self) -> Element {
+        Element::JsonString(self.to_string())
+    }
+}
+
+enum Escape {
+    Str(&'static str),
+    Unicode(char),
+}
+
+pub(crate) fn marshal_str(string: &str, s: &mut String, options: &MarshalOptions) {
+    let ascii = options.escaping == Escaping::Ascii;
+    let html_safe = options.escaping == Escaping::HtmlSafe;
+
+    s.reserve(string.len() + 2);
+    s.push('"');
+    let bytes = string.as_bytes();
+    // Start of the run of characters which don't need escaping
+    let mut start = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let escape = match bytes[i] {
+            b'\\' => Escape::Str("\\\\"),
+            b'"' => Escape::Str("\\\""),
+            b'/' if ascii => Escape::Str("\\/"),
+            b'\x08' => Escape::Str("\\b"), // Literal backspace
+            b'\x0c' => Escape::Str("\\f"), // Formfeed
+            b'\n' => Escape::Str("\\n"),
+            b'\r' => Escape::Str("\\r"),
+            b'\t' => Escape::Str("\\t"),
+            // Other control characters have always been output raw by the
+            // default ASCII escaping, so that's kept for compatibility
+            b @ 0x00..=0x1f if !ascii => Escape::Unicode(b as char),
+            b @ (b'<' | b'>' | b'&') if html_safe => Escape::Unicode(b as char),
+            b if b.is_ascii() => {
+                i += 1;
+                continue;
+            }
+            // The index is always on a character boundary here, since only
+            // ASCII bytes and whole characters are skipped
+            _ => {
+                let c = string[i..].chars().next().unwrap_or_default();
+                if ascii || (html_safe && matches!(c, '\u{2028}' | '\u{2029}')) {
+                    Escape::Unicode(c)
+                } else {
+                    i += c.len_utf8();
+                    continue;
                 }
-                _ => s.push(c),
-            };
+            }
+        };
+        s.push_str(&string[start..i]);
+        match escape {
+            Escape::Str(escape) => {
+                s.push_str(escape);
+                i += 1;
+            }
+            Escape::Unicode(c) => {
+                push_unicode_escape(c as u32, s, options.hex_case);
+                i += c.len_utf8();
+            }
         }
-        s.push('"')
+        start = i;
+    }
+    s.push_str(&string[start..]);
+    s.push('"')
+}
+
+fn push_unicode_escape(code_point: u32, s: &mut String, case: HexCase) {
+    if code_point < 0xFFFF {
+        // No surrogates necessary
+        push_hex_escape(code_point, s, case);
+    } else {
+        // https://datacadamia.com/data/type/text/surrogate#from_character_code_to_surrogate_pair1
+        const LEAD_OFFSET: u32 = 0xD800 - (0x10000 >> 10);
+        let lead = LEAD_OFFSET + (code_point >> 10);
+        let trail = 0xDC00 + (code_point & 0x3FF);
+
+        push_hex_escape(lead, s, case);
+        push_hex_escape(trail, s, case);
+    }
+}
+
+/// Pushes a "\uXXXX" escape without allocating
+fn push_hex_escape(unit: u32, s: &mut String, case: HexCase) {
+    let hex = match case {
+        HexCase::Lower => b"0123456789abcdef",
+        HexCase::Upper => b"0123456789ABCDEF",
+    };
+    s.push_str("\\u");
+    for shift in [12, 8, 4, 0] {
+        s.push(hex[(unit >> shift & 0xF) as usize] as char);
     }
 }
@@ -0,0 +1,166 @@
+use std::io::{self, Write};
+
+use crate::marshal::{string::marshal_str, MarshalOptions, Marshalable};
+
+#[derive(Debug)]
+pub enum WriteError {
+    Io(io::Error),
+    /// A value was written inside an object without a key before it
+    ValueWithoutKey,
+    /// A key was written outside of an object, or after another key
+    UnexpectedKey,
+    /// An end didn't match the innermost open object or array
+    UnbalancedEnd,
+    /// A second root value was written
+    MultipleRoots,
+    /// The writer was finished before the root value was complete
+    Unfinished,
+}
+
+impl From<io::Error> for WriteError {
+    fn from(e: io::Error) -> Self {
+        WriteError::Io(e)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Container {
+    Object,
+    Array,
+}
+
+struct Frame {
+    container: Container,
+    empty: bool,
+    has_key: bool,
+}
+
+/// Incrementally writes a single JSON document to an `io::Write`, for
+/// documents that are too large to build in memory before marshaling.
+pub struct JsonWriter<W: Write> {
+    w: W,
+    stack: Vec<Frame>,
+    root_written: bool,
+    buf: String,
+    options: MarshalOptions,
+}
+
+impl<W: Write> JsonWriter<W> {
+    pub fn new(w: W) -> JsonWriter<W> {
+        JsonWriter::with_options(w, MarshalOptions::default())
+    }
+
+    pub fn with_options(w: W, options: MarshalOptions) -> JsonWriter<W> {
+        JsonWriter {
+            w,
+            stack: Vec::new(),
+            root_written: false,
+            buf: String::new(),
+            options,
+        }
+    }
+
+    pub fn begin_object(&mut self) -> Result<(), WriteError> {
+        self.begin_value()?;
+        self.stack.push(Frame {
+            container: Container::Object,
+            empty: true,
+            has_key: false,
+        });
+        self.w.write_all(b"{")?;
+        Ok(())
+    }
+
+    pub fn end_object(&mut self) -> Result<(), WriteError> {
+        self.end(Container::Object, b"}")
+    }
+
+    pub fn begin_array(&mut self) -> Result<(), WriteError> {
+        self.begin_value()?;
+        self.stack.push(Frame {
+            container: Container::Array,
+            empty: true,
+            has_key: false,
+        });
+        self.w.write_all(b"[")?;
+        Ok(())
+    }
+
+    pub fn end_array(&mut self) -> Result<(), WriteError> {
+        self.end(Container::Array, b"]")
+    }
+
+    /// Writes the key for the next value in the current object
+    pub fn key(&mut self, key: &str) -> Result<(), WriteError> {
+        let frame = match self.stack.last_mut() {
+            Some(frame) if frame.container == Container::Object && !frame.has_key => frame,
+            _ => return Err(WriteError::UnexpectedKey),
+        };
+        self.buf.clear();
+        if !frame.empty {
+            self.buf.push_str(", ");
+        }
+        frame.empty = false;
+        frame.has_key = true;
+        marshal_str(key, &mut self.buf, &self.options);
+        self.buf.push_str(": ");
+        self.w.write_all(self.buf.as_bytes())?;
+        Ok(())
+    }
+
+    /// Writes a complete value as the root, an array element, or the value
+    /// for the last key written
+    pub fn value<T: Marshalable + ?Sized>(&mut self, value: &T) -> Result<(), WriteError> {
+        self.begin_value()?;
+        self.buf.clear();
+        value.marshal_json_into_with(&mut self.buf, &self.options);
+        self.w.write_all(self.buf.as_bytes())?;
+        Ok(())
+    }
+
+    /// Checks that the document is complete and returns the inner writer
+    pub fn finish(mut self) -> Result<W, WriteError> {
+        if !self.root_written || !self.stack.is_empty() {
+            return Err(WriteError::Unfinished);
+        }
+        self.w.flush()?;
+        Ok(self.w)
+    }
+
+    /// Checks that a value may be written, and writes any separator needed
+    /// before it
+    fn begin_value(&mut self) -> Result<(), WriteError> {
+        match self.stack.last_mut() {
+            None if self.root_written => Err(WriteError::MultipleRoots),
+            None => {
+                self.root_written = true;
+                Ok(())
+            }
+            Some(frame) => match frame.container {
+                Container::Object if !frame.has_key => Err(WriteError::ValueWithoutKey),
+                Container::Object => {
+                    frame.has_key = false;
+                    Ok(())
+                }
+                Container::Array => {
+                    if !frame.empty {
+                        self.w.write_all(b", ")?;
+                    }
+                    frame.empty = false;
+                    Ok(())
+                }
+            },
+        }
+    }
+
+    fn end(&mut self, container: Container, close: &[u8]) -> Result<(), WriteError> {
+        match self.stack.last() {
+            Some(frame) if frame.container == container && !frame.has_key => {
+                self.stack.pop();
+                self.w.write_all(close)?;
+                Ok(())
+            }
+            _ => Err(WriteError::UnbalancedEnd),
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use std::mem;
+
+use crate::Element;
+
+/// An error from looking up or changing an `Element` by JSON Pointer.
+/// Pointers in errors are the prefix of the original pointer up to and
+/// including the token that failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PointerError {
+    /// The pointer doesn't start with '/', or has a '~' which isn't followed
+    /// by '0' or '1'
+    Malformed { offset: usize },
+    /// An object has no member with the key, or an array index is past the
+    /// end
+    NotFound { pointer: String },
+    /// A token used on an array isn't an index or '-'
+    InvalidIndex { pointer: String },
+    /// A token was used on a value which isn't an object or array
+    NotContainer { pointer: String },
+    /// The root value can't be removed
+    Root,
+}
+
+/// An unescaped reference token, with the offset of the end of its escaped
+/// form in the pointer
+struct Token {
+    token: String,
+    end: usize,
+}
+
+fn parse(pointer: &str) -> Result<Vec<Token>, PointerError> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+    if !pointer.starts_with('/') {
+        return Err(PointerError::Malformed { offset: 0 });
+    }
+    let mut tokens = Vec::new();
+    let mut offset = 1;
+    for escaped in pointer[1..].split('/') {
+        let mut token = String::with_capacity(escaped.len());
+        let mut chars = escaped.char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '~' => match chars.next() {
+                    Some((_, '0')) => token.push('~'),
+                    Some((_, '1')) => token.push('/'),
+                    _ => return Err(PointerError::Malformed { offset: offset + i }),
+                },
+                c => token.push(c),
+            }
+        }
+        offset += escaped.len();
+        tokens.push(Token { token, end: offset });
+        offset += 1;
+    }
+    Ok(tokens)
+}
+
+/// Parses an array index, which can't have leading zeros. '-' refers to the
+/// index after the last element.
+fn parse_index(token: &str, len: usize) -> Option<usize> {
+    match token {
+        "-" => Some(len),
+        "0" => Some(0),
+        t if !t.starts_with('0') && t.bytes().all(|b| b.is_ascii_digit()) => t.parse().ok(),
+        _ => None,
+    }
+}
+
+/// Appends a reference token to a JSON Pointer, escaping '~' and '/'
+pub(crate) fn push_pointer_token(pointer: &mut String, token: &str) {
+    pointer.push('/');
+    for c in token.chars() {
+        match c {
+            '~' => pointer.push_str("~0"),
+            '/' => pointer.push_str("~1"),
+            _ => pointer.push(c),
+        }
+    }
+}
+
+/// Looks up the child for a token, returning an error for the pointer up to
+/// the token if it's missing
+fn child<'a>(
+    element: &'a Element,
+    token: &Token,
+    pointer: &str,
+) -> Result<&'a Element, PointerError> {
+    let pointer = || pointer[..token.end].to_owned();
+    match element {
+        Element::JsonObject(o) => o.get(&token.token),
+        Element::JsonList(l) => {
+            let i = parse_index(&token.token, l.len())
+                .ok_or_else(|| PointerError::InvalidIndex { pointer: pointer() })?;
+            l.get(i)
+        }
+        _ => return Err(PointerError::NotContainer { pointer: pointer() }),
+    }
+    .ok_or_else(|| PointerError::NotFound { pointer: pointer() })
+}
+
+fn child_mut<'a>(
+    element: &'a mut Element,
+    token: &Token,
+    pointer: &str,
+) -> Result<&'a mut Element, PointerError> {
+    let pointer = || pointer[..token.end].to_owned();
+    match element {
+        Element::JsonObject(o) => o.get_mut(&token.token),
+        Element::JsonList(l) => {
+            let i = parse_index(&token.token, l.len())
+                .ok_or_else(|| PointerError::InvalidIndex { pointer: pointer() })?;
+            l.get_mut(i)
+        }
+        _ => return Err(PointerError::NotContainer { pointer: pointer() }),
+    }
+    .ok_or_else(|| PointerError::NotFound { pointer: pointer() })
+}
+
+impl Element {
+    /// Looks up a value by JSON Pointer, returning `None` if it's missing or
+    /// the pointer is malformed
+    pub fn pointer(&self, pointer: &str) -> Option<&Element> {
+        self.try_pointer(pointer).ok()
+    }
+
+    /// Looks up a value by JSON Pointer, returning `None` if it's missing or
+    /// the pointer is malformed
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut Element> {
+        self.try_pointer_mut(pointer).ok()
+    }
+
+    /// Looks up a value by JSON Pointer, with an error describing why it
+    /// couldn't be found
+    pub fn try_pointer(&self, pointer: &str) -> Result<&Element, PointerError> {
+        parse(pointer)?
+            .iter()
+            .try_fold(self, |element, token| child(element, token, pointer))
+    }
+
+    pub fn try_pointer_mut(&mut self, pointer: &str) -> Result<&mut Element, PointerError> {
+        parse(pointer)?
+            .iter()
+            .try_fold(self, |element, token| child_mut(element, token, pointer))
+    }
+
+    /// Adds a value at a JSON Pointer like a JSON Patch "add". Object
+    /// members are inserted or replaced, returning the old value, and array
+    /// elements are inserted before the index, or appended for '-'.
+    pub fn pointer_insert(
+        &mut self,
+        pointer: &str,
+        value: Element,
+    ) -> Result<Option<Element>, PointerError> {
+        let mut tokens = parse(pointer)?;
+        let Some(last) = tokens.pop() else {
+            return Ok(Some(mem::replace(self, value)));
+        };
+        let parent = tokens
+            .iter()
+            .try_fold(self, |element, token| child_mut(element, token, pointer))?;
+        match parent {
+            Element::JsonObject(o) => Ok(o.insert(last.token, value)),
+            Element::JsonList(l) => match parse_index(&last.token, l.len()) {
+                Some(i) if i <= l.len() => {
+                    l.insert(i, value);
+                    Ok(None)
+                }
+                Some(_) => Err(PointerError::NotFound {
+                    pointer: pointer.to_owned(),
+                }),
+                None => Err(PointerError::InvalidIndex {
+                    pointer: pointer.to_owned(),
+                }),
+            },
+            _ => Err(PointerError::NotContainer {
+                pointer: pointer.to_owned(),
+            }),
+        }
+    }
+
+    /// Replaces an existing value at a JSON Pointer, returning the old value
+    pub fn pointer_replace(
+        &mut self,
+        pointer: &str,
+        value: Element,
+    ) -> Result<Element, PointerError> {
+        Ok(mem::replace(self.try_pointer_mut(pointer)?, value))
+    }
+
+    /// Removes the value at a JSON Pointer, returning it. Later array
+    /// elements are shifted down.
+    pub fn pointer_remove(&mut self, pointer: &str) -> Result<Element, PointerError> {
+        let mut tokens = parse(pointer)?;
+        let last = tokens.pop().ok_or(PointerError::Root)?;
+        let parent = tokens
+            .iter()
+            .try_fold(self, |element, token| child_mut(element, token, pointer))?;
+        // Check that the value exists, and that the token is valid
+        child(parent, &last, pointer)?;
+        Ok(match parent {
+            Element::JsonObject(o) => o.remove(&last.token),
+            Element::JsonList(l) => parse_index(&last.token, l.len()).map(|i| l.remove(i)),
+            _ => None,
+        }
+        .expect("the value was found"))
+    }
+}
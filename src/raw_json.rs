@@ -0,0 +1,36 @@
+use crate::{UnmarshalError, Unmarshalable};
+
+/// The unchanged source text of a single JSON value, which is validated when
+/// it's unmarshaled but can be parsed into another type later. It's
+/// marshaled verbatim, ignoring any marshal options.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawJson(pub(crate) String);
+
+impl RawJson {
+    /// Checks that the text is a single valid value, trimming any
+    /// surrounding whitespace
+    pub fn new(json: &str) -> Result<RawJson, UnmarshalError> {
+        RawJson::unmarshal_json(json.chars())
+    }
+
+    /// Wraps text which the caller has already validated, for `json-macros`
+    /// expansions of files checked at compile time. Converting invalid text
+    /// to an element panics.
+    #[doc(hidden)]
+    pub fn new_unchecked(json: String) -> RawJson {
+        RawJson(json)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn into_string(self) -> String {
+        self.0
+    }
+
+    /// Unmarshals the text into another type
+    pub fn parse<T: Unmarshalable>(&self) -> Result<T, UnmarshalError> {
+        T::unmarshal_json(self.0.chars())
+    }
+}
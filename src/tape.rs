@@ -0,0 +1,291 @@
+use std::{collections::HashMap, str::Chars};
+
+use crate::{
+    unmarshal::{
+        list::unmarshal_items, object::unmarshal_members, string::unmarshal_cow, Number, Source,
+        UnmarshalIter, ValueKind,
+    },
+    Element, UnmarshalError,
+};
+
+/// A node on the tape. Objects and arrays are followed by their children,
+/// with each object member stored as a key string followed by its value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Node {
+    /// `end` is the index of the node after the last child
+    Object {
+        len: u32,
+        end: u32,
+    },
+    Array {
+        len: u32,
+        end: u32,
+    },
+    /// A range of the string arena
+    String {
+        start: u32,
+        len: u32,
+    },
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Null,
+}
+
+/// A read-only document stored as a flat tape of nodes, with every string
+/// stored in a single arena. Uses much less memory than an `Element`, and
+/// is navigated with a `Cursor`.
+#[derive(Debug, PartialEq)]
+pub struct TapeDocument {
+    nodes: Vec<Node>,
+    strings: String,
+}
+
+impl TapeDocument {
+    pub fn unmarshal_json(chars: Chars) -> Result<TapeDocument, UnmarshalError> {
+        let mut u = UnmarshalIter::new(chars);
+        let mut doc = TapeDocument {
+            nodes: Vec::new(),
+            strings: String::new(),
+        };
+        doc.unmarshal_node(&mut u)?;
+        u.check_finished()?;
+        doc.nodes.shrink_to_fit();
+        doc.strings.shrink_to_fit();
+        Ok(doc)
+    }
+
+    pub fn root(&self) -> Cursor<'_> {
+        Cursor {
+            doc: self,
+            index: 0,
+        }
+    }
+
+    pub fn to_element(&self) -> Element {
+        self.root().to_element()
+    }
+
+    fn unmarshal_node(&mut self, u: &mut UnmarshalIter) -> Result<(), UnmarshalError> {
+        match u.peek_kind() {
+            Some(ValueKind::Object) => {
+                let index = self.nodes.len();
+                self.nodes.push(Node::Null);
+                let mut len = 0usize;
+                unmarshal_members(u, unmarshal_cow, |u, key, _| {
+                    self.push_string(&key)?;
+                    self.unmarshal_node(u)?;
+                    len += 1;
+                    Ok(())
+                })?;
+                self.nodes[index] = Node::Object {
+                    len: tape_u32(len)?,
+                    end: tape_u32(self.nodes.len())?,
+                };
+            }
+            Some(ValueKind::Array) => {
+                let index = self.nodes.len();
+                self.nodes.push(Node::Null);
+                let mut len = 0usize;
+                unmarshal_items(u, |u| {
+                    self.unmarshal_node(u)?;
+                    len += 1;
+                    Ok(())
+                })?;
+                self.nodes[index] = Node::Array {
+                    len: tape_u32(len)?,
+                    end: tape_u32(self.nodes.len())?,
+                };
+            }
+            Some(ValueKind::String) => self.push_string(&unmarshal_cow(u)?)?,
+            Some(ValueKind::Number) => {
+                let node = match u.read_number()? {
+                    Number::Int(i) => Node::Int(i),
+                    Number::Float(f) => Node::Float(f),
+                };
+                self.nodes.push(node);
+            }
+            Some(ValueKind::Bool) => {
+                let b = u.read_bool()?;
+                self.nodes.push(Node::Bool(b));
+            }
+            Some(ValueKind::Null) => {
+                u.read_null()?;
+                self.nodes.push(Node::Null);
+            }
+            None => return Err(u.unexpected_value("a value")),
+        }
+        Ok(())
+    }
+
+    fn push_string(&mut self, s: &str) -> Result<(), UnmarshalError> {
+        // Strings are sliced up to start + len, so that must fit too
+        tape_u32(self.strings.len() + s.len())?;
+        self.nodes.push(Node::String {
+            start: tape_u32(self.strings.len())?,
+            len: tape_u32(s.len())?,
+        });
+        self.strings.push_str(s);
+        Ok(())
+    }
+}
+
+/// Converts an index or length to the size stored on the tape, which limits
+/// documents to 4 GiB of strings and 2^32 nodes
+fn tape_u32(n: usize) -> Result<u32, UnmarshalError> {
+    u32::try_from(n).map_err(|_| UnmarshalError::TooLarge)
+}
+
+/// A reference to a value in a `TapeDocument`.
+#[derive(Debug, Clone, Copy)]
+pub struct Cursor<'a> {
+    doc: &'a TapeDocument,
+    index: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn node(&self) -> Node {
+        self.doc.nodes[self.index]
+    }
+
+    /// Returns the index of the node after this value and its children
+    fn end(&self) -> usize {
+        match self.node() {
+            Node::Object { end, .. } | Node::Array { end, .. } => end as usize,
+            _ => self.index + 1,
+        }
+    }
+
+    fn at(&self, index: usize) -> Cursor<'a> {
+        Cursor {
+            doc: self.doc,
+            index,
+        }
+    }
+
+    pub fn is_object(&self) -> bool {
+        matches!(self.node(), Node::Object { .. })
+    }
+
+    pub fn is_array(&self) -> bool {
+        matches!(self.node(), Node::Array { .. })
+    }
+
+    pub fn is_null(&self) -> bool {
+        self.node() == Node::Null
+    }
+
+    pub fn as_str(&self) -> Option<&'a str> {
+        match self.node() {
+            Node::String { start, len } => {
+                Some(&self.doc.strings[start as usize..(start + len) as usize])
+            }
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self.node() {
+            Node::Int(i) => Some(i),
+            _ => None,
+        }
+    }
+
+    /// Returns floats, or ints converted to floats
+    pub fn as_f64(&self) -> Option<f64> {
+        match self.node() {
+            Node::Float(f) => Some(f),
+            Node::Int(i) => Some(i as f64),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self.node() {
+            Node::Bool(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of members or elements of an object or array
+    pub fn len(&self) -> Option<usize> {
+        match self.node() {
+            Node::Object { len, .. } | Node::Array { len, .. } => Some(len as usize),
+            _ => None,
+        }
+    }
+
+    /// Returns whether an object or array has no members or elements
+    pub fn is_empty(&self) -> Option<bool> {
+        self.len().map(|len| len == 0)
+    }
+
+    /// Looks up the value for a key in an object. If the key appears more
+    /// than once the last value is returned, like when unmarshaling a
+    /// `HashMap`.
+    pub fn get(&self, key: &str) -> Option<Cursor<'a>> {
+        self.members()
+            .filter(|&(k, _)| k == key)
+            .last()
+            .map(|(_, v)| v)
+    }
+
+    /// Returns the element at an index of an array
+    pub fn index(&self, i: usize) -> Option<Cursor<'a>> {
+        self.elements().nth(i)
+    }
+
+    /// Iterates over the members of an object, or nothing for other values
+    pub fn members(&self) -> impl Iterator<Item = (&'a str, Cursor<'a>)> {
+        let mut next = self.index + 1;
+        let end = if self.is_object() { self.end() } else { next };
+        let cursor = *self;
+        std::iter::from_fn(move || {
+            if next >= end {
+                return None;
+            }
+            let key = cursor.at(next);
+            let value = cursor.at(next + 1);
+            next = value.end();
+            Some((key.as_str().unwrap_or_default(), value))
+        })
+    }
+
+    /// Iterates over the elements of an array, or nothing for other values
+    pub fn elements(&self) -> impl Iterator<Item = Cursor<'a>> {
+        let mut next = self.index + 1;
+        let end = if self.is_array() { self.end() } else { next };
+        let cursor = *self;
+        std::iter::from_fn(move || {
+            if next >= end {
+                return None;
+            }
+            let element = cursor.at(next);
+            next = element.end();
+            Some(element)
+        })
+    }
+
+    /// Copies the value and its children into an `Element`
+    pub fn to_element(&self) -> Element {
+        match self.node() {
+            Node::Object { len, .. } => {
+                let mut object = HashMap::with_capacity(len as usize);
+                for (k, v) in self.members() {
+                    object.insert(k.to_owned(), v.to_element());
+                }
+                Element::JsonObject(object)
+            }
+            Node::Array { .. } => {
+                Element::JsonList(self.elements().map(|e| e.to_element()).collect())
+            }
+            Node::String { .. } => {
+                Element::JsonString(self.as_str().unwrap_or_default().to_owned())
+            }
+            Node::Int(i) => Element::JsonInt(i),
+            Node::Float(f) => Element::JsonFloat(f),
+            Node::Bool(b) => Element::JsonBool(b),
+            Node::Null => Element::JsonNull,
+        }
+    }
+}
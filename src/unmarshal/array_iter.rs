@@ -0,0 +1,133 @@
+use std::{io::Read, marker::PhantomData};
+
+use crate::Unmarshalable;
+
+use super::{byte_reader::ByteReader, unmarshalable::UnmarshalError};
+
+#[derive(Clone, Copy, PartialEq)]
+enum State {
+    /// The path to the array hasn't been followed yet
+    Start,
+    FirstElement,
+    Element,
+    Finished,
+}
+
+/// Lazily unmarshals the elements of a JSON array read from an `io::Read`.
+/// Only one element is buffered in memory at a time.
+pub struct ArrayIter<R: Read, T> {
+    r: ByteReader<R>,
+    path: Vec<String>,
+    /// Whether the array is the root, so nothing may follow it
+    root: bool,
+    buf: Vec<u8>,
+    state: State,
+    _marker: PhantomData<T>,
+}
+
+impl<R: Read, T: Unmarshalable> ArrayIter<R, T> {
+    /// Iterates over the array which is the root of the input
+    pub fn new(reader: R) -> ArrayIter<R, T> {
+        ArrayIter::at_path(reader, &[])
+    }
+
+    /// Iterates over the array found by following a path of keys through
+    /// nested objects. Anything after the array is not read.
+    pub fn at_path(reader: R, path: &[&str]) -> ArrayIter<R, T> {
+        ArrayIter {
+            r: ByteReader::new(reader),
+            path: path.iter().map(|&k| k.to_owned()).collect(),
+            root: path.is_empty(),
+            buf: Vec::new(),
+            state: State::Start,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Reads up to and including the opening bracket of the array
+    fn find_array(&mut self) -> Result<(), UnmarshalError> {
+        for key in std::mem::take(&mut self.path) {
+            self.find_key(&key)?;
+        }
+        match self.r.next_non_whitespace()? {
+            Some(b'[') => Ok(()),
+            unexpected => Err(self.r.unexpected(unexpected)),
+        }
+    }
+
+    /// Reads the members of an object until the value for the key is next
+    fn find_key(&mut self, key: &str) -> Result<(), UnmarshalError> {
+        match self.r.next_non_whitespace()? {
+            Some(b'{') => (),
+            unexpected => return Err(self.r.unexpected(unexpected)),
+        }
+        if self.r.peek_non_whitespace()? == Some(b'}') {
+            return Err(UnmarshalError::KeyNotFound(key.to_owned()));
+        }
+        loop {
+            let k: String = self.r.unmarshal_value(&mut self.buf)?;
+            match self.r.next_non_whitespace()? {
+                Some(b':') => (),
+                unexpected => return Err(self.r.unexpected(unexpected)),
+            }
+            if k == key {
+                return Ok(());
+            }
+
+            self.r.peek_non_whitespace()?;
+            self.r.skip_value()?;
+            match self.r.next_non_whitespace()? {
+                Some(b',') => (),
+                Some(b'}') => return Err(UnmarshalError::KeyNotFound(key.to_owned())),
+                unexpected => return Err(self.r.unexpected(unexpected)),
+            }
+        }
+    }
+
+    fn next_element(&mut self) -> Result<Option<T>, UnmarshalError> {
+        match self.state {
+            State::Start => {
+                self.find_array()?;
+                self.state = State::FirstElement;
+                if self.r.peek_non_whitespace()? == Some(b']') {
+                    self.r.next()?;
+                    return self.end();
+                }
+            }
+            State::Element => match self.r.next_non_whitespace()? {
+                Some(b',') => (),
+                Some(b']') => return self.end(),
+                unexpected => return Err(self.r.unexpected(unexpected)),
+            },
+            State::FirstElement => (),
+            State::Finished => return Ok(None),
+        }
+        let element = self.r.unmarshal_value(&mut self.buf)?;
+        self.state = State::Element;
+        Ok(Some(element))
+    }
+
+    /// Finishes after the closing bracket, checking for trailing characters
+    /// if the array was the root
+    fn end(&mut self) -> Result<Option<T>, UnmarshalError> {
+        self.state = State::Finished;
+        if self.root {
+            if let Some(b) = self.r.next_non_whitespace()? {
+                return Err(self.r.unexpected(Some(b)));
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl<R: Read, T: Unmarshalable> Iterator for ArrayIter<R, T> {
+    type Item = Result<T, UnmarshalError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let element = self.next_element();
+        if element.is_err() {
+            self.state = State::Finished;
+        }
+        element.transpose()
+    }
+}
@@ -0,0 +1,156 @@
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+    hash::Hash,
+};
+
+use crate::{BorrowedElement, Element, Unmarshalable};
+
+use super::{
+    list::unmarshal_items, object::unmarshal_members, string::unmarshal_cow,
+    unmarshal_iter::UnmarshalIter, unmarshalable::UnmarshalError,
+};
+
+/// Like `Unmarshalable`, but for types which can borrow from the input, such
+/// as `&'a str` and `Cow<'a, str>`.
+pub trait UnmarshalableBorrowed<'a>: Sized {
+    /// Unmarshals the type using characters provided from the unmarshaler.
+    /// Should exhaust all characters necessary to unmarshal the type, and
+    /// leave any remaining characters to allow for nested unmarshaling.
+    fn unmarshal_json_borrowed_with_state(
+        u: &mut UnmarshalIter<'a>,
+    ) -> Result<Self, UnmarshalError>;
+
+    fn unmarshal_json_borrowed(s: &'a str) -> Result<Self, UnmarshalError> {
+        let mut u = UnmarshalIter::new(s.chars());
+        let unmarshaled = Self::unmarshal_json_borrowed_with_state(&mut u)?;
+        u.check_finished()?;
+        Ok(unmarshaled)
+    }
+}
+
+/// Implements `UnmarshalableBorrowed` for types which never borrow
+macro_rules! unmarshalable_owned {
+    ($($t:ty),*) => {
+        $(
+            impl<'a> UnmarshalableBorrowed<'a> for $t {
+                fn unmarshal_json_borrowed_with_state(
+                    u: &mut UnmarshalIter<'a>,
+                ) -> Result<Self, UnmarshalError> {
+                    <$t>::unmarshal_json_with_state(u)
+                }
+            }
+        )*
+    };
+}
+
+unmarshalable_owned!(bool, i64, f64, String, Element, Option<()>);
+
+impl<'a> UnmarshalableBorrowed<'a> for Cow<'a, str> {
+    fn unmarshal_json_borrowed_with_state(
+        u: &mut UnmarshalIter<'a>,
+    ) -> Result<Self, UnmarshalError> {
+        unmarshal_cow(u)
+    }
+}
+
+/// Borrowed strings can't be unescaped, so strings with escapes return an
+/// `UnmarshalError::EscapedBorrow`. Use `Cow<'a, str>` to allow escapes.
+impl<'a> UnmarshalableBorrowed<'a> for &'a str {
+    fn unmarshal_json_borrowed_with_state(
+        u: &mut UnmarshalIter<'a>,
+    ) -> Result<Self, UnmarshalError> {
+        u.peek_non_whitespace();
+        let start = u.position_at(u.offset());
+        match unmarshal_cow(u)? {
+            Cow::Borrowed(s) => Ok(s),
+            Cow::Owned(_) => Err(UnmarshalError::EscapedBorrow {
+                row: start.row,
+                col: start.col,
+            }),
+        }
+    }
+}
+
+impl<'a, T: UnmarshalableBorrowed<'a>> UnmarshalableBorrowed<'a> for Option<T> {
+    fn unmarshal_json_borrowed_with_state(
+        u: &mut UnmarshalIter<'a>,
+    ) -> Result<Self, UnmarshalError> {
+        if u.peek_non_whitespace() == Some(&'n') {
+            Option::<()>::unmarshal_json_with_state(u).map(|_| None)
+        } else {
+            T::unmarshal_json_borrowed_with_state(u).map(Some)
+        }
+    }
+}
+
+impl<'a, T: UnmarshalableBorrowed<'a>> UnmarshalableBorrowed<'a> for Vec<T> {
+    fn unmarshal_json_borrowed_with_state(
+        u: &mut UnmarshalIter<'a>,
+    ) -> Result<Self, UnmarshalError> {
+        let mut elements = vec![];
+        unmarshal_items(u, |u| {
+            elements.push(T::unmarshal_json_borrowed_with_state(u)?);
+            Ok(())
+        })?;
+        Ok(elements)
+    }
+}
+
+impl<'a, K, V> UnmarshalableBorrowed<'a> for HashMap<K, V>
+where
+    K: UnmarshalableBorrowed<'a> + AsRef<str> + Eq + Hash,
+    V: UnmarshalableBorrowed<'a>,
+{
+    fn unmarshal_json_borrowed_with_state(
+        u: &mut UnmarshalIter<'a>,
+    ) -> Result<Self, UnmarshalError> {
+        let mut object = HashMap::new();
+        unmarshal_members(u, K::unmarshal_json_borrowed_with_state, |u, k, _| {
+            object.insert(k, V::unmarshal_json_borrowed_with_state(u)?);
+            Ok(())
+        })?;
+        Ok(object)
+    }
+}
+
+impl<'a, K, V> UnmarshalableBorrowed<'a> for BTreeMap<K, V>
+where
+    K: UnmarshalableBorrowed<'a> + AsRef<str> + Ord,
+    V: UnmarshalableBorrowed<'a>,
+{
+    fn unmarshal_json_borrowed_with_state(
+        u: &mut UnmarshalIter<'a>,
+    ) -> Result<Self, UnmarshalError> {
+        let mut object = BTreeMap::new();
+        unmarshal_members(u, K::unmarshal_json_borrowed_with_state, |u, k, _| {
+            object.insert(k, V::unmarshal_json_borrowed_with_state(u)?);
+            Ok(())
+        })?;
+        Ok(object)
+    }
+}
+
+impl<'a> UnmarshalableBorrowed<'a> for BorrowedElement<'a> {
+    fn unmarshal_json_borrowed_with_state(
+        u: &mut UnmarshalIter<'a>,
+    ) -> Result<Self, UnmarshalError> {
+        match u.peek_non_whitespace() {
+            Some('{') => {
+                HashMap::unmarshal_json_borrowed_with_state(u).map(BorrowedElement::JsonObject)
+            }
+            Some('[') => Vec::unmarshal_json_borrowed_with_state(u).map(BorrowedElement::JsonList),
+            Some('"') => unmarshal_cow(u).map(BorrowedElement::JsonString),
+            _ => Ok(match Element::unmarshal_json_with_state(u)? {
+                Element::JsonInt(i) => BorrowedElement::JsonInt(i),
+                Element::JsonFloat(f) => BorrowedElement::JsonFloat(f),
+                Element::JsonBool(b) => BorrowedElement::JsonBool(b),
+                Element::JsonNull => BorrowedElement::JsonNull,
+                // Objects, arrays and strings are handled by the cases above
+                Element::JsonObject(_) | Element::JsonList(_) | Element::JsonString(_) => {
+                    unreachable!()
+                }
+            }),
+        }
+    }
+}
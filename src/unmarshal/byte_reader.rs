@@ -0,0 +1,169 @@
+use std::io::{BufReader, Bytes, Read};
+
+use crate::Unmarshalable;
+
+use super::{
+    scanner::{Scan, ValueScanner},
+    unmarshalable::UnmarshalError,
+};
+
+/// Reads bytes from an `io::Read` while keeping track of the row and column
+/// for errors, like `UnmarshalIter` does for characters.
+pub(crate) struct ByteReader<R: Read> {
+    bytes: Bytes<BufReader<R>>,
+    peeked: Option<u8>,
+    offset: usize,
+    row: u32,
+    col: u32,
+}
+
+impl<R: Read> ByteReader<R> {
+    pub fn new(reader: R) -> ByteReader<R> {
+        ByteReader {
+            bytes: BufReader::new(reader).bytes(),
+            peeked: None,
+            offset: 0,
+            row: 1,
+            col: 0,
+        }
+    }
+
+    pub fn peek(&mut self) -> Result<Option<u8>, UnmarshalError> {
+        if self.peeked.is_none() {
+            self.peeked = self.bytes.next().transpose()?;
+        }
+        Ok(self.peeked)
+    }
+
+    pub fn next(&mut self) -> Result<Option<u8>, UnmarshalError> {
+        let b = self.peek()?;
+        self.peeked = None;
+        if b.is_some() {
+            self.offset += 1;
+        }
+        match b {
+            Some(b'\n') => {
+                self.row += 1;
+                self.col = 0;
+            }
+            // Only count the first byte of each character
+            Some(b) if b & 0xc0 != 0x80 => self.col += 1,
+            _ => (),
+        }
+        Ok(b)
+    }
+
+    /// Skips whitespace and returns the next byte without consuming it
+    pub fn peek_non_whitespace(&mut self) -> Result<Option<u8>, UnmarshalError> {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek()? {
+            self.next()?;
+        }
+        self.peek()
+    }
+
+    pub fn next_non_whitespace(&mut self) -> Result<Option<u8>, UnmarshalError> {
+        self.peek_non_whitespace()?;
+        self.next()
+    }
+
+    /// Returns the number of bytes read so far
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the row and the column of the next character
+    pub fn position(&self) -> (u32, u32) {
+        (self.row, self.col + 1)
+    }
+
+    /// Returns an error for some unexpected byte that was read
+    pub fn unexpected(&self, got: Option<u8>) -> UnmarshalError {
+        match got {
+            Some(b) => UnmarshalError::UnexpectedChar {
+                c: if b.is_ascii() {
+                    b as char
+                } else {
+                    char::REPLACEMENT_CHARACTER
+                },
+                row: self.row,
+                col: self.col,
+            },
+            None => UnmarshalError::EndOfChars,
+        }
+    }
+
+    /// Appends the bytes of the next value to the buffer, leaving any bytes
+    /// after it unread
+    pub fn read_value(&mut self, buf: &mut Vec<u8>) -> Result<(), UnmarshalError> {
+        self.scan_value(|b| buf.push(b))
+    }
+
+    /// Reads past the next value without buffering it
+    pub fn skip_value(&mut self) -> Result<(), UnmarshalError> {
+        self.scan_value(|_| ())
+    }
+
+    /// Reads the bytes of the next value, passing each one to f
+    fn scan_value(&mut self, mut f: impl FnMut(u8)) -> Result<(), UnmarshalError> {
+        let mut scanner = ValueScanner::new();
+        loop {
+            let b = match self.peek()? {
+                Some(b) => b,
+                None if scanner.finish() => return Ok(()),
+                None => return Err(UnmarshalError::EndOfChars),
+            };
+            match scanner.push(b) {
+                Ok(Scan::Continue) => f(b),
+                Ok(Scan::Complete) => {
+                    self.next()?;
+                    f(b);
+                    return Ok(());
+                }
+                Ok(Scan::Ended) => return Ok(()),
+                Err(()) => {
+                    self.next()?;
+                    return Err(self.unexpected(Some(b)));
+                }
+            }
+            self.next()?;
+        }
+    }
+
+    /// Reads and unmarshals the next value
+    pub fn unmarshal_value<T: Unmarshalable>(
+        &mut self,
+        buf: &mut Vec<u8>,
+    ) -> Result<T, UnmarshalError> {
+        self.peek_non_whitespace()?;
+        let start = self.position();
+        buf.clear();
+        self.read_value(buf)?;
+        unmarshal_bytes(buf, start)
+    }
+}
+
+/// Unmarshals a value from bytes which started at the given row and column
+/// of a larger input. Error positions are made relative to that input.
+pub(crate) fn unmarshal_bytes<T: Unmarshalable>(
+    bytes: &[u8],
+    (start_row, start_col): (u32, u32),
+) -> Result<T, UnmarshalError> {
+    let offset = |row: u32, col: u32| {
+        if row == 1 {
+            (start_row, start_col + col - 1)
+        } else {
+            (start_row + row - 1, col)
+        }
+    };
+    T::unmarshal_json_bytes(bytes).map_err(|e| match e {
+        UnmarshalError::UnexpectedChar { c, row, col } => {
+            let (row, col) = offset(row, col);
+            UnmarshalError::UnexpectedChar { c, row, col }
+        }
+        UnmarshalError::InvalidUtf8 { row, col } => {
+            let (row, col) = offset(row, col);
+            UnmarshalError::InvalidUtf8 { row, col }
+        }
+        e => e,
+    })
+}
@@ -1,36 +1,61 @@
-use std::collections::HashMap;
+use std::{borrow::Cow, collections::HashMap, str::FromStr};
 
 use crate::*;
 
 use super::{
-    f64::unmarshal_float_from_int, unmarshal_iter::UnmarshalIter, unmarshalable::UnmarshalError,
+    events::Number,
+    f64::unmarshal_float_from_int,
+    i64::unmarshal_i64,
+    source::{Source, ValueKind},
+    unmarshal_iter::UnmarshalIter,
+    unmarshalable::{FromElementError, UnmarshalError},
 };
 
 impl Unmarshalable for Element {
-    fn unmarshal_json_with_state(u: &mut UnmarshalIter) -> Result<Self, UnmarshalError> {
-        // The peeked character must be cloned so u doesn't remain mutably
-        // borrowed when attempting to report errors
-        match u.peek_non_whitespace().cloned() {
-            Some('{') => Ok(Element::JsonObject(
-                HashMap::<String, Element>::unmarshal_json_with_state(u)?,
-            )),
-            Some('[') => Vec::<Element>::unmarshal_json_with_state(u).map(Element::JsonList),
-            Some('"') => String::unmarshal_json_with_state(u).map(Element::JsonString),
-            Some('n') => Option::<()>::unmarshal_json_with_state(u).and(Ok(Element::JsonNull)),
-            Some('t' | 'f') => bool::unmarshal_json_with_state(u).map(Element::JsonBool),
-            Some(num_start @ ('-' | '0'..='9')) => {
-                // Attempts to read the number as an int, but returns a float
-                // if a decimal or exponent is found.
-                let i = i64::unmarshal_json_with_state(u)?;
-                match u.peek().cloned() {
-                    Some('.' | 'e' | 'E') => {
-                        let sign = if num_start == '-' { -1.0 } else { 1.0 };
-                        unmarshal_float_from_int(i, sign, u).map(Element::JsonFloat)
-                    }
-                    _ => Ok(Element::JsonInt(i)),
-                }
+    fn unmarshal_json_with_state<S: Source>(u: &mut S) -> Result<Self, S::Error> {
+        match u.peek_kind() {
+            Some(ValueKind::Object) => {
+                HashMap::<String, Element>::unmarshal_json_with_state(u).map(Element::JsonObject)
             }
-            unexpected => Err(u.unexpected(unexpected)),
+            Some(ValueKind::Array) => {
+                Vec::<Element>::unmarshal_json_with_state(u).map(Element::JsonList)
+            }
+            Some(ValueKind::String) => u.read_string().map(Element::JsonString),
+            Some(ValueKind::Number) => u.read_number().map(|n| match n {
+                Number::Int(i) => Element::JsonInt(i),
+                Number::Float(f) => Element::JsonFloat(f),
+            }),
+            Some(ValueKind::Bool) => u.read_bool().map(Element::JsonBool),
+            Some(ValueKind::Null) => u.read_null().map(|()| Element::JsonNull),
+            None => Err(u.unexpected_value("a value")),
         }
     }
+
+    /// Moves or clones the element as a whole
+    fn from_element_cow(element: Cow<Element>) -> Result<Self, FromElementError> {
+        Ok(element.into_owned())
+    }
+}
+
+/// Attempts to read the number as an int, but returns a float if a decimal
+/// or exponent is found
+pub(crate) fn unmarshal_number(u: &mut UnmarshalIter) -> Result<Number, UnmarshalError> {
+    let sign = if u.peek_non_whitespace() == Some(&'-') {
+        -1.0
+    } else {
+        1.0
+    };
+    let i = unmarshal_i64(u)?;
+    match u.peek() {
+        Some('.' | 'e' | 'E') => unmarshal_float_from_int(i, sign, u).map(Number::Float),
+        _ => Ok(Number::Int(i)),
+    }
+}
+
+impl FromStr for Element {
+    type Err = UnmarshalError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Element::unmarshal_json(s.chars())
+    }
 }
@@ -0,0 +1,212 @@
+use std::str::Chars;
+
+use crate::{Element, Unmarshalable};
+
+use super::{
+    unmarshal_iter::{Position, UnmarshalIter},
+    unmarshalable::UnmarshalError,
+};
+
+/// A JSON number, kept as an int unless it has a decimal or exponent.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Number {
+    Int(i64),
+    Float(f64),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EventKind {
+    StartObject,
+    Key(String),
+    StartArray,
+    String(String),
+    Number(Number),
+    Bool(bool),
+    Null,
+    EndArray,
+    EndObject,
+}
+
+/// A token read by an `EventIter`, along with the position it started at.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    pub kind: EventKind,
+    pub position: Position,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Container {
+    Object,
+    Array,
+}
+
+/// What the iterator expects to read next.
+#[derive(Clone, Copy, PartialEq)]
+enum Expect {
+    Value,
+    FirstValueOrEnd,
+    Key,
+    FirstKeyOrEnd,
+    CommaOrEnd,
+    /// The root value has been read, only whitespace may remain
+    Done,
+    /// The iterator returned an error or reached the end of the input
+    Finished,
+}
+
+/// A streaming tokenizer which yields the events of a single JSON document
+/// without building it in memory.
+pub struct EventIter<'a> {
+    u: UnmarshalIter<'a>,
+    stack: Vec<Container>,
+    expect: Expect,
+}
+
+impl<'a> EventIter<'a> {
+    pub fn new(chars: Chars) -> EventIter {
+        EventIter {
+            u: UnmarshalIter::new(chars),
+            stack: Vec::new(),
+            expect: Expect::Value,
+        }
+    }
+
+    /// Skips the value that the next event would have started, including all
+    /// of its children if it is an object or array. If a key, comma or the
+    /// end of a container is next instead, an error is returned without
+    /// reading it, so iteration can continue.
+    pub fn skip_value(&mut self) -> Result<(), UnmarshalError> {
+        match self.expect {
+            Expect::Value | Expect::FirstValueOrEnd
+                if self.u.peek_non_whitespace() != Some(&']') =>
+            {
+                if let Err(e) = self.u.skip_value() {
+                    self.expect = Expect::Finished;
+                    return Err(e);
+                }
+                self.expect = self.after_value();
+                Ok(())
+            }
+            Expect::Done | Expect::Finished => Err(UnmarshalError::EndOfChars),
+            _ => Err(self.unexpected_next()),
+        }
+    }
+
+    /// Returns an error for the next character without consuming it
+    fn unexpected_next(&mut self) -> UnmarshalError {
+        match self.u.peek_non_whitespace().cloned() {
+            Some(c) => {
+                let position = self.u.position();
+                UnmarshalError::UnexpectedChar {
+                    c,
+                    row: position.row,
+                    col: position.col,
+                }
+            }
+            None => self.u.end_of_chars(),
+        }
+    }
+
+    fn after_value(&self) -> Expect {
+        if self.stack.is_empty() {
+            Expect::Done
+        } else {
+            Expect::CommaOrEnd
+        }
+    }
+
+    fn end_container(&mut self, position: Position) -> Event {
+        let kind = match self.stack.pop() {
+            Some(Container::Object) => EventKind::EndObject,
+            _ => EventKind::EndArray,
+        };
+        self.expect = self.after_value();
+        Event { kind, position }
+    }
+
+    fn next_event(&mut self) -> Result<Option<Event>, UnmarshalError> {
+        loop {
+            let peeked = self.u.peek_non_whitespace().cloned();
+            let position = self.u.position();
+            match (self.expect, peeked) {
+                (Expect::Finished, _) => return Ok(None),
+                (Expect::Done, _) => {
+                    self.expect = Expect::Finished;
+                    self.u.check_finished()?;
+                    return Ok(None);
+                }
+                (Expect::FirstValueOrEnd, Some(']')) | (Expect::FirstKeyOrEnd, Some('}')) => {
+                    self.u.next();
+                    return Ok(Some(self.end_container(position)));
+                }
+                (Expect::CommaOrEnd, _) => {
+                    let c = self.u.next();
+                    match (c, self.stack.last()) {
+                        (Some(','), Some(Container::Object)) => self.expect = Expect::Key,
+                        (Some(','), Some(Container::Array)) => self.expect = Expect::Value,
+                        (Some('}'), Some(Container::Object))
+                        | (Some(']'), Some(Container::Array)) => {
+                            return Ok(Some(self.end_container(position)))
+                        }
+                        _ => return Err(self.u.unexpected(c)),
+                    }
+                }
+                (Expect::Key | Expect::FirstKeyOrEnd, _) => {
+                    let key = String::unmarshal_json_with_state(&mut self.u)?;
+                    match self.u.next_non_whitespace() {
+                        Some(':') => (),
+                        unexpected => return Err(self.u.unexpected(unexpected)),
+                    }
+                    self.expect = Expect::Value;
+                    return Ok(Some(Event {
+                        kind: EventKind::Key(key),
+                        position,
+                    }));
+                }
+                (Expect::Value | Expect::FirstValueOrEnd, Some('{')) => {
+                    self.u.next();
+                    self.stack.push(Container::Object);
+                    self.expect = Expect::FirstKeyOrEnd;
+                    return Ok(Some(Event {
+                        kind: EventKind::StartObject,
+                        position,
+                    }));
+                }
+                (Expect::Value | Expect::FirstValueOrEnd, Some('[')) => {
+                    self.u.next();
+                    self.stack.push(Container::Array);
+                    self.expect = Expect::FirstValueOrEnd;
+                    return Ok(Some(Event {
+                        kind: EventKind::StartArray,
+                        position,
+                    }));
+                }
+                (Expect::Value | Expect::FirstValueOrEnd, _) => {
+                    let kind = match Element::unmarshal_json_with_state(&mut self.u)? {
+                        Element::JsonString(s) => EventKind::String(s),
+                        Element::JsonInt(i) => EventKind::Number(Number::Int(i)),
+                        Element::JsonFloat(f) => EventKind::Number(Number::Float(f)),
+                        Element::JsonBool(b) => EventKind::Bool(b),
+                        Element::JsonNull => EventKind::Null,
+                        // Objects and arrays are handled by the cases above
+                        Element::JsonObject(_) | Element::JsonList(_) => unreachable!(),
+                    };
+                    self.expect = self.after_value();
+                    return Ok(Some(Event { kind, position }));
+                }
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for EventIter<'a> {
+    type Item = Result<Event, UnmarshalError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let event = self.next_event();
+        if event.is_err() {
+            self.expect = Expect::Finished;
+        }
+        event.transpose()
+    }
+}
@@ -1,42 +1,46 @@
 use crate::Unmarshalable;
 
-use super::{unmarshal_iter::UnmarshalIter, unmarshalable::UnmarshalError};
+use super::{source::Source, unmarshal_iter::UnmarshalIter, unmarshalable::UnmarshalError};
 
 impl Unmarshalable for f64 {
-    fn unmarshal_json_with_state(u: &mut UnmarshalIter) -> Result<Self, UnmarshalError> {
-        // Unmarshal before decimal point and/or exponent
-        let sign = if Some(&'-') == u.peek_non_whitespace() {
-            u.next();
-            -1.0
-        } else {
-            1.0
-        };
-        let mut f = u.try_next_digit(10)? as f64;
+    fn unmarshal_json_with_state<S: Source>(u: &mut S) -> Result<Self, S::Error> {
+        u.read_f64()
+    }
+}
+
+pub(crate) fn unmarshal_f64(u: &mut UnmarshalIter) -> Result<f64, UnmarshalError> {
+    // Unmarshal before decimal point and/or exponent
+    let sign = if Some(&'-') == u.peek_non_whitespace() {
+        u.next();
+        -1.0
+    } else {
+        1.0
+    };
+    let mut f = u.try_next_digit(10)? as f64;
+    while let Some(d) = u.peek().and_then(|c| c.to_digit(10)) {
+        f = f * 10.0 + d as f64;
+        u.next();
+    }
+
+    // Unmarshal decimal
+    if Some(&'.') == u.peek() {
+        u.next();
+        let mut dec_place = 0.1;
+        f += u.try_next_digit(10)? as f64 * dec_place;
         while let Some(d) = u.peek().and_then(|c| c.to_digit(10)) {
-            f = f * 10.0 + d as f64;
+            dec_place /= 10.0;
+            f += d as f64 * dec_place;
             u.next();
         }
+    }
 
-        // Unmarshal decimal
-        if Some(&'.') == u.peek() {
+    // Unmarshal exponent
+    match u.peek() {
+        Some(&('e' | 'E')) => {
             u.next();
-            let mut dec_place = 0.1;
-            f += u.try_next_digit(10)? as f64 * dec_place;
-            while let Some(d) = u.peek().and_then(|c| c.to_digit(10)) {
-                dec_place /= 10.0;
-                f += d as f64 * dec_place;
-                u.next();
-            }
-        }
-
-        // Unmarshal exponent
-        match u.peek() {
-            Some(&('e' | 'E')) => {
-                u.next();
-                Ok(f * 10f64.powi(try_unmarshal_exponent(u)?) * sign)
-            }
-            _ => Ok(f * sign),
+            Ok(f * 10f64.powi(try_unmarshal_exponent(u)?) * sign)
         }
+        _ => Ok(f * sign),
     }
 }
 
@@ -48,7 +52,7 @@ fn try_unmarshal_exponent(u: &mut UnmarshalIter) -> Result<i32, UnmarshalError>
             c.to_digit(10).ok_or_else(|| u.unexpected_char(c))? as i32,
             1,
         ),
-        None => return Err(UnmarshalError::EndOfChars),
+        None => return Err(u.end_of_chars()),
     };
     while let Some(d) = u.peek().and_then(|c| c.to_digit(10)) {
         exponent += exponent * 10 + d as i32;
@@ -66,6 +70,8 @@ pub(crate) fn unmarshal_float_from_int(
     sign: f64,
     u: &mut UnmarshalIter,
 ) -> Result<f64, UnmarshalError> {
+    // Signed from the sign so that "-0.0" stays negative
+    let whole = (i as f64).abs() * sign;
     match u.next() {
         // Unmarshal decimal and maybe exponent
         Some('.') => {
@@ -78,7 +84,7 @@ pub(crate) fn unmarshal_float_from_int(
                 u.next();
             }
 
-            let f = i as f64 + (decimal * sign);
+            let f = whole + decimal * sign;
             match u.peek() {
                 Some(&('e' | 'E')) => {
                     u.next();
@@ -89,7 +95,7 @@ pub(crate) fn unmarshal_float_from_int(
         }
 
         // Unmarshal exponent
-        Some('e' | 'E') => Ok(i as f64 * 10f64.powi(try_unmarshal_exponent(u)?)),
+        Some('e' | 'E') => Ok(whole * 10f64.powi(try_unmarshal_exponent(u)?)),
 
         // Function expects u to continue to a float
         unexpected => Err(u.unexpected(unexpected)),
@@ -1,20 +1,24 @@
 use crate::Unmarshalable;
 
-use super::{unmarshal_iter::UnmarshalIter, unmarshalable::UnmarshalError};
+use super::{source::Source, unmarshal_iter::UnmarshalIter, unmarshalable::UnmarshalError};
 
 impl Unmarshalable for i64 {
-    fn unmarshal_json_with_state(u: &mut UnmarshalIter) -> Result<Self, UnmarshalError> {
-        let sign = if Some(&'-') == u.peek_non_whitespace() {
-            u.next();
-            -1
-        } else {
-            1
-        };
-        let mut i = u.try_next_digit(10)? as i64;
-        while let Some(d) = u.peek().and_then(|c| c.to_digit(10)) {
-            i = i * 10 + d as i64;
-            u.next();
-        }
-        Ok(i * sign)
+    fn unmarshal_json_with_state<S: Source>(u: &mut S) -> Result<Self, S::Error> {
+        u.read_i64()
     }
 }
+
+pub(crate) fn unmarshal_i64(u: &mut UnmarshalIter) -> Result<i64, UnmarshalError> {
+    let sign = if Some(&'-') == u.peek_non_whitespace() {
+        u.next();
+        -1
+    } else {
+        1
+    };
+    let mut i = u.try_next_digit(10)? as i64;
+    while let Some(d) = u.peek().and_then(|c| c.to_digit(10)) {
+        i = i * 10 + d as i64;
+        u.next();
+    }
+    Ok(i * sign)
+}
@@ -0,0 +1,118 @@
+use std::{
+    io::{BufRead, BufReader, Read},
+    marker::PhantomData,
+};
+
+use crate::Unmarshalable;
+
+use super::{byte_reader::unmarshal_bytes, unmarshalable::UnmarshalError};
+
+/// An error unmarshaling one line of newline-delimited JSON.
+#[derive(Debug, PartialEq)]
+pub struct LineError {
+    /// Line number, starting at 1
+    pub line: u32,
+    pub error: UnmarshalError,
+}
+
+/// What a `LinesIter` does when a line fails to unmarshal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnError {
+    /// Yield the error and stop iterating
+    Stop,
+    /// Silently skip the line
+    Skip,
+    /// Skip the line, keeping the error to be read from `errors()`
+    Collect,
+}
+
+/// Unmarshals each line of newline-delimited JSON (JSON Lines) read from an
+/// `io::Read`. Blank lines are ignored, and lines may end with "\r\n".
+pub struct LinesIter<R: Read, T> {
+    r: BufReader<R>,
+    line: u32,
+    buf: Vec<u8>,
+    on_error: OnError,
+    errors: Vec<LineError>,
+    finished: bool,
+    _marker: PhantomData<T>,
+}
+
+impl<R: Read, T: Unmarshalable> LinesIter<R, T> {
+    pub fn new(reader: R) -> LinesIter<R, T> {
+        LinesIter {
+            r: BufReader::new(reader),
+            line: 0,
+            buf: Vec::new(),
+            on_error: OnError::Stop,
+            errors: Vec::new(),
+            finished: false,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Sets what to do with lines that fail to unmarshal. Errors reading
+    /// from the input always stop the iterator.
+    pub fn on_error(mut self, on_error: OnError) -> LinesIter<R, T> {
+        self.on_error = on_error;
+        self
+    }
+
+    /// Returns the errors skipped so far with `OnError::Collect`
+    pub fn errors(&self) -> &[LineError] {
+        &self.errors
+    }
+
+    fn next_line(&mut self) -> Result<Option<T>, LineError> {
+        loop {
+            self.buf.clear();
+            self.line += 1;
+            let line = self.line;
+            match self.r.read_until(b'\n', &mut self.buf) {
+                Ok(0) => return Ok(None),
+                Ok(_) => (),
+                Err(e) => {
+                    self.finished = true;
+                    return Err(LineError {
+                        line,
+                        error: e.into(),
+                    });
+                }
+            }
+
+            let mut bytes = self.buf.as_slice();
+            if let Some(b) = bytes.strip_suffix(b"\n") {
+                bytes = b.strip_suffix(b"\r").unwrap_or(b);
+            }
+            if bytes.iter().all(u8::is_ascii_whitespace) {
+                continue;
+            }
+
+            match unmarshal_bytes(bytes, (line, 1)) {
+                Ok(value) => return Ok(Some(value)),
+                Err(error) => {
+                    let error = LineError { line, error };
+                    match self.on_error {
+                        OnError::Stop => {
+                            self.finished = true;
+                            return Err(error);
+                        }
+                        OnError::Skip => (),
+                        OnError::Collect => self.errors.push(error),
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<R: Read, T: Unmarshalable> Iterator for LinesIter<R, T> {
+    type Item = Result<T, LineError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        self.next_line().transpose()
+    }
+}
@@ -1,28 +1,40 @@
 use crate::Unmarshalable;
 
-use super::{unmarshal_iter::UnmarshalIter, unmarshalable::UnmarshalError};
+use super::{source::Source, unmarshal_iter::UnmarshalIter, unmarshalable::UnmarshalError};
 
 impl<T: Unmarshalable> Unmarshalable for Vec<T> {
-    fn unmarshal_json_with_state(u: &mut UnmarshalIter) -> Result<Self, UnmarshalError> {
-        let first = u.next_non_whitespace();
-        if first != Some('[') {
-            return Err(u.unexpected(first));
-        }
+    fn unmarshal_json_with_state<S: Source>(u: &mut S) -> Result<Self, S::Error> {
+        let mut elements = vec![];
+        u.read_array(|u| {
+            elements.push(T::unmarshal_json_with_state(u)?);
+            Ok(())
+        })?;
+        Ok(elements)
+    }
+}
 
-        if u.peek_non_whitespace() == Some(&']') {
-            u.next();
-            return Ok(vec![]);
-        }
+/// Reads an array, calling f to unmarshal each of its items
+pub(crate) fn unmarshal_items<'a>(
+    u: &mut UnmarshalIter<'a>,
+    mut f: impl FnMut(&mut UnmarshalIter<'a>) -> Result<(), UnmarshalError>,
+) -> Result<(), UnmarshalError> {
+    let first = u.next_non_whitespace();
+    if first != Some('[') {
+        return Err(u.unexpected(first));
+    }
 
-        let mut elements = vec![T::unmarshal_json_with_state(u)?];
+    if u.peek_non_whitespace() == Some(&']') {
+        u.next();
+        return Ok(());
+    }
 
-        while let Some(c) = u.next_non_whitespace() {
-            match c {
-                ',' => elements.push(T::unmarshal_json_with_state(u)?),
-                ']' => return Ok(elements.into_iter().collect()),
-                _ => return Err(u.unexpected_char(c)),
-            }
+    f(u)?;
+    while let Some(c) = u.next_non_whitespace() {
+        match c {
+            ',' => f(u)?,
+            ']' => return Ok(()),
+            _ => return Err(u.unexpected_char(c)),
         }
-        Err(UnmarshalError::EndOfChars)
     }
+    Err(u.end_of_chars())
 }
@@ -1,12 +1,33 @@
+pub mod array_iter;
 pub mod bool;
+pub mod borrowed;
+mod byte_reader;
 pub mod element;
+pub mod events;
 pub mod f64;
 pub mod i64;
+pub mod lines;
 pub mod list;
 pub mod null;
 pub mod object;
+pub mod push_parser;
+pub mod raw_json;
+pub mod scan;
+mod scanner;
+pub mod sequence;
+pub mod source;
+pub mod spanned;
 pub mod string;
 pub mod unmarshal_iter;
 pub mod unmarshalable;
 
-pub use unmarshalable::UnmarshalError;
+pub use array_iter::ArrayIter;
+pub use borrowed::UnmarshalableBorrowed;
+pub use events::{Event, EventIter, EventKind, Number};
+pub use lines::{LineError, LinesIter, OnError};
+pub use push_parser::{PushParser, Status};
+pub use sequence::{Document, Framing, SequenceIter};
+pub use source::{ElementSource, Source, ValueKind};
+pub use spanned::{Span, Spans};
+pub use unmarshal_iter::{Position, UnmarshalIter};
+pub use unmarshalable::{FromElementError, UnmarshalError};
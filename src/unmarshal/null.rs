@@ -1,50 +1,44 @@
 use crate::Unmarshalable;
 
-use super::{unmarshal_iter::UnmarshalIter, unmarshalable::UnmarshalError};
+use super::{
+    source::{Source, ValueKind},
+    unmarshal_iter::UnmarshalIter,
+    unmarshalable::UnmarshalError,
+};
 
 // Use Option<> for nullable values
 impl<T: Unmarshalable> Unmarshalable for Option<T> {
-    fn unmarshal_json_with_state(u: &mut UnmarshalIter) -> Result<Self, UnmarshalError> {
-        if u.peek_non_whitespace() == Some(&'n') {
-            u.next();
-            match u.next() {
-                Some('u') => (),
-                unexpected => return Err(u.unexpected(unexpected)),
-            }
-            match u.next() {
-                Some('l') => (),
-                unexpected => return Err(u.unexpected(unexpected)),
-            }
-            match u.next() {
-                Some('l') => (),
-                unexpected => return Err(u.unexpected(unexpected)),
-            }
-            Ok(None)
+    fn unmarshal_json_with_state<S: Source>(u: &mut S) -> Result<Self, S::Error> {
+        if u.peek_kind() == Some(ValueKind::Null) {
+            u.read_null().map(|()| None)
         } else {
-            Ok(Some(T::unmarshal_json_with_state(u)?))
+            T::unmarshal_json_with_state(u).map(Some)
         }
     }
 }
 
 // Use Option<()> for required nulls
 impl Unmarshalable for Option<()> {
-    fn unmarshal_json_with_state(u: &mut UnmarshalIter) -> Result<Self, UnmarshalError> {
-        match u.next_non_whitespace() {
-            Some('n') => (),
-            unexpected => return Err(u.unexpected(unexpected)),
-        }
-        match u.next() {
-            Some('u') => (),
-            unexpected => return Err(u.unexpected(unexpected)),
-        }
-        match u.next() {
-            Some('l') => (),
-            unexpected => return Err(u.unexpected(unexpected)),
-        }
-        match u.next() {
-            Some('l') => (),
-            unexpected => return Err(u.unexpected(unexpected)),
-        }
-        Ok(None)
+    fn unmarshal_json_with_state<S: Source>(u: &mut S) -> Result<Self, S::Error> {
+        u.read_null().map(|()| None)
+    }
+}
+
+pub(crate) fn unmarshal_null(u: &mut UnmarshalIter) -> Result<(), UnmarshalError> {
+    match u.next_non_whitespace() {
+        Some('n') => (),
+        unexpected => return Err(u.unexpected(unexpected)),
+    }
+    match u.next() {
+        Some('u') => (),
+        unexpected => return Err(u.unexpected(unexpected)),
+    }
+    match u.next() {
+        Some('l') => (),
+        unexpected => return Err(u.unexpected(unexpected)),
+    }
+    match u.next() {
+        Some('l') => Ok(()),
+        unexpected => Err(u.unexpected(unexpected)),
     }
 }
@@ -1,52 +1,65 @@
 use std::{
     collections::{BTreeMap, HashMap},
-    iter,
+    ops::Range,
 };
 
 use crate::Unmarshalable;
 
-use super::{unmarshal_iter::UnmarshalIter, unmarshalable::UnmarshalError};
+use super::{source::Source, unmarshal_iter::UnmarshalIter, unmarshalable::UnmarshalError};
 
 impl<T: Unmarshalable> Unmarshalable for HashMap<String, T> {
-    fn unmarshal_json_with_state(u: &mut UnmarshalIter) -> Result<Self, UnmarshalError> {
-        let first = u.next_non_whitespace();
-        if first != Some('{') {
-            return Err(u.unexpected(first));
-        }
-
-        if u.peek_non_whitespace() == Some(&'}') {
-            u.next();
-            return Ok(HashMap::from_iter(iter::empty()));
-        }
-
-        let mut object: HashMap<String, T> = HashMap::from([unmarshal_pair(u)?]);
-        while let Some(c) = u.next_non_whitespace() {
-            match c {
-                ',' => {
-                    let (k, v) = unmarshal_pair(u)?;
-                    object.insert(k, v);
-                }
-                '}' => return Ok(HashMap::from_iter(object.into_iter())),
-                _ => return Err(u.unexpected_char(c)),
-            }
-        }
-        Err(UnmarshalError::EndOfChars)
+    fn unmarshal_json_with_state<S: Source>(u: &mut S) -> Result<Self, S::Error> {
+        let mut object = HashMap::new();
+        u.read_object(|u, key| {
+            object.insert(key, T::unmarshal_json_with_state(u)?);
+            Ok(())
+        })?;
+        Ok(object)
     }
 }
 
 impl<T: Unmarshalable> Unmarshalable for BTreeMap<String, T> {
-    fn unmarshal_json_with_state(u: &mut UnmarshalIter) -> Result<Self, UnmarshalError> {
-        Ok(HashMap::<String, T>::unmarshal_json_with_state(u)?
-            .into_iter()
-            .collect())
+    fn unmarshal_json_with_state<S: Source>(u: &mut S) -> Result<Self, S::Error> {
+        let mut object = BTreeMap::new();
+        u.read_object(|u, key| {
+            object.insert(key, T::unmarshal_json_with_state(u)?);
+            Ok(())
+        })?;
+        Ok(object)
     }
 }
 
-/// Unmarshals a key value pair separated with a ':'
-fn unmarshal_pair<T: Unmarshalable>(u: &mut UnmarshalIter) -> Result<(String, T), UnmarshalError> {
-    let key = String::unmarshal_json_with_state(u)?;
-    match u.next_non_whitespace() {
-        Some(':') => Ok((key, T::unmarshal_json_with_state(u)?)),
-        unexpected => Err(u.unexpected(unexpected)),
+/// Reads an object, reading each key with read_key and calling f to unmarshal
+/// the value of each member. f is passed the member's key along with the
+/// byte offsets of the quoted key.
+pub(crate) fn unmarshal_members<'a, K>(
+    u: &mut UnmarshalIter<'a>,
+    mut read_key: impl FnMut(&mut UnmarshalIter<'a>) -> Result<K, UnmarshalError>,
+    mut f: impl FnMut(&mut UnmarshalIter<'a>, K, Range<usize>) -> Result<(), UnmarshalError>,
+) -> Result<(), UnmarshalError> {
+    let first = u.next_non_whitespace();
+    if first != Some('{') {
+        return Err(u.unexpected(first));
+    }
+
+    if u.peek_non_whitespace() == Some(&'}') {
+        u.next();
+        return Ok(());
+    }
+
+    loop {
+        u.peek_non_whitespace();
+        let start = u.offset();
+        let key = read_key(u)?;
+        let key_range = start..u.offset();
+        match u.next_non_whitespace() {
+            Some(':') => f(u, key, key_range)?,
+            unexpected => return Err(u.unexpected(unexpected)),
+        }
+        match u.next_non_whitespace() {
+            Some(',') => (),
+            Some('}') => return Ok(()),
+            unexpected => return Err(u.unexpected(unexpected)),
+        }
     }
 }
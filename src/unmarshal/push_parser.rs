@@ -0,0 +1,440 @@
+use std::{collections::HashMap, mem};
+
+use crate::{Element, Unmarshalable};
+
+use super::unmarshalable::UnmarshalError;
+
+/// The result of feeding input to a `PushParser`.
+#[derive(Debug, PartialEq)]
+pub enum Status {
+    /// The input so far is a valid prefix of a value
+    NeedMoreInput,
+    Complete(Element),
+}
+
+enum Frame {
+    Object {
+        object: HashMap<String, Element>,
+        key: Option<String>,
+    },
+    Array(Vec<Element>),
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Expect {
+    Value,
+    FirstValueOrEnd,
+    Key,
+    FirstKeyOrEnd,
+    Colon,
+    CommaOrEnd,
+}
+
+#[derive(Clone, Copy)]
+enum Escape {
+    None,
+    Backslash,
+    Unicode {
+        code: u32,
+        digits: u8,
+        lead: Option<u32>,
+    },
+    /// A lead surrogate was read, so "\u" must follow
+    SurrogateBackslash(u32),
+    SurrogateU(u32),
+}
+
+/// A token which may be split between chunks.
+enum Token {
+    None,
+    String {
+        bytes: Vec<u8>,
+        escape: Escape,
+        key: bool,
+    },
+    Number {
+        chars: String,
+        row: u32,
+        col: u32,
+    },
+    Literal {
+        rest: &'static [u8],
+        value: Element,
+    },
+}
+
+/// A resumable parser which is fed input in chunks, for input that arrives
+/// without blocking. Chunks may be split anywhere, including inside UTF-8
+/// sequences and escapes, and earlier chunks are never scanned again.
+pub struct PushParser {
+    stack: Vec<Frame>,
+    expect: Expect,
+    token: Token,
+    completed: Option<Element>,
+    /// Input after a complete value, which is parsed from `leftover_start`
+    /// by later calls
+    leftover: Vec<u8>,
+    leftover_start: usize,
+    row: u32,
+    col: u32,
+}
+
+impl Default for PushParser {
+    fn default() -> Self {
+        PushParser::new()
+    }
+}
+
+impl PushParser {
+    pub fn new() -> PushParser {
+        PushParser {
+            stack: Vec::new(),
+            expect: Expect::Value,
+            token: Token::None,
+            completed: None,
+            leftover: Vec::new(),
+            leftover_start: 0,
+            row: 1,
+            col: 0,
+        }
+    }
+
+    /// Parses the next chunk of input. Once a value is complete, it's
+    /// returned and parsing restarts for the next value in the stream. Any
+    /// input after the value is kept for the next call, so call `feed` with
+    /// an empty chunk to continue parsing it.
+    pub fn feed(&mut self, chunk: &[u8]) -> Result<Status, UnmarshalError> {
+        if self.leftover_start == self.leftover.len() {
+            self.leftover.clear();
+            self.leftover_start = 0;
+            let (status, parsed) = self.parse(chunk)?;
+            // The rest of the chunk is only copied once, and later values
+            // are parsed from it in place
+            self.leftover.extend_from_slice(&chunk[parsed..]);
+            return Ok(status);
+        }
+        if !chunk.is_empty() {
+            self.leftover.drain(..self.leftover_start);
+            self.leftover_start = 0;
+            self.leftover.extend_from_slice(chunk);
+        }
+        let input = mem::take(&mut self.leftover);
+        let result = self.parse(&input[self.leftover_start..]);
+        self.leftover = input;
+        let (status, parsed) = result?;
+        self.leftover_start += parsed;
+        Ok(status)
+    }
+
+    /// Signals the end of the input. Returns the next value if one is
+    /// completed by the end of the input, or `None` if only whitespace
+    /// remained, so it should be called until it returns `None`.
+    pub fn finish(&mut self) -> Result<Option<Element>, UnmarshalError> {
+        if let Status::Complete(e) = self.feed(&[])? {
+            return Ok(Some(e));
+        }
+        match self.token {
+            Token::Number { .. } if self.stack.is_empty() => {
+                self.end_number()?;
+                Ok(self.completed.take())
+            }
+            Token::None if self.stack.is_empty() && self.expect == Expect::Value => Ok(None),
+            _ => Err(UnmarshalError::EndOfChars),
+        }
+    }
+
+    /// Parses input until a value is complete, also returning the number of
+    /// bytes parsed
+    fn parse(&mut self, input: &[u8]) -> Result<(Status, usize), UnmarshalError> {
+        for (i, &b) in input.iter().enumerate() {
+            self.push(b)?;
+            if let Some(element) = self.completed.take() {
+                return Ok((Status::Complete(element), i + 1));
+            }
+        }
+        Ok((Status::NeedMoreInput, input.len()))
+    }
+
+    fn push(&mut self, b: u8) -> Result<(), UnmarshalError> {
+        match b {
+            b'\n' => {
+                self.row += 1;
+                self.col = 0;
+            }
+            // Only count the first byte of each character
+            _ if b & 0xc0 != 0x80 => self.col += 1,
+            _ => (),
+        }
+
+        match &mut self.token {
+            Token::None => (),
+            Token::String { .. } => return self.push_string(b),
+            Token::Number { chars, .. } => {
+                if matches!(b, b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E') {
+                    chars.push(b as char);
+                    return Ok(());
+                }
+                // The byte after a number is handled as a new token
+                self.end_number()?;
+            }
+            Token::Literal { rest, .. } => {
+                if rest.first() != Some(&b) {
+                    return Err(self.unexpected(b));
+                }
+                *rest = &rest[1..];
+                if rest.is_empty() {
+                    if let Token::Literal { value, .. } = mem::replace(&mut self.token, Token::None)
+                    {
+                        self.end_value(value);
+                    }
+                }
+                return Ok(());
+            }
+        }
+
+        if b.is_ascii_whitespace() {
+            return Ok(());
+        }
+        match (self.expect, b) {
+            (Expect::FirstValueOrEnd, b']') | (Expect::FirstKeyOrEnd, b'}') => self.end_container(),
+            (Expect::CommaOrEnd, b',') => {
+                self.expect = match self.stack.last() {
+                    Some(Frame::Object { .. }) => Expect::Key,
+                    _ => Expect::Value,
+                };
+            }
+            (Expect::CommaOrEnd, b']') if matches!(self.stack.last(), Some(Frame::Array(_))) => {
+                self.end_container()
+            }
+            (Expect::CommaOrEnd, b'}')
+                if matches!(self.stack.last(), Some(Frame::Object { .. })) =>
+            {
+                self.end_container()
+            }
+            (Expect::Key | Expect::FirstKeyOrEnd, b'"') => {
+                self.token = Token::String {
+                    bytes: Vec::new(),
+                    escape: Escape::None,
+                    key: true,
+                }
+            }
+            (Expect::Colon, b':') => self.expect = Expect::Value,
+            (Expect::Value | Expect::FirstValueOrEnd, _) => return self.start_value(b),
+            _ => return Err(self.unexpected(b)),
+        }
+        Ok(())
+    }
+
+    fn start_value(&mut self, b: u8) -> Result<(), UnmarshalError> {
+        match b {
+            b'{' => {
+                self.stack.push(Frame::Object {
+                    object: HashMap::new(),
+                    key: None,
+                });
+                self.expect = Expect::FirstKeyOrEnd;
+            }
+            b'[' => {
+                self.stack.push(Frame::Array(Vec::new()));
+                self.expect = Expect::FirstValueOrEnd;
+            }
+            b'"' => {
+                self.token = Token::String {
+                    bytes: Vec::new(),
+                    escape: Escape::None,
+                    key: false,
+                }
+            }
+            b'-' | b'0'..=b'9' => {
+                self.token = Token::Number {
+                    chars: (b as char).to_string(),
+                    row: self.row,
+                    col: self.col,
+                }
+            }
+            b't' => {
+                self.token = Token::Literal {
+                    rest: b"rue",
+                    value: Element::JsonBool(true),
+                }
+            }
+            b'f' => {
+                self.token = Token::Literal {
+                    rest: b"alse",
+                    value: Element::JsonBool(false),
+                }
+            }
+            b'n' => {
+                self.token = Token::Literal {
+                    rest: b"ull",
+                    value: Element::JsonNull,
+                }
+            }
+            _ => return Err(self.unexpected(b)),
+        }
+        Ok(())
+    }
+
+    fn push_string(&mut self, b: u8) -> Result<(), UnmarshalError> {
+        let Token::String { bytes, escape, .. } = &mut self.token else {
+            return Ok(());
+        };
+        let unescaped = match (*escape, b) {
+            (Escape::None, b'"') => return self.end_string(),
+            (Escape::None, b'\\') => {
+                *escape = Escape::Backslash;
+                return Ok(());
+            }
+            (Escape::None, _) => {
+                bytes.push(b);
+                return Ok(());
+            }
+            (Escape::Backslash, b'\\' | b'"' | b'/') => b as char,
+            (Escape::Backslash, b'b') => '\x08', // Literal backspace
+            (Escape::Backslash, b'f') => '\x0c', // Formfeed
+            (Escape::Backslash, b'n') => '\n',
+            (Escape::Backslash, b'r') => '\r',
+            (Escape::Backslash, b't') => '\t',
+            (Escape::Backslash, b'u') => {
+                *escape = Escape::Unicode {
+                    code: 0,
+                    digits: 0,
+                    lead: None,
+                };
+                return Ok(());
+            }
+            (Escape::SurrogateBackslash(lead), b'\\') => {
+                *escape = Escape::SurrogateU(lead);
+                return Ok(());
+            }
+            (Escape::SurrogateU(lead), b'u') => {
+                *escape = Escape::Unicode {
+                    code: 0,
+                    digits: 0,
+                    lead: Some(lead),
+                };
+                return Ok(());
+            }
+            (Escape::Unicode { code, digits, lead }, _) => {
+                let Some(d) = (b as char).to_digit(16) else {
+                    return Err(self.unexpected(b));
+                };
+                let code = (code << 4) + d;
+                if digits < 3 {
+                    *escape = Escape::Unicode {
+                        code,
+                        digits: digits + 1,
+                        lead,
+                    };
+                    return Ok(());
+                }
+                let c = match lead {
+                    None if (0xd800..=0xdbff).contains(&code) => {
+                        *escape = Escape::SurrogateBackslash(code);
+                        return Ok(());
+                    }
+                    None => char::from_u32(code),
+                    Some(lead) if (0xdc00..=0xdfff).contains(&code) => {
+                        char::from_u32(0x10000 + (((lead - 0xd800) << 10) | (code - 0xdc00)))
+                    }
+                    Some(_) => None,
+                };
+                match c {
+                    Some(c) => c,
+                    None => return Err(self.unexpected_char('u')),
+                }
+            }
+            _ => return Err(self.unexpected(b)),
+        };
+        let Token::String { bytes, escape, .. } = &mut self.token else {
+            return Ok(());
+        };
+        *escape = Escape::None;
+        let mut buf = [0; 4];
+        bytes.extend_from_slice(unescaped.encode_utf8(&mut buf).as_bytes());
+        Ok(())
+    }
+
+    fn end_string(&mut self) -> Result<(), UnmarshalError> {
+        let Token::String { bytes, key, .. } = mem::replace(&mut self.token, Token::None) else {
+            return Ok(());
+        };
+        // Strings are only checked to be valid UTF-8 once they're complete, so
+        // the error is reported at the closing quote
+        let s = String::from_utf8(bytes).map_err(|_| UnmarshalError::InvalidUtf8 {
+            row: self.row,
+            col: self.col,
+        })?;
+        if key {
+            if let Some(Frame::Object { key, .. }) = self.stack.last_mut() {
+                *key = Some(s);
+            }
+            self.expect = Expect::Colon;
+        } else {
+            self.end_value(Element::JsonString(s));
+        }
+        Ok(())
+    }
+
+    fn end_number(&mut self) -> Result<(), UnmarshalError> {
+        let Token::Number { chars, row, col } = mem::replace(&mut self.token, Token::None) else {
+            return Ok(());
+        };
+        // Numbers are short, so the whole token is unmarshaled at once
+        let element = Element::unmarshal_json(chars.chars()).map_err(|e| match e {
+            UnmarshalError::UnexpectedChar { c, col: c_col, .. } => {
+                UnmarshalError::UnexpectedChar {
+                    c,
+                    row,
+                    col: col + c_col - 1,
+                }
+            }
+            e => e,
+        })?;
+        self.end_value(element);
+        Ok(())
+    }
+
+    fn end_container(&mut self) {
+        let element = match self.stack.pop() {
+            Some(Frame::Object { object, .. }) => Element::JsonObject(object),
+            Some(Frame::Array(list)) => Element::JsonList(list),
+            None => return,
+        };
+        self.end_value(element);
+    }
+
+    /// Adds a complete value to its parent, or completes the root value
+    fn end_value(&mut self, element: Element) {
+        match self.stack.last_mut() {
+            None => {
+                self.completed = Some(element);
+                self.expect = Expect::Value;
+                return;
+            }
+            Some(Frame::Array(list)) => list.push(element),
+            Some(Frame::Object { object, key }) => {
+                if let Some(key) = key.take() {
+                    object.insert(key, element);
+                }
+            }
+        }
+        self.expect = Expect::CommaOrEnd;
+    }
+
+    fn unexpected(&self, b: u8) -> UnmarshalError {
+        self.unexpected_char(if b.is_ascii() {
+            b as char
+        } else {
+            char::REPLACEMENT_CHARACTER
+        })
+    }
+
+    fn unexpected_char(&self, c: char) -> UnmarshalError {
+        UnmarshalError::UnexpectedChar {
+            c,
+            row: self.row,
+            col: self.col,
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use crate::{raw_json::RawJson, Element, Unmarshalable};
+
+use super::{
+    list::unmarshal_items,
+    object::unmarshal_members,
+    source::{Source, ValueKind},
+    string::unmarshal_cow,
+    unmarshal_iter::UnmarshalIter,
+    unmarshalable::UnmarshalError,
+};
+
+/// Elements are marshaled, since their source text isn't kept
+impl Unmarshalable for RawJson {
+    fn unmarshal_json_with_state<S: Source>(u: &mut S) -> Result<Self, S::Error> {
+        u.read_raw()
+    }
+}
+
+pub(crate) fn unmarshal_raw(u: &mut UnmarshalIter) -> Result<RawJson, UnmarshalError> {
+    u.peek_non_whitespace();
+    let start = u.offset();
+    validate_value(u)?;
+    Ok(RawJson(u.str_since(start, 0).to_owned()))
+}
+
+/// Reads a value, checking it the same way as unmarshaling an `Element`
+/// without building it
+fn validate_value(u: &mut UnmarshalIter) -> Result<(), UnmarshalError> {
+    match u.peek_kind() {
+        Some(ValueKind::Object) => unmarshal_members(u, unmarshal_cow, |u, _, _| validate_value(u)),
+        Some(ValueKind::Array) => unmarshal_items(u, validate_value),
+        Some(ValueKind::String) => unmarshal_cow(u).map(drop),
+        // Scalars are unmarshaled without allocating
+        _ => Element::unmarshal_json_with_state(u).map(drop),
+    }
+}
@@ -0,0 +1,363 @@
+//! Scanning routines used by `UnmarshalIter` on hot paths, with SSE2 and AVX2
+//! versions on x86_64 which are picked at runtime.
+
+use std::{str, sync::OnceLock};
+
+/// An implementation of the scanning routines. All backends give identical
+/// results. Backends are ordered from slowest to fastest, and the CPU
+/// supports every backend before the fastest one it supports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Backend {
+    Scalar,
+    Sse2,
+    Avx2,
+}
+
+impl Backend {
+    /// Returns the fastest backend supported by the CPU
+    pub fn detect() -> Backend {
+        static BACKEND: OnceLock<Backend> = OnceLock::new();
+        *BACKEND.get_or_init(|| {
+            Backend::available()
+                .last()
+                .copied()
+                .unwrap_or(Backend::Scalar)
+        })
+    }
+
+    /// Returns every backend supported by the CPU
+    pub fn available() -> Vec<Backend> {
+        #[allow(unused_mut)]
+        let mut backends = vec![Backend::Scalar];
+        #[cfg(target_arch = "x86_64")]
+        {
+            if is_x86_feature_detected!("sse2") {
+                backends.push(Backend::Sse2);
+            }
+            if is_x86_feature_detected!("avx2") {
+                backends.push(Backend::Avx2);
+            }
+        }
+        backends
+    }
+
+    /// Returns the backend if the CPU supports it, or the scalar backend
+    fn supported(self) -> Backend {
+        if self <= Backend::detect() {
+            self
+        } else {
+            Backend::Scalar
+        }
+    }
+
+    /// Returns the index of the first quote, backslash, or control character,
+    /// or the length of the bytes if there are none
+    pub fn find_string_special(self, bytes: &[u8]) -> usize {
+        // SAFETY: the backend is supported by the CPU
+        unsafe { self.supported().find_string_special_unchecked(bytes) }
+    }
+
+    /// Returns the number of ASCII whitespace bytes at the start of the bytes
+    pub fn skip_whitespace(self, bytes: &[u8]) -> usize {
+        // SAFETY: the backend is supported by the CPU
+        unsafe { self.supported().skip_whitespace_unchecked(bytes) }
+    }
+
+    /// Checks that the bytes are valid UTF-8, returning the length of the
+    /// valid prefix if they aren't
+    pub fn validate_utf8(self, bytes: &[u8]) -> Result<(), usize> {
+        // SAFETY: the backend is supported by the CPU
+        unsafe { self.supported().validate_utf8_unchecked(bytes) }
+    }
+
+    /// # Safety
+    /// The CPU must support the backend
+    unsafe fn find_string_special_unchecked(self, bytes: &[u8]) -> usize {
+        match self {
+            #[cfg(target_arch = "x86_64")]
+            Backend::Sse2 => unsafe { x86::find_string_special_sse2(bytes) },
+            #[cfg(target_arch = "x86_64")]
+            Backend::Avx2 => unsafe { x86::find_string_special_avx2(bytes) },
+            _ => scalar::find_string_special(bytes, 0),
+        }
+    }
+
+    /// # Safety
+    /// The CPU must support the backend
+    unsafe fn skip_whitespace_unchecked(self, bytes: &[u8]) -> usize {
+        match self {
+            #[cfg(target_arch = "x86_64")]
+            Backend::Sse2 => unsafe { x86::skip_whitespace_sse2(bytes) },
+            #[cfg(target_arch = "x86_64")]
+            Backend::Avx2 => unsafe { x86::skip_whitespace_avx2(bytes) },
+            _ => scalar::skip_whitespace(bytes, 0),
+        }
+    }
+
+    /// # Safety
+    /// The CPU must support the backend
+    unsafe fn validate_utf8_unchecked(self, bytes: &[u8]) -> Result<(), usize> {
+        match self {
+            #[cfg(target_arch = "x86_64")]
+            Backend::Sse2 => unsafe { x86::validate_utf8_sse2(bytes) },
+            #[cfg(target_arch = "x86_64")]
+            Backend::Avx2 => unsafe { x86::validate_utf8_avx2(bytes) },
+            // The standard library is the reference the other backends are
+            // tested against
+            _ => str::from_utf8(bytes)
+                .map(|_| ())
+                .map_err(|e| e.valid_up_to()),
+        }
+    }
+}
+
+pub fn find_string_special(bytes: &[u8]) -> usize {
+    // SAFETY: the detected backend is supported by the CPU
+    unsafe { Backend::detect().find_string_special_unchecked(bytes) }
+}
+
+pub fn skip_whitespace(bytes: &[u8]) -> usize {
+    // SAFETY: the detected backend is supported by the CPU
+    unsafe { Backend::detect().skip_whitespace_unchecked(bytes) }
+}
+
+pub fn validate_utf8(bytes: &[u8]) -> Result<(), usize> {
+    // SAFETY: the detected backend is supported by the CPU
+    unsafe { Backend::detect().validate_utf8_unchecked(bytes) }
+}
+
+/// Scalar versions which also finish the tails of the vectorized versions.
+mod scalar {
+    pub fn find_string_special(bytes: &[u8], start: usize) -> usize {
+        bytes[start..]
+            .iter()
+            .position(|&b| b == b'"' || b == b'\\' || b < 0x20)
+            .map_or(bytes.len(), |i| start + i)
+    }
+
+    pub fn skip_whitespace(bytes: &[u8], start: usize) -> usize {
+        bytes[start..]
+            .iter()
+            .position(|b| !matches!(b, b' ' | b'\t' | b'\n' | b'\r'))
+            .map_or(bytes.len(), |i| start + i)
+    }
+
+    /// The UTF-8 sequence being validated, which is carried between blocks.
+    #[cfg(target_arch = "x86_64")]
+    #[derive(Clone, Copy, Default)]
+    pub struct Utf8State {
+        /// Continuation bytes left in the sequence
+        remaining: u8,
+        /// The range of the next continuation byte, which is narrower after
+        /// some lead bytes to reject overlong encodings and surrogates
+        lo: u8,
+        hi: u8,
+        /// Offset of the sequence's lead byte
+        start: usize,
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    impl Utf8State {
+        /// Returns true if the state is between sequences
+        pub fn is_boundary(&self) -> bool {
+            self.remaining == 0
+        }
+
+        /// Validates the bytes in the range, returning the offset of the
+        /// sequence which is invalid
+        pub fn validate(&mut self, bytes: &[u8], start: usize, end: usize) -> Result<(), usize> {
+            for (i, &b) in bytes[start..end].iter().enumerate() {
+                if self.remaining > 0 {
+                    if b < self.lo || b > self.hi {
+                        return Err(self.start);
+                    }
+                    self.remaining -= 1;
+                    (self.lo, self.hi) = (0x80, 0xbf);
+                    continue;
+                }
+                self.start = start + i;
+                (self.remaining, self.lo, self.hi) = match b {
+                    0x00..=0x7f => continue,
+                    0xc2..=0xdf => (1, 0x80, 0xbf),
+                    0xe0 => (2, 0xa0, 0xbf),
+                    0xed => (2, 0x80, 0x9f),
+                    0xe1..=0xef => (2, 0x80, 0xbf),
+                    0xf0 => (3, 0x90, 0xbf),
+                    0xf1..=0xf3 => (3, 0x80, 0xbf),
+                    0xf4 => (3, 0x80, 0x8f),
+                    _ => return Err(self.start),
+                };
+            }
+            Ok(())
+        }
+
+        /// Checks that the bytes didn't end partway through a sequence
+        pub fn finish(&self) -> Result<(), usize> {
+            match self.remaining {
+                0 => Ok(()),
+                _ => Err(self.start),
+            }
+        }
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    use std::arch::x86_64::*;
+
+    use super::scalar::{self, Utf8State};
+
+    /// Generates a function which scans blocks of bytes, stopping at the first
+    /// block where the mask of matching bytes isn't empty
+    macro_rules! block_scan {
+        (
+            $name:ident, $feature:literal, $vector:ty, $width:literal, $load:ident,
+            |$v:ident| $mask:expr, $found:expr, $tail:expr
+        ) => {
+            #[target_feature(enable = $feature)]
+            pub unsafe fn $name(bytes: &[u8]) -> usize {
+                let mut i = 0;
+                while i + $width <= bytes.len() {
+                    // SAFETY: the block is within the bytes, and unaligned
+                    // loads are used
+                    let $v = unsafe { $load(bytes.as_ptr().add(i) as *const $vector) };
+                    let mask: u32 = $mask;
+                    if mask != 0 {
+                        return $found(i, mask);
+                    }
+                    i += $width;
+                }
+                $tail(bytes, i)
+            }
+        };
+    }
+
+    block_scan!(
+        find_string_special_sse2,
+        "sse2",
+        __m128i,
+        16,
+        _mm_loadu_si128,
+        |v| {
+            let special = _mm_or_si128(
+                _mm_or_si128(
+                    _mm_cmpeq_epi8(v, _mm_set1_epi8(b'"' as i8)),
+                    _mm_cmpeq_epi8(v, _mm_set1_epi8(b'\\' as i8)),
+                ),
+                // Unsigned v <= 0x1f
+                _mm_cmpeq_epi8(_mm_min_epu8(v, _mm_set1_epi8(0x1f)), v),
+            );
+            _mm_movemask_epi8(special) as u32
+        },
+        |i: usize, mask: u32| i + mask.trailing_zeros() as usize,
+        scalar::find_string_special
+    );
+
+    block_scan!(
+        find_string_special_avx2,
+        "avx2",
+        __m256i,
+        32,
+        _mm256_loadu_si256,
+        |v| {
+            let special = _mm256_or_si256(
+                _mm256_or_si256(
+                    _mm256_cmpeq_epi8(v, _mm256_set1_epi8(b'"' as i8)),
+                    _mm256_cmpeq_epi8(v, _mm256_set1_epi8(b'\\' as i8)),
+                ),
+                _mm256_cmpeq_epi8(_mm256_min_epu8(v, _mm256_set1_epi8(0x1f)), v),
+            );
+            _mm256_movemask_epi8(special) as u32
+        },
+        |i: usize, mask: u32| i + mask.trailing_zeros() as usize,
+        scalar::find_string_special
+    );
+
+    block_scan!(
+        skip_whitespace_sse2,
+        "sse2",
+        __m128i,
+        16,
+        _mm_loadu_si128,
+        |v| {
+            let whitespace = _mm_or_si128(
+                _mm_or_si128(
+                    _mm_cmpeq_epi8(v, _mm_set1_epi8(b' ' as i8)),
+                    _mm_cmpeq_epi8(v, _mm_set1_epi8(b'\t' as i8)),
+                ),
+                _mm_or_si128(
+                    _mm_cmpeq_epi8(v, _mm_set1_epi8(b'\n' as i8)),
+                    _mm_cmpeq_epi8(v, _mm_set1_epi8(b'\r' as i8)),
+                ),
+            );
+            !(_mm_movemask_epi8(whitespace) as u32) & 0xffff
+        },
+        |i: usize, mask: u32| i + mask.trailing_zeros() as usize,
+        scalar::skip_whitespace
+    );
+
+    block_scan!(
+        skip_whitespace_avx2,
+        "avx2",
+        __m256i,
+        32,
+        _mm256_loadu_si256,
+        |v| {
+            let whitespace = _mm256_or_si256(
+                _mm256_or_si256(
+                    _mm256_cmpeq_epi8(v, _mm256_set1_epi8(b' ' as i8)),
+                    _mm256_cmpeq_epi8(v, _mm256_set1_epi8(b'\t' as i8)),
+                ),
+                _mm256_or_si256(
+                    _mm256_cmpeq_epi8(v, _mm256_set1_epi8(b'\n' as i8)),
+                    _mm256_cmpeq_epi8(v, _mm256_set1_epi8(b'\r' as i8)),
+                ),
+            );
+            !(_mm256_movemask_epi8(whitespace) as u32)
+        },
+        |i: usize, mask: u32| i + mask.trailing_zeros() as usize,
+        scalar::skip_whitespace
+    );
+
+    /// Generates a function which validates UTF-8 a block at a time. Blocks
+    /// of ASCII between sequences are skipped, and the state of the sequence
+    /// being validated is carried from one block to the next.
+    macro_rules! validate_utf8 {
+        ($name:ident, $feature:literal, $vector:ty, $width:literal, $load:ident, $movemask:ident) => {
+            #[target_feature(enable = $feature)]
+            pub unsafe fn $name(bytes: &[u8]) -> Result<(), usize> {
+                let mut state = Utf8State::default();
+                let mut i = 0;
+                while i + $width <= bytes.len() {
+                    // SAFETY: the block is within the bytes, and unaligned
+                    // loads are used
+                    let v = unsafe { $load(bytes.as_ptr().add(i) as *const $vector) };
+                    if $movemask(v) != 0 || !state.is_boundary() {
+                        state.validate(bytes, i, i + $width)?;
+                    }
+                    i += $width;
+                }
+                state.validate(bytes, i, bytes.len())?;
+                state.finish()
+            }
+        };
+    }
+
+    validate_utf8!(
+        validate_utf8_sse2,
+        "sse2",
+        __m128i,
+        16,
+        _mm_loadu_si128,
+        _mm_movemask_epi8
+    );
+
+    validate_utf8!(
+        validate_utf8_avx2,
+        "avx2",
+        __m256i,
+        32,
+        _mm256_loadu_si256,
+        _mm256_movemask_epi8
+    );
+}
@@ -0,0 +1,123 @@
+/// The result of pushing a byte into a `ValueScanner`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Scan {
+    /// The byte is part of the value, which is not yet complete
+    Continue,
+    /// The byte completed the value
+    Complete,
+    /// The byte is not part of the value, which was already complete
+    Ended,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum State {
+    Start,
+    Scalar,
+    String { escaped: bool },
+    Container,
+}
+
+/// Finds where a value ends in a stream of bytes without unmarshaling it,
+/// so the bytes of a single value can be buffered. Brackets are checked to
+/// be balanced, but the value is otherwise left to be validated when it is
+/// unmarshaled. Multi-byte UTF-8 sequences never contain ASCII bytes, so
+/// the value can be split anywhere.
+pub(crate) struct ValueScanner {
+    brackets: Vec<u8>,
+    state: State,
+}
+
+impl ValueScanner {
+    pub fn new() -> ValueScanner {
+        ValueScanner {
+            brackets: Vec::new(),
+            state: State::Start,
+        }
+    }
+
+    /// Returns Err if the byte can never be part of the value
+    pub fn push(&mut self, b: u8) -> Result<Scan, ()> {
+        match self.state {
+            State::Start => match b {
+                b' ' | b'\t' | b'\n' | b'\r' => Ok(Scan::Continue),
+                b'{' | b'[' => {
+                    self.open(b);
+                    Ok(Scan::Continue)
+                }
+                b'"' => {
+                    self.state = State::String { escaped: false };
+                    Ok(Scan::Continue)
+                }
+                b'}' | b']' | b',' | b':' => Err(()),
+                _ => {
+                    self.state = State::Scalar;
+                    Ok(Scan::Continue)
+                }
+            },
+            State::Scalar => match b {
+                b' ' | b'\t' | b'\n' | b'\r' | b',' | b':' | b'{' | b'}' | b'[' | b']' | b'"' => {
+                    self.state = State::Start;
+                    Ok(Scan::Ended)
+                }
+                _ => Ok(Scan::Continue),
+            },
+            State::String { escaped: true } => {
+                self.state = State::String { escaped: false };
+                Ok(Scan::Continue)
+            }
+            State::String { escaped: false } => match b {
+                b'\\' => {
+                    self.state = State::String { escaped: true };
+                    Ok(Scan::Continue)
+                }
+                b'"' if self.brackets.is_empty() => Ok(self.complete()),
+                b'"' => {
+                    self.state = State::Container;
+                    Ok(Scan::Continue)
+                }
+                _ => Ok(Scan::Continue),
+            },
+            State::Container => match b {
+                b'"' => {
+                    self.state = State::String { escaped: false };
+                    Ok(Scan::Continue)
+                }
+                b'{' | b'[' => {
+                    self.open(b);
+                    Ok(Scan::Continue)
+                }
+                b'}' | b']' => {
+                    if self.brackets.pop() != Some(b) {
+                        return Err(());
+                    }
+                    if self.brackets.is_empty() {
+                        Ok(self.complete())
+                    } else {
+                        Ok(Scan::Continue)
+                    }
+                }
+                _ => Ok(Scan::Continue),
+            },
+        }
+    }
+
+    /// Returns true if the value is complete at the end of the input
+    pub fn finish(&mut self) -> bool {
+        if self.state == State::Scalar {
+            self.complete();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn open(&mut self, b: u8) {
+        self.brackets.push(if b == b'{' { b'}' } else { b']' });
+        self.state = State::Container;
+    }
+
+    fn complete(&mut self) -> Scan {
+        self.state = State::Start;
+        Scan::Complete
+    }
+}
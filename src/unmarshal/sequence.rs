@@ -0,0 +1,128 @@
+use std::{io::Read, marker::PhantomData};
+
+use crate::Unmarshalable;
+
+use super::{
+    byte_reader::{unmarshal_bytes, ByteReader},
+    unmarshalable::UnmarshalError,
+};
+
+/// The record separator which starts each text in an RFC 7464 sequence
+pub const RECORD_SEPARATOR: u8 = 0x1e;
+
+/// How the documents in a stream are separated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Framing {
+    /// Values follow each other directly, with optional whitespace between
+    /// them, like `{"a":1}{"b":2}`. An error stops the iteration, since the
+    /// start of the next value can't be found.
+    Concatenated,
+    /// RFC 7464 JSON text sequences, where each value is preceded by a
+    /// record separator. A value that fails to unmarshal doesn't stop the
+    /// iteration.
+    TextSequence,
+}
+
+/// A value read from a multi-document stream.
+#[derive(Debug, PartialEq)]
+pub struct Document<T> {
+    /// Byte offset of the start of the value in the stream
+    pub offset: usize,
+    pub value: T,
+}
+
+/// Unmarshals successive JSON documents read from an `io::Read`.
+pub struct SequenceIter<R: Read, T> {
+    r: ByteReader<R>,
+    framing: Framing,
+    buf: Vec<u8>,
+    finished: bool,
+    _marker: PhantomData<T>,
+}
+
+impl<R: Read, T: Unmarshalable> SequenceIter<R, T> {
+    pub fn new(reader: R, framing: Framing) -> SequenceIter<R, T> {
+        SequenceIter {
+            r: ByteReader::new(reader),
+            framing,
+            buf: Vec::new(),
+            finished: false,
+            _marker: PhantomData,
+        }
+    }
+
+    fn next_concatenated(&mut self) -> Result<Option<Document<T>>, UnmarshalError> {
+        if self.r.peek_non_whitespace()?.is_none() {
+            return Ok(None);
+        }
+        let offset = self.r.offset();
+        let value = self.r.unmarshal_value(&mut self.buf)?;
+        Ok(Some(Document { offset, value }))
+    }
+
+    fn next_text(&mut self) -> Result<Option<Document<T>>, UnmarshalError> {
+        loop {
+            match self.r.next_non_whitespace()? {
+                Some(RECORD_SEPARATOR) => (),
+                None => return Ok(None),
+                unexpected => {
+                    // Skip to the next record so the iteration can continue
+                    let error = self.r.unexpected(unexpected);
+                    self.read_record()?;
+                    return Err(error);
+                }
+            }
+
+            self.r.peek_non_whitespace()?;
+            let offset = self.r.offset();
+            let start = self.r.position();
+            self.read_record()?;
+            let len = self.buf.len()
+                - self
+                    .buf
+                    .iter()
+                    .rev()
+                    .take_while(|b| b.is_ascii_whitespace())
+                    .count();
+            if len == 0 {
+                // Empty records are ignored
+                continue;
+            }
+            let value = unmarshal_bytes(&self.buf[..len], start)?;
+            return Ok(Some(Document { offset, value }));
+        }
+    }
+
+    /// Reads bytes into the buffer up to the next record separator
+    fn read_record(&mut self) -> Result<(), UnmarshalError> {
+        self.buf.clear();
+        while let Some(b) = self.r.peek()? {
+            if b == RECORD_SEPARATOR {
+                break;
+            }
+            self.buf.push(b);
+            self.r.next()?;
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read, T: Unmarshalable> Iterator for SequenceIter<R, T> {
+    type Item = Result<Document<T>, UnmarshalError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let document = match self.framing {
+            Framing::Concatenated => self.next_concatenated(),
+            Framing::TextSequence => self.next_text(),
+        };
+        match document {
+            Ok(None) | Err(UnmarshalError::Io(_)) => self.finished = true,
+            Err(_) if self.framing == Framing::Concatenated => self.finished = true,
+            _ => (),
+        }
+        document.transpose()
+    }
+}
@@ -0,0 +1,317 @@
+use std::borrow::Cow;
+
+use crate::{pointer::push_pointer_token, raw_json::RawJson, Element, Marshalable};
+
+use super::{
+    bool::unmarshal_bool,
+    element::unmarshal_number,
+    events::Number,
+    f64::unmarshal_f64,
+    i64::unmarshal_i64,
+    list::unmarshal_items,
+    null::unmarshal_null,
+    object::unmarshal_members,
+    raw_json::unmarshal_raw,
+    string::unmarshal_cow,
+    unmarshal_iter::UnmarshalIter,
+    unmarshalable::{describe, FromElementError, UnmarshalError},
+};
+
+/// The kind of value a `Source` will read next
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueKind {
+    Object,
+    Array,
+    String,
+    Number,
+    Bool,
+    Null,
+}
+
+/// Values which `Unmarshalable` types are read from. It's implemented for
+/// JSON text by `UnmarshalIter`, and for already unmarshaled elements by
+/// `ElementSource`, so that each type only needs to be unmarshaled once.
+pub trait Source {
+    type Error;
+
+    /// Returns the kind of the next value without reading it, or `None` if
+    /// no value starts here
+    fn peek_kind(&mut self) -> Option<ValueKind>;
+
+    fn read_null(&mut self) -> Result<(), Self::Error>;
+
+    fn read_bool(&mut self) -> Result<bool, Self::Error>;
+
+    fn read_i64(&mut self) -> Result<i64, Self::Error>;
+
+    /// Reads any number as a float
+    fn read_f64(&mut self) -> Result<f64, Self::Error>;
+
+    /// Reads a number, keeping it as an int unless it has a decimal or
+    /// exponent
+    fn read_number(&mut self) -> Result<Number, Self::Error>;
+
+    fn read_string(&mut self) -> Result<String, Self::Error>;
+
+    /// Reads any value as JSON text
+    fn read_raw(&mut self) -> Result<RawJson, Self::Error>;
+
+    /// Reads an array, calling f to read each of its items
+    fn read_array<F>(&mut self, f: F) -> Result<(), Self::Error>
+    where
+        F: FnMut(&mut Self) -> Result<(), Self::Error>;
+
+    /// Reads an object, calling f with each key to read the member's value
+    fn read_object<F>(&mut self, f: F) -> Result<(), Self::Error>
+    where
+        F: FnMut(&mut Self, String) -> Result<(), Self::Error>;
+
+    fn skip_value(&mut self) -> Result<(), Self::Error>;
+
+    /// Returns an error for a next value which isn't the expected kind,
+    /// described like "an integer"
+    fn unexpected_value(&mut self, expected: &'static str) -> Self::Error;
+}
+
+impl<'a> Source for UnmarshalIter<'a> {
+    type Error = UnmarshalError;
+
+    fn peek_kind(&mut self) -> Option<ValueKind> {
+        match self.peek_non_whitespace()? {
+            '{' => Some(ValueKind::Object),
+            '[' => Some(ValueKind::Array),
+            '"' => Some(ValueKind::String),
+            '-' | '0'..='9' => Some(ValueKind::Number),
+            't' | 'f' => Some(ValueKind::Bool),
+            'n' => Some(ValueKind::Null),
+            _ => None,
+        }
+    }
+
+    fn read_null(&mut self) -> Result<(), UnmarshalError> {
+        unmarshal_null(self)
+    }
+
+    fn read_bool(&mut self) -> Result<bool, UnmarshalError> {
+        unmarshal_bool(self)
+    }
+
+    fn read_i64(&mut self) -> Result<i64, UnmarshalError> {
+        unmarshal_i64(self)
+    }
+
+    fn read_f64(&mut self) -> Result<f64, UnmarshalError> {
+        unmarshal_f64(self)
+    }
+
+    fn read_number(&mut self) -> Result<Number, UnmarshalError> {
+        unmarshal_number(self)
+    }
+
+    fn read_string(&mut self) -> Result<String, UnmarshalError> {
+        unmarshal_cow(self).map(Cow::into_owned)
+    }
+
+    fn read_raw(&mut self) -> Result<RawJson, UnmarshalError> {
+        unmarshal_raw(self)
+    }
+
+    fn read_array<F>(&mut self, f: F) -> Result<(), UnmarshalError>
+    where
+        F: FnMut(&mut Self) -> Result<(), UnmarshalError>,
+    {
+        unmarshal_items(self, f)
+    }
+
+    fn read_object<F>(&mut self, mut f: F) -> Result<(), UnmarshalError>
+    where
+        F: FnMut(&mut Self, String) -> Result<(), UnmarshalError>,
+    {
+        unmarshal_members(self, Self::read_string, |u, key, _| f(u, key))
+    }
+
+    fn skip_value(&mut self) -> Result<(), UnmarshalError> {
+        UnmarshalIter::skip_value(self)
+    }
+
+    /// Consumes the next character so the error reports its position
+    fn unexpected_value(&mut self, _expected: &'static str) -> UnmarshalError {
+        let unexpected = self.next_non_whitespace();
+        self.unexpected(unexpected)
+    }
+}
+
+/// Reads values from an already unmarshaled element, tracking the JSON
+/// Pointer of the current value for errors. Owned elements are moved out of
+/// rather than copied.
+pub struct ElementSource<'a> {
+    /// The next value, which is taken when it's read
+    element: Option<Cow<'a, Element>>,
+    pointer: String,
+}
+
+impl<'a> ElementSource<'a> {
+    pub fn new(element: Cow<'a, Element>) -> ElementSource<'a> {
+        ElementSource {
+            element: Some(element),
+            pointer: String::new(),
+        }
+    }
+
+    /// Returns the JSON Pointer of the current value
+    pub fn pointer(&self) -> &str {
+        &self.pointer
+    }
+
+    /// Takes the next value if f accepts it, otherwise leaves it to be
+    /// described by the error
+    fn take<T>(
+        &mut self,
+        expected: &'static str,
+        f: impl FnOnce(Cow<'a, Element>) -> Result<T, Cow<'a, Element>>,
+    ) -> Result<T, FromElementError> {
+        let Some(element) = self.element.take() else {
+            return Err(self.unexpected_value(expected));
+        };
+        f(element).map_err(|element| {
+            self.element = Some(element);
+            self.unexpected_value(expected)
+        })
+    }
+
+    /// Reads each child with f, adding its key or index to the pointer
+    fn read_children<F>(
+        &mut self,
+        children: impl Iterator<Item = (String, Cow<'a, Element>)>,
+        mut f: F,
+    ) -> Result<(), FromElementError>
+    where
+        F: FnMut(&mut Self, String) -> Result<(), FromElementError>,
+    {
+        for (token, child) in children {
+            let len = self.pointer.len();
+            push_pointer_token(&mut self.pointer, &token);
+            self.element = Some(child);
+            f(self, token)?;
+            self.pointer.truncate(len);
+        }
+        self.element = None;
+        Ok(())
+    }
+}
+
+impl<'a> Source for ElementSource<'a> {
+    type Error = FromElementError;
+
+    fn peek_kind(&mut self) -> Option<ValueKind> {
+        Some(match self.element.as_deref()? {
+            Element::JsonObject(_) => ValueKind::Object,
+            Element::JsonList(_) => ValueKind::Array,
+            Element::JsonString(_) => ValueKind::String,
+            Element::JsonInt(_) | Element::JsonFloat(_) => ValueKind::Number,
+            Element::JsonBool(_) => ValueKind::Bool,
+            Element::JsonNull => ValueKind::Null,
+        })
+    }
+
+    fn read_null(&mut self) -> Result<(), FromElementError> {
+        self.take("null", |e| match *e {
+            Element::JsonNull => Ok(()),
+            _ => Err(e),
+        })
+    }
+
+    fn read_bool(&mut self) -> Result<bool, FromElementError> {
+        self.take("a boolean", |e| match *e {
+            Element::JsonBool(b) => Ok(b),
+            _ => Err(e),
+        })
+    }
+
+    fn read_i64(&mut self) -> Result<i64, FromElementError> {
+        self.take("an integer", |e| match *e {
+            Element::JsonInt(i) => Ok(i),
+            _ => Err(e),
+        })
+    }
+
+    fn read_f64(&mut self) -> Result<f64, FromElementError> {
+        self.take("a number", |e| match *e {
+            Element::JsonFloat(f) => Ok(f),
+            Element::JsonInt(i) => Ok(i as f64),
+            _ => Err(e),
+        })
+    }
+
+    fn read_number(&mut self) -> Result<Number, FromElementError> {
+        self.take("a number", |e| match *e {
+            Element::JsonFloat(f) => Ok(Number::Float(f)),
+            Element::JsonInt(i) => Ok(Number::Int(i)),
+            _ => Err(e),
+        })
+    }
+
+    fn read_string(&mut self) -> Result<String, FromElementError> {
+        self.take("a string", |e| match e {
+            Cow::Owned(Element::JsonString(s)) => Ok(s),
+            Cow::Borrowed(Element::JsonString(s)) => Ok(s.clone()),
+            e => Err(e),
+        })
+    }
+
+    fn read_raw(&mut self) -> Result<RawJson, FromElementError> {
+        self.take("a value", |e| Ok(RawJson(e.marshal_json())))
+    }
+
+    fn read_array<F>(&mut self, mut f: F) -> Result<(), FromElementError>
+    where
+        F: FnMut(&mut Self) -> Result<(), FromElementError>,
+    {
+        let list = self.take("an array", |e| match e {
+            Cow::Owned(Element::JsonList(l)) => Ok(Cow::Owned(l)),
+            Cow::Borrowed(Element::JsonList(l)) => Ok(Cow::Borrowed(l)),
+            e => Err(e),
+        })?;
+        let indexed = |(i, e)| (usize::to_string(&i), e);
+        match list {
+            Cow::Owned(l) => self.read_children(
+                l.into_iter().map(Cow::Owned).enumerate().map(indexed),
+                |s, _| f(s),
+            ),
+            Cow::Borrowed(l) => self.read_children(
+                l.iter().map(Cow::Borrowed).enumerate().map(indexed),
+                |s, _| f(s),
+            ),
+        }
+    }
+
+    fn read_object<F>(&mut self, f: F) -> Result<(), FromElementError>
+    where
+        F: FnMut(&mut Self, String) -> Result<(), FromElementError>,
+    {
+        let object = self.take("an object", |e| match e {
+            Cow::Owned(Element::JsonObject(o)) => Ok(Cow::Owned(o)),
+            Cow::Borrowed(Element::JsonObject(o)) => Ok(Cow::Borrowed(o)),
+            e => Err(e),
+        })?;
+        match object {
+            Cow::Owned(o) => self.read_children(o.into_iter().map(|(k, v)| (k, Cow::Owned(v))), f),
+            Cow::Borrowed(o) => {
+                self.read_children(o.iter().map(|(k, v)| (k.clone(), Cow::Borrowed(v))), f)
+            }
+        }
+    }
+
+    fn skip_value(&mut self) -> Result<(), FromElementError> {
+        self.take("a value", |_| Ok(()))
+    }
+
+    /// A value which was already read is described as "nothing"
+    fn unexpected_value(&mut self, expected: &'static str) -> FromElementError {
+        FromElementError::InvalidType {
+            pointer: self.pointer.clone(),
+            expected,
+            found: self.element.as_deref().map_or("nothing", describe),
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use std::{collections::HashMap, str::Chars};
+
+use crate::{pointer::push_pointer_token, Element, Unmarshalable};
+
+use super::{
+    list::unmarshal_items,
+    object::unmarshal_members,
+    unmarshal_iter::{Position, UnmarshalIter},
+    unmarshalable::UnmarshalError,
+};
+
+/// The source range of an unmarshaled value. The end position is exclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: Position,
+    pub end: Position,
+}
+
+/// Source locations of every node and object key in an unmarshaled document,
+/// indexed by JSON Pointer.
+#[derive(Debug, Default, PartialEq)]
+pub struct Spans {
+    values: HashMap<String, Span>,
+    keys: HashMap<String, Span>,
+}
+
+impl Spans {
+    /// Returns the span of the value at the pointer. The root value is
+    /// located at the empty pointer "".
+    pub fn value(&self, pointer: &str) -> Option<&Span> {
+        self.values.get(pointer)
+    }
+
+    /// Returns the span of the quoted key of the object member at the pointer
+    pub fn key(&self, pointer: &str) -> Option<&Span> {
+        self.keys.get(pointer)
+    }
+
+    /// Iterates over the pointers of every recorded value
+    pub fn pointers(&self) -> impl Iterator<Item = &str> {
+        self.values.keys().map(String::as_str)
+    }
+}
+
+impl Element {
+    /// Unmarshals an element, also recording where each value and object key
+    /// was found in the input.
+    pub fn unmarshal_json_spanned(chars: Chars) -> Result<(Element, Spans), UnmarshalError> {
+        let mut u = UnmarshalIter::new(chars);
+        let mut spans = Spans::default();
+        let element = unmarshal_spanned(&mut u, &mut String::new(), &mut spans)?;
+        u.check_finished()?;
+        Ok((element, spans))
+    }
+}
+
+fn unmarshal_spanned(
+    u: &mut UnmarshalIter,
+    pointer: &mut String,
+    spans: &mut Spans,
+) -> Result<Element, UnmarshalError> {
+    let first = u.peek_non_whitespace().cloned();
+    let start = u.position();
+    let element = match first {
+        Some('{') => {
+            let mut object = HashMap::new();
+            unmarshal_members(u, String::unmarshal_json_with_state, |u, key, key_range| {
+                let len = pointer.len();
+                push_pointer_token(pointer, &key);
+                spans.keys.insert(
+                    pointer.clone(),
+                    Span {
+                        start: u.position_at(key_range.start),
+                        end: u.position_at(key_range.end),
+                    },
+                );
+                object.insert(key, unmarshal_spanned(u, pointer, spans)?);
+                pointer.truncate(len);
+                Ok(())
+            })?;
+            Element::JsonObject(object)
+        }
+        Some('[') => {
+            let mut list = Vec::new();
+            unmarshal_items(u, |u| {
+                let len = pointer.len();
+                push_pointer_token(pointer, &list.len().to_string());
+                list.push(unmarshal_spanned(u, pointer, spans)?);
+                pointer.truncate(len);
+                Ok(())
+            })?;
+            Element::JsonList(list)
+        }
+        _ => Element::unmarshal_json_with_state(u)?,
+    };
+    spans.values.insert(
+        pointer.clone(),
+        Span {
+            start,
+            end: u.position(),
+        },
+    );
+    Ok(element)
+}
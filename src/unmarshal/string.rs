@@ -1,19 +1,39 @@
+use std::borrow::Cow;
+
 use crate::Unmarshalable;
 
-use super::{unmarshal_iter::UnmarshalIter, unmarshalable::UnmarshalError};
+use super::{source::Source, unmarshal_iter::UnmarshalIter, unmarshalable::UnmarshalError};
 
 impl Unmarshalable for String {
-    fn unmarshal_json_with_state(u: &mut UnmarshalIter) -> Result<Self, UnmarshalError> {
-        let first = u.next_non_whitespace();
-        if first != Some('"') {
-            return Err(u.unexpected(first));
-        }
+    fn unmarshal_json_with_state<S: Source>(u: &mut S) -> Result<Self, S::Error> {
+        u.read_string()
+    }
+}
+
+/// Unmarshals a string, borrowing it from the input if it has no escapes
+pub(crate) fn unmarshal_cow<'a>(u: &mut UnmarshalIter<'a>) -> Result<Cow<'a, str>, UnmarshalError> {
+    let first = u.next_non_whitespace();
+    if first != Some('"') {
+        return Err(u.unexpected(first));
+    }
 
-        let mut out = String::new();
-        while let Some(c) = u.next() {
-            match c {
-                '"' => return Ok(out),
-                '\\' => match u.next() {
+    let start = u.offset();
+    let mut out = None::<String>;
+    loop {
+        let run = u.next_str_run()?;
+        if let Some(out) = &mut out {
+            out.push_str(run);
+        }
+        match u.next() {
+            Some('"') => {
+                return Ok(match out {
+                    Some(out) => Cow::Owned(out),
+                    None => Cow::Borrowed(u.str_since(start, 1)),
+                })
+            }
+            Some('\\') => {
+                let out = out.get_or_insert_with(|| u.str_since(start, 1).to_owned());
+                match u.next() {
                     Some(escaped @ ('\\' | '"' | '/')) => out.push(escaped),
                     Some('b') => out.push('\x08'), // Literal backspace
                     Some('f') => out.push('\x0c'), // Formfeed
@@ -22,11 +42,16 @@ impl Unmarshalable for String {
                     Some('t') => out.push('\t'),
                     Some('u') => out.push(try_read_unicode_escape(u)?),
                     unexpected => return Err(u.unexpected(unexpected)),
-                },
-                _ => out.push(c),
+                }
+            }
+            // Control characters are allowed unescaped
+            Some(c) => {
+                if let Some(out) = &mut out {
+                    out.push(c);
+                }
             }
+            None => return Err(u.end_of_chars()),
         }
-        Err(UnmarshalError::EndOfChars)
     }
 }
 
@@ -1,36 +1,147 @@
-use std::{iter::Peekable, str::Chars};
+use std::{cell::Cell, str, str::Chars};
 
-use super::unmarshalable::UnmarshalError;
+use super::{scan, unmarshalable::UnmarshalError};
+
+/// A location in the unmarshaled input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    /// Byte offset from the start of the input
+    pub offset: usize,
+    /// Line number, starting at 1
+    pub row: u32,
+    /// Column in characters, starting at 1
+    pub col: u32,
+    /// Column in UTF-8 bytes, starting at 1
+    pub col_utf8: u32,
+    /// Column in UTF-16 code units, starting at 1
+    pub col_utf16: u32,
+}
+
+impl Position {
+    fn start() -> Position {
+        Position {
+            offset: 0,
+            row: 1,
+            col: 1,
+            col_utf8: 1,
+            col_utf16: 1,
+        }
+    }
+
+    /// Moves the position forward over some bytes. Columns are counted from
+    /// the lead byte of each UTF-8 sequence, so the bytes don't need to be
+    /// validated.
+    fn advance(&mut self, bytes: &[u8]) {
+        self.offset += bytes.len();
+        for &b in bytes {
+            match b {
+                b'\n' => {
+                    self.row += 1;
+                    self.col = 1;
+                    self.col_utf8 = 1;
+                    self.col_utf16 = 1;
+                    continue;
+                }
+                // Continuation bytes
+                0x80..=0xbf => (),
+                // Characters outside the BMP are surrogate pairs in UTF-16
+                0xf0..=0xff => {
+                    self.col += 1;
+                    self.col_utf16 += 2;
+                }
+                _ => {
+                    self.col += 1;
+                    self.col_utf16 += 1;
+                }
+            }
+            self.col_utf8 += 1;
+        }
+    }
+}
 
 /// Stores the remaining characters for unmarshaling.
+///
+/// The input is read as bytes. Rows and columns are only computed when a
+/// position is requested, and UTF-8 is only validated for input that didn't
+/// come from a `str`.
 pub struct UnmarshalIter<'a> {
-    chars: Peekable<Chars<'a>>,
-    row: u32,
-    col: u32,
+    bytes: &'a [u8],
+    /// Whether the bytes are known to be valid UTF-8
+    validated: bool,
+    /// Offset of the next character to be read
+    pos: usize,
+    /// Offset of the last character that was read, used for errors
+    last: usize,
+    /// The next character, so that peek can return a reference
+    peeked: Option<char>,
+    /// Offset of invalid UTF-8 which ended the input early
+    invalid_utf8: Option<usize>,
+    /// The last position computed, which later positions are counted from
+    cached: Cell<Position>,
 }
 
 impl<'a> UnmarshalIter<'a> {
-    pub fn new(chars: Chars) -> UnmarshalIter {
+    pub fn new(chars: Chars<'a>) -> UnmarshalIter<'a> {
+        UnmarshalIter::with_bytes(chars.as_str().as_bytes(), true)
+    }
+
+    /// Unmarshals from bytes, validating that they are UTF-8 as they're read
+    pub fn from_bytes(bytes: &'a [u8]) -> UnmarshalIter<'a> {
+        UnmarshalIter::with_bytes(bytes, false)
+    }
+
+    fn with_bytes(bytes: &'a [u8], validated: bool) -> UnmarshalIter<'a> {
         UnmarshalIter {
-            chars: chars.peekable(),
-            row: 1,
-            col: 0,
+            bytes,
+            validated,
+            pos: 0,
+            last: 0,
+            peeked: None,
+            invalid_utf8: None,
+            cached: Cell::new(Position::start()),
         }
     }
 
-    pub fn next(&mut self) -> Option<char> {
-        let c = self.chars.next();
-        if Some('\n') != c {
-            self.col += 1;
-        } else {
-            self.row += 1;
-            self.col = 1;
+    /// Decodes the character at the offset, returning it with its length
+    fn decode(&mut self, offset: usize) -> Option<(char, usize)> {
+        let b = *self.bytes.get(offset)?;
+        if b.is_ascii() {
+            return Some((b as char, 1));
+        }
+        let len = match b {
+            0xc2..=0xdf => 2,
+            0xe0..=0xef => 3,
+            0xf0..=0xf4 => 4,
+            _ => 0,
+        };
+        let decoded = self
+            .bytes
+            .get(offset..offset + len)
+            .filter(|_| len > 0)
+            .and_then(|bytes| str::from_utf8(bytes).ok())
+            .and_then(|s| s.chars().next());
+        match decoded {
+            Some(c) => Some((c, len)),
+            None => {
+                // Invalid UTF-8 ends the input, and is reported instead of
+                // the end of the characters
+                self.invalid_utf8 = Some(offset);
+                None
+            }
         }
-        c
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<char> {
+        let (c, len) = self.decode(self.pos)?;
+        self.last = self.pos;
+        self.pos += len;
+        Some(c)
     }
 
     pub fn peek(&mut self) -> Option<&char> {
-        self.chars.peek()
+        self.peeked = self.decode(self.pos).map(|(c, _)| c);
+        self.peeked.as_ref()
     }
 
     pub fn next_non_whitespace(&mut self) -> Option<char> {
@@ -39,41 +150,205 @@ impl<'a> UnmarshalIter<'a> {
     }
 
     pub fn peek_non_whitespace(&mut self) -> Option<&char> {
-        while let Some(_) = self.peek().filter(|c| c.is_whitespace()) {
-            self.next();
+        loop {
+            let skipped = scan::skip_whitespace(&self.bytes[self.pos..]);
+            if skipped > 0 {
+                self.last = self.pos + skipped - 1;
+                self.pos += skipped;
+            }
+            match self.bytes.get(self.pos) {
+                Some(b) if !b.is_ascii() => match self.decode(self.pos) {
+                    Some((c, len)) if c.is_whitespace() => {
+                        self.last = self.pos;
+                        self.pos += len;
+                    }
+                    _ => break,
+                },
+                _ => break,
+            }
         }
         self.peek()
     }
 
+    /// Reads characters up to the next quote, backslash, or control
+    /// character, for unmarshaling the contents of strings in bulk
+    pub fn next_str_run(&mut self) -> Result<&'a str, UnmarshalError> {
+        let bytes = self.bytes;
+        let start = self.pos;
+        let len = scan::find_string_special(&bytes[start..]);
+        let run = &bytes[start..start + len];
+        let run = if self.validated {
+            // SAFETY: the bytes came from a str, and the run starts and ends
+            // next to ASCII characters or the ends of the input, so it's on
+            // character boundaries
+            unsafe { str::from_utf8_unchecked(run) }
+        } else {
+            match scan::validate_utf8(run) {
+                // SAFETY: the run was just validated
+                Ok(()) => unsafe { str::from_utf8_unchecked(run) },
+                Err(valid) => {
+                    self.invalid_utf8 = Some(start + valid);
+                    return Err(self.end_of_chars());
+                }
+            }
+        };
+        self.pos += len;
+        Ok(run)
+    }
+
+    /// Returns the byte offset of the next character to be read
+    pub fn offset(&self) -> usize {
+        self.pos
+    }
+
+    /// Moves to an offset on a character boundary, so later characters are
+    /// read from there
+    pub(crate) fn seek(&mut self, offset: usize) {
+        self.pos = offset;
+        self.last = offset;
+    }
+
+    /// Returns the input from the offset up to the next character to be
+    /// read, excluding some number of ASCII characters at the end
+    pub(crate) fn str_since(&self, start: usize, exclude: usize) -> &'a str {
+        let bytes = &self.bytes[start..self.pos - exclude];
+        // SAFETY: everything that has been read was validated, and the slice
+        // starts and ends next to characters that have been read
+        unsafe { str::from_utf8_unchecked(bytes) }
+    }
+
+    /// Returns the position of the next character to be read
+    pub fn position(&self) -> Position {
+        self.position_at(self.pos)
+    }
+
+    /// Computes the position of an offset which has already been read
+    pub fn position_at(&self, offset: usize) -> Position {
+        let mut pos = self.cached.get();
+        if offset < pos.offset {
+            pos = Position::start();
+        }
+        pos.advance(&self.bytes[pos.offset..offset]);
+        self.cached.set(pos);
+        pos
+    }
+
     /// Returns an error if any unmarshaled characters remain in the iterator
     pub fn check_finished(&mut self) -> Result<(), UnmarshalError> {
         match self.next_non_whitespace() {
-            None => Ok(()),
-            Some(c) => Err(self.unexpected_char(c)),
+            None if self.invalid_utf8.is_none() => Ok(()),
+            unexpected => Err(self.unexpected(unexpected)),
+        }
+    }
+
+    /// Skips over the next value without unmarshaling it. Only balanced
+    /// brackets and terminated strings are checked, so the skipped value is
+    /// not fully validated.
+    pub fn skip_value(&mut self) -> Result<(), UnmarshalError> {
+        let mut brackets = Vec::new();
+        loop {
+            match self.peek_non_whitespace().cloned() {
+                Some(open @ ('{' | '[')) => {
+                    self.next();
+                    brackets.push(if open == '{' { '}' } else { ']' });
+                }
+                Some(close @ ('}' | ']')) => {
+                    self.next();
+                    if brackets.pop() != Some(close) {
+                        return Err(self.unexpected_char(close));
+                    }
+                }
+                Some(',' | ':') if !brackets.is_empty() => {
+                    self.next();
+                }
+                Some('"') => {
+                    self.next();
+                    self.skip_string_body()?;
+                }
+                Some(_) => {
+                    // Literals and numbers run until the next delimiter
+                    let start = self.pos;
+                    while let Some(&c) = self.peek() {
+                        if c.is_whitespace() || matches!(c, ',' | ':' | '{' | '}' | '[' | ']' | '"')
+                        {
+                            break;
+                        }
+                        self.next();
+                    }
+                    if self.pos == start {
+                        let unexpected = self.next();
+                        return Err(self.unexpected(unexpected));
+                    }
+                }
+                None => return Err(self.end_of_chars()),
+            }
+            if brackets.is_empty() {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Skips the remaining bytes of a string after its opening quote
+    fn skip_string_body(&mut self) -> Result<(), UnmarshalError> {
+        loop {
+            let i = self.pos + scan::find_string_special(&self.bytes[self.pos..]);
+            match self.bytes.get(i) {
+                Some(&b) => {
+                    self.last = i;
+                    self.pos = i + 1;
+                    if b == b'"' {
+                        return Ok(());
+                    } else if b == b'\\' {
+                        // Skip the escaped character
+                        self.next();
+                    }
+                }
+                None => {
+                    self.pos = i;
+                    return Err(self.end_of_chars());
+                }
+            }
         }
     }
 
     /// Attempts to read the next character as a digit
     pub fn try_next_digit(&mut self, radix: u32) -> Result<u32, UnmarshalError> {
-        self.next().map_or(Err(UnmarshalError::EndOfChars), |c| {
-            c.to_digit(radix).ok_or_else(|| self.unexpected_char(c))
-        })
+        match self.next() {
+            Some(c) => c.to_digit(radix).ok_or_else(|| self.unexpected_char(c)),
+            None => Err(self.end_of_chars()),
+        }
+    }
+
+    /// Returns an error for reaching the end of the input, or for invalid
+    /// UTF-8 if that's what ended it
+    pub fn end_of_chars(&self) -> UnmarshalError {
+        match self.invalid_utf8 {
+            Some(offset) => {
+                let pos = self.position_at(offset);
+                UnmarshalError::InvalidUtf8 {
+                    row: pos.row,
+                    col: pos.col,
+                }
+            }
+            None => UnmarshalError::EndOfChars,
+        }
     }
 
     /// Returns an error for some unexpected value that was read
     pub fn unexpected(&self, got: Option<char>) -> UnmarshalError {
         match got {
             Some(c) => self.unexpected_char(c),
-            None => UnmarshalError::EndOfChars,
+            None => self.end_of_chars(),
         }
     }
 
     /// Returns an error for some unexpected character that was read
     pub fn unexpected_char(&self, c: char) -> UnmarshalError {
-        return UnmarshalError::UnexpectedChar {
+        let pos = self.position_at(self.last);
+        UnmarshalError::UnexpectedChar {
             c,
-            row: self.row,
-            col: self.col,
-        };
+            row: pos.row,
+            col: pos.col,
+        }
     }
 }
@@ -1,23 +1,134 @@
-use std::str::Chars;
+use std::{borrow::Cow, error, fmt, io, str::Chars};
 
-use super::unmarshal_iter::UnmarshalIter;
+use crate::Element;
+
+use super::{
+    source::{ElementSource, Source},
+    unmarshal_iter::UnmarshalIter,
+};
 
 pub trait Unmarshalable: Sized {
-    /// Unmarshals the type using characters provided from the unmarshaler.
-    /// Should exhaust all characters necessary to unmarshal the type, and
-    /// leave any remaining characters to allow for nested unmarshaling.
-    fn unmarshal_json_with_state(u: &mut UnmarshalIter) -> Result<Self, UnmarshalError>;
+    /// Unmarshals the type from values provided by the source, which is
+    /// either JSON text or an `Element`. Should read exactly one value, and
+    /// leave anything after it to allow for nested unmarshaling.
+    fn unmarshal_json_with_state<S: Source>(u: &mut S) -> Result<Self, S::Error>;
 
     fn unmarshal_json(chars: Chars) -> Result<Self, UnmarshalError> {
-        let mut u = UnmarshalIter::new(chars);
-        let unmarshaled = Self::unmarshal_json_with_state(&mut u)?;
+        Self::unmarshal_json_complete(&mut UnmarshalIter::new(chars))
+    }
+
+    /// Unmarshals from bytes, which are validated as UTF-8 while they're read
+    fn unmarshal_json_bytes(bytes: &[u8]) -> Result<Self, UnmarshalError> {
+        Self::unmarshal_json_complete(&mut UnmarshalIter::from_bytes(bytes))
+    }
+
+    /// Unmarshals the type, expecting no characters to remain after it
+    fn unmarshal_json_complete(u: &mut UnmarshalIter) -> Result<Self, UnmarshalError> {
+        let unmarshaled = Self::unmarshal_json_with_state(u)?;
         u.check_finished()?;
         Ok(unmarshaled)
     }
+
+    /// Converts an element which has already been unmarshaled, moving out of
+    /// it when it's owned
+    fn from_element_cow(element: Cow<Element>) -> Result<Self, FromElementError> {
+        Self::unmarshal_json_with_state(&mut ElementSource::new(element))
+    }
+
+    fn from_element(element: Element) -> Result<Self, FromElementError> {
+        Self::from_element_cow(Cow::Owned(element))
+    }
+
+    fn from_element_ref(element: &Element) -> Result<Self, FromElementError> {
+        Self::from_element_cow(Cow::Borrowed(element))
+    }
 }
 
 #[derive(Debug, PartialEq)]
 pub enum UnmarshalError {
-    UnexpectedChar { c: char, row: u32, col: u32 },
+    UnexpectedChar {
+        c: char,
+        row: u32,
+        col: u32,
+    },
     EndOfChars,
+    /// Reading from an `io::Read` failed
+    Io(io::ErrorKind),
+    /// The input read as bytes was not valid UTF-8
+    InvalidUtf8 {
+        row: u32,
+        col: u32,
+    },
+    /// A key in the requested path wasn't found in its object
+    KeyNotFound(String),
+    /// A string unmarshaled as a `&str` contained escapes, so it couldn't be
+    /// borrowed from the input
+    EscapedBorrow {
+        row: u32,
+        col: u32,
+    },
+    /// The input is too large to be stored in the type being unmarshaled
+    TooLarge,
+}
+
+impl From<io::Error> for UnmarshalError {
+    fn from(e: io::Error) -> Self {
+        UnmarshalError::Io(e.kind())
+    }
+}
+
+/// An error converting an `Element` into a type. The pointer locates the
+/// value which couldn't be converted.
+#[derive(Debug, PartialEq)]
+pub enum FromElementError {
+    InvalidType {
+        pointer: String,
+        /// A description of the expected value, like "an integer"
+        expected: &'static str,
+        /// A description of the value found instead, like "a string"
+        found: &'static str,
+    },
+}
+
+impl FromElementError {
+    pub(crate) fn invalid_type(expected: &'static str, found: &Element) -> Self {
+        FromElementError::InvalidType {
+            pointer: String::new(),
+            expected,
+            found: describe(found),
+        }
+    }
+}
+
+impl fmt::Display for FromElementError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FromElementError::InvalidType {
+                pointer,
+                expected,
+                found,
+            } => {
+                f.write_str("invalid type")?;
+                if !pointer.is_empty() {
+                    write!(f, " at {pointer}")?;
+                }
+                write!(f, ": expected {expected}, found {found}")
+            }
+        }
+    }
+}
+
+impl error::Error for FromElementError {}
+
+/// Describes the kind of an element for errors, like "an integer"
+pub(crate) fn describe(element: &Element) -> &'static str {
+    match element {
+        Element::JsonObject(_) => "an object",
+        Element::JsonList(_) => "an array",
+        Element::JsonString(_) => "a string",
+        Element::JsonInt(_) => "an integer",
+        Element::JsonFloat(_) => "a float",
+        Element::JsonBool(_) => "a boolean",
+        Element::JsonNull => "null",
+    }
 }
@@ -0,0 +1,108 @@
+use std::{
+    collections::HashMap,
+    io::{self, Read},
+};
+
+use json::{unmarshal::ArrayIter, Element, UnmarshalError};
+
+#[test]
+fn array_iter_ints_test() {
+    let iter = ArrayIter::<_, i64>::new(" [1, 2,\n3 ] ".as_bytes());
+    assert_eq!(iter.collect::<Result<Vec<_>, _>>().unwrap(), vec![1, 2, 3]);
+}
+
+#[test]
+fn array_iter_empty_test() {
+    let mut iter = ArrayIter::<_, i64>::new("[ ]".as_bytes());
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn array_iter_objects_test() {
+    let input = r#"[{"a": "]}"}, {"b": [1, {}]}, null, "🦀"]"#;
+    let elements: Vec<Element> = ArrayIter::new(input.as_bytes())
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(
+        elements,
+        vec![
+            Element::JsonObject(HashMap::from([(
+                "a".to_owned(),
+                Element::JsonString("]}".to_owned())
+            )])),
+            Element::JsonObject(HashMap::from([(
+                "b".to_owned(),
+                Element::JsonList(vec![
+                    Element::JsonInt(1),
+                    Element::JsonObject(HashMap::new())
+                ])
+            )])),
+            Element::JsonNull,
+            Element::JsonString("🦀".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn array_iter_at_path_test() {
+    let input = r#"{"meta": {"items": [0]}, "data": {"count": 2, "items": [4, 5]}} trailing"#;
+    let iter = ArrayIter::<_, i64>::at_path(input.as_bytes(), &["data", "items"]);
+    assert_eq!(iter.collect::<Result<Vec<_>, _>>().unwrap(), vec![4, 5]);
+}
+
+#[test]
+fn array_iter_at_path_large_sibling_test() {
+    // Siblings on the path are skipped as they're read, so they can be
+    // larger than anything that would be buffered
+    let input = r#"{"skip": ["]", ""#
+        .as_bytes()
+        .chain(io::repeat(b'x').take(16 << 20))
+        .chain(r#"", {"a": ["}"]}], "items": [1, 2]}"#.as_bytes());
+    let iter = ArrayIter::<_, i64>::at_path(input, &["items"]);
+    assert_eq!(iter.collect::<Result<Vec<_>, _>>().unwrap(), vec![1, 2]);
+}
+
+#[test]
+fn array_iter_missing_key_test() {
+    let mut iter = ArrayIter::<_, i64>::at_path(r#"{"a": []}"#.as_bytes(), &["b"]);
+    assert_eq!(
+        iter.next().unwrap().unwrap_err(),
+        UnmarshalError::KeyNotFound("b".to_owned())
+    );
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn array_iter_element_error_test() {
+    let mut iter = ArrayIter::<_, i64>::new("[1,\n  2x, 3]".as_bytes());
+    assert_eq!(iter.next().unwrap().unwrap(), 1);
+    assert_eq!(
+        iter.next().unwrap().unwrap_err(),
+        UnmarshalError::UnexpectedChar {
+            c: 'x',
+            row: 2,
+            col: 4
+        }
+    );
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn array_iter_unterminated_test() {
+    let mut iter = ArrayIter::<_, i64>::new("[1, 2".as_bytes());
+    assert_eq!(iter.next().unwrap().unwrap(), 1);
+    assert_eq!(iter.next().unwrap().unwrap(), 2);
+    assert_eq!(
+        iter.next().unwrap().unwrap_err(),
+        UnmarshalError::EndOfChars
+    );
+}
+
+#[test]
+fn array_iter_invalid_utf8_test() {
+    let mut iter = ArrayIter::<_, String>::new(&b"[\"a\xffb\"]"[..]);
+    assert_eq!(
+        iter.next().unwrap().unwrap_err(),
+        UnmarshalError::InvalidUtf8 { row: 1, col: 4 }
+    );
+}
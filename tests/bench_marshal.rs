@@ -1,5 +1,4 @@
 #![feature(test)]
-#![feature(bench_black_box)]
 
 extern crate test;
 mod test_data;
@@ -43,3 +42,25 @@ fn bench_menu2(b: &mut Bencher) {
     let marshal = black_box(json::Element::unmarshal_json(test_data::MENU2.chars()).unwrap());
     b.iter(|| marshal_repeatedly(&marshal));
 }
+
+fn non_ascii_corpus(text: &str) -> json::Element {
+    json::Element::JsonList(
+        (0..10)
+            .map(|i| json::Element::JsonString(format!("{i}: {}", text.repeat(2))))
+            .collect(),
+    )
+}
+
+#[bench]
+fn bench_cjk(b: &mut Bencher) {
+    let marshal = black_box(non_ascii_corpus(
+        "日本語のテキストと中文文本，한국어 텍스트。",
+    ));
+    b.iter(|| marshal_repeatedly(&marshal));
+}
+
+#[bench]
+fn bench_emoji(b: &mut Bencher) {
+    let marshal = black_box(non_ascii_corpus("🦀🚀 emoji ❤️👍🏽 mixed with text 🎉"));
+    b.iter(|| marshal_repeatedly(&marshal));
+}
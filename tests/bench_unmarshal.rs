@@ -0,0 +1,70 @@
+#![feature(test)]
+
+extern crate test;
+mod test_data;
+use std::hint::black_box;
+
+use json::{self, tape::TapeDocument, Unmarshalable};
+use test::Bencher;
+
+fn unmarshal_repeatedly(s: &str) {
+    for _ in 0..1000 {
+        json::Element::unmarshal_json(black_box(s).chars()).unwrap();
+    }
+}
+
+fn unmarshal_tape_repeatedly(s: &str) {
+    for _ in 0..1000 {
+        TapeDocument::unmarshal_json(black_box(s).chars()).unwrap();
+    }
+}
+
+#[bench]
+fn bench_glossary(b: &mut Bencher) {
+    b.iter(|| unmarshal_repeatedly(test_data::GLOSSARY));
+}
+
+#[bench]
+fn bench_menu1(b: &mut Bencher) {
+    b.iter(|| unmarshal_repeatedly(test_data::MENU1));
+}
+
+#[bench]
+fn bench_widget(b: &mut Bencher) {
+    b.iter(|| unmarshal_repeatedly(test_data::WIDGET));
+}
+
+#[bench]
+fn bench_web_app(b: &mut Bencher) {
+    b.iter(|| unmarshal_repeatedly(test_data::WEB_APP));
+}
+
+#[bench]
+fn bench_menu2(b: &mut Bencher) {
+    b.iter(|| unmarshal_repeatedly(test_data::MENU2));
+}
+
+#[bench]
+fn bench_tape_glossary(b: &mut Bencher) {
+    b.iter(|| unmarshal_tape_repeatedly(test_data::GLOSSARY));
+}
+
+#[bench]
+fn bench_tape_menu1(b: &mut Bencher) {
+    b.iter(|| unmarshal_tape_repeatedly(test_data::MENU1));
+}
+
+#[bench]
+fn bench_tape_widget(b: &mut Bencher) {
+    b.iter(|| unmarshal_tape_repeatedly(test_data::WIDGET));
+}
+
+#[bench]
+fn bench_tape_web_app(b: &mut Bencher) {
+    b.iter(|| unmarshal_tape_repeatedly(test_data::WEB_APP));
+}
+
+#[bench]
+fn bench_tape_menu2(b: &mut Bencher) {
+    b.iter(|| unmarshal_tape_repeatedly(test_data::MENU2));
+}
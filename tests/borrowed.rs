@@ -0,0 +1,98 @@
+mod test_data;
+
+use std::{borrow::Cow, collections::HashMap};
+
+use json::{
+    unmarshal::UnmarshalIter, BorrowedElement, Element, Marshalable, UnmarshalError, Unmarshalable,
+    UnmarshalableBorrowed,
+};
+
+#[test]
+fn borrowed_str_test() {
+    let input = r#"["a", "🦀 b"]"#;
+    let strs = Vec::<&str>::unmarshal_json_borrowed(input).unwrap();
+    assert_eq!(strs, vec!["a", "🦀 b"]);
+    // The strings point into the input
+    assert!(input.as_bytes().as_ptr_range().contains(&strs[1].as_ptr()));
+}
+
+#[test]
+fn borrowed_str_escape_test() {
+    assert_eq!(
+        Vec::<&str>::unmarshal_json_borrowed("[\"a\",\n  \"b\\n\"]").unwrap_err(),
+        UnmarshalError::EscapedBorrow { row: 2, col: 3 }
+    );
+}
+
+#[test]
+fn borrowed_cow_test() {
+    let cows = Vec::<Cow<str>>::unmarshal_json_borrowed(r#"["plain", "esc\"aped"]"#).unwrap();
+    assert!(matches!(cows[0], Cow::Borrowed("plain")));
+    assert!(matches!(&cows[1], Cow::Owned(s) if s == "esc\"aped"));
+}
+
+#[test]
+fn borrowed_struct_fields_test() {
+    // A user type with borrowed fields
+    #[derive(Debug, PartialEq)]
+    struct Entry<'a> {
+        id: &'a str,
+        count: i64,
+    }
+
+    impl<'a> UnmarshalableBorrowed<'a> for Entry<'a> {
+        fn unmarshal_json_borrowed_with_state(
+            u: &mut UnmarshalIter<'a>,
+        ) -> Result<Self, UnmarshalError> {
+            let mut fields =
+                HashMap::<&'a str, BorrowedElement<'a>>::unmarshal_json_borrowed_with_state(u)?;
+            match (fields.remove("id"), fields.remove("count")) {
+                (
+                    Some(BorrowedElement::JsonString(Cow::Borrowed(id))),
+                    Some(BorrowedElement::JsonInt(count)),
+                ) => Ok(Entry { id, count }),
+                _ => Err(UnmarshalError::EndOfChars),
+            }
+        }
+    }
+
+    assert_eq!(
+        Vec::<Entry>::unmarshal_json_borrowed(r#"[{"id": "x", "count": 2}]"#).unwrap(),
+        vec![Entry { id: "x", count: 2 }]
+    );
+}
+
+#[test]
+fn borrowed_element_matches_owned_test() {
+    for data in [
+        test_data::GLOSSARY,
+        test_data::MENU1,
+        test_data::WIDGET,
+        test_data::WEB_APP,
+        test_data::MENU2,
+    ] {
+        let borrowed = BorrowedElement::unmarshal_json_borrowed(data).unwrap();
+        let owned = Element::unmarshal_json(data.chars()).unwrap();
+        assert_eq!(borrowed.to_element(), owned);
+        assert_eq!(
+            Element::unmarshal_json(borrowed.marshal_json().chars()).unwrap(),
+            owned
+        );
+        assert_eq!(borrowed.into_owned(), owned);
+    }
+}
+
+#[test]
+fn borrowed_element_option_test() {
+    assert_eq!(
+        Option::<&str>::unmarshal_json_borrowed("null").unwrap(),
+        None
+    );
+    assert_eq!(
+        BorrowedElement::unmarshal_json_borrowed(r#"{"k": [1, 2.5, true, null]}"#)
+            .unwrap()
+            .into_owned()
+            .marshal_json(),
+        r#"{"k": [1, 2.5, true, null]}"#
+    );
+}
@@ -0,0 +1,293 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use json::{
+    json, unmarshal::Source, BorrowedElement, Element, FromElementError, Marshalable, RawJson,
+    UnmarshalError, Unmarshalable, UnmarshalableBorrowed,
+};
+
+#[test]
+fn from_element_test() {
+    let e = json!({"a": [1, 2], "b": [], "c": [3]});
+    let expect = HashMap::from([
+        ("a".to_owned(), vec![1, 2]),
+        ("b".to_owned(), vec![]),
+        ("c".to_owned(), vec![3]),
+    ]);
+    assert_eq!(
+        HashMap::<String, Vec<i64>>::from_element_ref(&e).unwrap(),
+        expect
+    );
+    assert_eq!(
+        HashMap::<String, Vec<i64>>::from_element(e.clone()).unwrap(),
+        expect
+    );
+    assert_eq!(
+        BTreeMap::<String, Vec<f64>>::from_element(e).unwrap()["a"],
+        vec![1.0, 2.0]
+    );
+
+    assert_eq!(i64::from_element(json!(-3)), Ok(-3));
+    assert_eq!(f64::from_element(json!(-3)), Ok(-3.0));
+    assert_eq!(f64::from_element(json!(0.5)), Ok(0.5));
+    assert_eq!(bool::from_element(json!(true)), Ok(true));
+    assert_eq!(String::from_element(json!("s")), Ok("s".to_owned()));
+    assert_eq!(Option::<i64>::from_element(json!(null)), Ok(None));
+    assert_eq!(Option::<i64>::from_element(json!(1)), Ok(Some(1)));
+    assert_eq!(Option::<()>::from_element(json!(null)), Ok(None));
+    let e = json!({"x": [null, {}]});
+    assert_eq!(Element::from_element_ref(&e), Ok(e.clone()));
+    assert_eq!(
+        RawJson::from_element_ref(&e["x"]).unwrap().as_str(),
+        "[null, {}]"
+    );
+}
+
+#[test]
+fn from_element_error_test() {
+    let e = json!({"a": [1, 2, "3"]});
+    assert_eq!(
+        HashMap::<String, Vec<i64>>::from_element_ref(&e),
+        Err(FromElementError::InvalidType {
+            pointer: "/a/2".to_owned(),
+            expected: "an integer",
+            found: "a string"
+        })
+    );
+    let e = json!({"a/b": {"c~": null}});
+    assert_eq!(
+        HashMap::<String, HashMap<String, String>>::from_element(e),
+        Err(FromElementError::InvalidType {
+            pointer: "/a~1b/c~0".to_owned(),
+            expected: "a string",
+            found: "null"
+        })
+    );
+    assert_eq!(
+        Vec::<i64>::from_element(json!({})),
+        Err(FromElementError::InvalidType {
+            pointer: "".to_owned(),
+            expected: "an array",
+            found: "an object"
+        })
+    );
+    // Floats aren't truncated into integers
+    assert_eq!(
+        i64::from_element(json!(1.5)),
+        Err(FromElementError::InvalidType {
+            pointer: "".to_owned(),
+            expected: "an integer",
+            found: "a float"
+        })
+    );
+    assert_eq!(
+        Option::<()>::from_element(json!(0)),
+        Err(FromElementError::InvalidType {
+            pointer: "".to_owned(),
+            expected: "null",
+            found: "an integer"
+        })
+    );
+}
+
+#[test]
+fn to_element_test() {
+    assert_eq!(1.to_element(), json!(1));
+    assert_eq!(1.0.to_element(), Element::JsonFloat(1.0));
+    assert_eq!(
+        vec![f64::NAN, f64::INFINITY].to_element(),
+        json!([null, null])
+    );
+    assert_eq!(false.to_element(), json!(false));
+    assert_eq!("s".to_element(), json!("s"));
+    assert_eq!("s".to_owned().to_element(), json!("s"));
+    assert_eq!(Some(2).to_element(), json!(2));
+    assert_eq!(None::<i64>.to_element(), json!(null));
+    assert_eq!(vec![1, 2].to_element(), json!([1, 2]));
+    assert_eq!([1, 2][..].to_element(), json!([1, 2]));
+    assert_eq!(HashSet::from([1]).to_element(), json!([1]));
+    assert_eq!(
+        HashMap::from([("a".to_owned(), vec![Some(1.5), None])]).to_element(),
+        json!({"a": [1.5, null]})
+    );
+    let raw = RawJson::new(r#"{"a": [true]}"#).unwrap();
+    assert_eq!(raw.to_element(), json!({"a": [true]}));
+
+    let json = r#"{"a\n": ["b", 1, 2.5, null]}"#;
+    let borrowed = BorrowedElement::unmarshal_json_borrowed(json).unwrap();
+    assert_eq!(
+        borrowed.to_element(),
+        Element::unmarshal_json(json.chars()).unwrap()
+    );
+}
+
+#[test]
+fn element_round_trip_test() {
+    let value = HashMap::from([
+        ("x".to_owned(), vec![Some("a".to_owned()), None]),
+        ("y".to_owned(), vec![]),
+    ]);
+    let element = value.to_element();
+    assert_eq!(
+        HashMap::<String, Vec<Option<String>>>::from_element(element),
+        Ok(value)
+    );
+}
+
+/// Unmarshals from both text and elements through `Source`
+#[derive(Debug, PartialEq)]
+struct Wrapper(i64);
+
+impl Unmarshalable for Wrapper {
+    fn unmarshal_json_with_state<S: Source>(u: &mut S) -> Result<Self, S::Error> {
+        i64::unmarshal_json_with_state(u).map(Wrapper)
+    }
+}
+
+/// Reads its fields from an object, skipping unknown members
+#[derive(Debug, Default, PartialEq)]
+struct Point {
+    x: i64,
+    y: Option<f64>,
+}
+
+impl Unmarshalable for Point {
+    fn unmarshal_json_with_state<S: Source>(u: &mut S) -> Result<Self, S::Error> {
+        let mut point = Point::default();
+        u.read_object(|u, key| {
+            match key.as_str() {
+                "x" => point.x = i64::unmarshal_json_with_state(u)?,
+                "y" => point.y = Option::unmarshal_json_with_state(u)?,
+                _ => u.skip_value()?,
+            }
+            Ok(())
+        })?;
+        Ok(point)
+    }
+}
+
+#[test]
+fn from_element_source_test() {
+    assert_eq!(
+        Vec::<Wrapper>::from_element(json!([1, 2])),
+        Ok(vec![Wrapper(1), Wrapper(2)])
+    );
+    assert_eq!(
+        Vec::<Wrapper>::from_element(json!([1, "2"])),
+        Err(FromElementError::InvalidType {
+            pointer: "/1".to_owned(),
+            expected: "an integer",
+            found: "a string"
+        })
+    );
+
+    let text = r#"{"x": 1, "z": [{}], "y": 2.5}"#;
+    let point = Point { x: 1, y: Some(2.5) };
+    assert_eq!(Point::unmarshal_json(text.chars()), Ok(point));
+    let element = Element::unmarshal_json(text.chars()).unwrap();
+    assert_eq!(
+        Point::from_element_ref(&element),
+        Ok(Point { x: 1, y: Some(2.5) })
+    );
+    assert_eq!(
+        Point::from_element(element),
+        Ok(Point { x: 1, y: Some(2.5) })
+    );
+    assert_eq!(
+        Vec::<Point>::from_element(json!([{"x": 1}, {"y": null}, {"y": "2"}])),
+        Err(FromElementError::InvalidType {
+            pointer: "/2/y".to_owned(),
+            expected: "a number",
+            found: "a string"
+        })
+    );
+    assert_eq!(
+        Point::unmarshal_json(r#"{"y": "2"}"#.chars()),
+        Err(UnmarshalError::UnexpectedChar {
+            c: '"',
+            row: 1,
+            col: 7
+        })
+    );
+    assert_eq!(
+        HashMap::<String, Point>::from_element(json!({"a/b": []})),
+        Err(FromElementError::InvalidType {
+            pointer: "/a~1b".to_owned(),
+            expected: "an object",
+            found: "an array"
+        })
+    );
+}
+
+#[test]
+fn element_from_test() {
+    assert_eq!(Element::from(1), Element::JsonInt(1));
+    assert_eq!(Element::from(1.5), Element::JsonFloat(1.5));
+    assert_eq!(Element::from(f64::NEG_INFINITY), Element::JsonNull);
+    assert_eq!(Element::from(true), Element::JsonBool(true));
+    assert_eq!(Element::from("s"), Element::JsonString("s".to_owned()));
+    assert_eq!(Element::from("s".to_owned()), json!("s"));
+    assert_eq!(Element::from(vec![Some(1), None]), json!([1, null]));
+    assert_eq!(Element::from(None::<bool>), Element::JsonNull);
+    assert_eq!(
+        Element::from(HashMap::from([("a".to_owned(), vec!["b"])])),
+        json!({"a": ["b"]})
+    );
+    let e: Element = vec![json!({}), 2.into()].into();
+    assert_eq!(e, json!([{}, 2]));
+}
+
+#[test]
+fn element_try_from_test() {
+    assert_eq!(i64::try_from(json!(2)), Ok(2));
+    assert_eq!(f64::try_from(&json!(2)), Ok(2.0));
+    assert_eq!(bool::try_from(&json!(false)), Ok(false));
+    assert_eq!(String::try_from(json!("s")), Ok("s".to_owned()));
+    let e = json!("borrowed");
+    let s: &str = (&e).try_into().unwrap();
+    assert_eq!(s, "borrowed");
+    assert_eq!(
+        i64::try_from(&json!("2")),
+        Err(FromElementError::InvalidType {
+            pointer: "".to_owned(),
+            expected: "an integer",
+            found: "a string"
+        })
+    );
+    assert_eq!(
+        <&str>::try_from(&json!(null)),
+        Err(FromElementError::InvalidType {
+            pointer: "".to_owned(),
+            expected: "a string",
+            found: "null"
+        })
+    );
+}
+
+#[test]
+fn from_element_error_display_test() {
+    let err = HashMap::<String, Vec<i64>>::from_element(json!({"a": [true]})).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid type at /a/0: expected an integer, found a boolean"
+    );
+    let err: Box<dyn std::error::Error> = Box::new(i64::try_from(json!(null)).unwrap_err());
+    assert_eq!(
+        err.to_string(),
+        "invalid type: expected an integer, found null"
+    );
+}
+
+#[test]
+fn element_from_iter_test() {
+    let list = (1..=3).map(|i| i * 2).collect::<Element>();
+    assert_eq!(list, json!([2, 4, 6]));
+    let object = ["a", "b"]
+        .iter()
+        .map(|&k| (k, k.to_uppercase()))
+        .collect::<Element>();
+    assert_eq!(object, json!({"a": "A", "b": "B"}));
+    assert_eq!(
+        Vec::<bool>::new().into_iter().collect::<Element>(),
+        json!([])
+    );
+}
@@ -0,0 +1,300 @@
+use std::collections::HashMap;
+
+use json::{debug::DebugLimits, Element, UnmarshalError, Unmarshalable};
+
+fn parse(json: &str) -> Element {
+    Element::unmarshal_json(json.chars()).unwrap()
+}
+
+#[test]
+fn element_index_test() {
+    let doc = parse(r#"{"a": [1, {"b": "c"}], "n": null, "f": 1.5}"#);
+    assert_eq!(doc["a"][0], Element::JsonInt(1));
+    assert_eq!(doc["a"][1]["b"].as_str(), Some("c"));
+    assert_eq!(doc[&"a".to_owned()][1]["b"], doc["a"][1]["b"]);
+    // Misses return null rather than panicking
+    assert!(doc["missing"]["b"][3].is_null());
+    assert!(doc["a"][2].is_null());
+    assert!(doc[0].is_null());
+    assert!(doc["a"]["b"].is_null());
+
+    assert_eq!(doc.get("f"), Some(&Element::JsonFloat(1.5)));
+    assert_eq!(doc.get("n"), Some(&Element::JsonNull));
+    assert_eq!(doc.get("missing"), None);
+    assert_eq!(doc["a"].get(5), None);
+    assert_eq!(doc.get(0), None);
+}
+
+#[test]
+fn element_index_mut_test() {
+    let mut doc = Element::JsonNull;
+    doc["a"]["b"] = Element::JsonInt(1);
+    doc["a"]["c"] = Element::JsonList(vec![Element::JsonNull]);
+    doc["a"]["c"][0] = Element::JsonBool(true);
+    assert_eq!(doc, parse(r#"{"a": {"b": 1, "c": [true]}}"#));
+
+    if let Some(b) = doc["a"].get_mut("b") {
+        *b = Element::JsonString("x".to_owned());
+    }
+    assert_eq!(doc["a"]["b"].as_str(), Some("x"));
+    assert!(doc.get_mut("missing").is_none());
+    // Looking up doesn't insert
+    assert_eq!(doc.len(), Some(1));
+}
+
+#[test]
+#[should_panic]
+fn element_index_mut_out_of_bounds_test() {
+    let mut doc = parse("[1]");
+    doc[1] = Element::JsonNull;
+}
+
+#[test]
+#[should_panic]
+fn element_index_mut_non_object_test() {
+    let mut doc = parse("[1]");
+    doc["a"] = Element::JsonNull;
+}
+
+#[test]
+fn element_accessor_test() {
+    let doc = parse(r#"{"s": "x", "i": -2, "f": 0.5, "b": false, "l": [], "o": {}}"#);
+    assert_eq!(doc["s"].as_str(), Some("x"));
+    assert_eq!(doc["i"].as_i64(), Some(-2));
+    assert_eq!(doc["i"].as_f64(), Some(-2.0));
+    assert_eq!(doc["f"].as_f64(), Some(0.5));
+    assert_eq!(doc["f"].as_i64(), None);
+    assert_eq!(doc["b"].as_bool(), Some(false));
+    assert_eq!(doc["s"].as_bool(), None);
+    assert_eq!(doc["l"].as_array(), Some(&vec![]));
+    assert!(doc["o"].as_object().unwrap().is_empty());
+    assert!(doc["l"].as_object().is_none());
+    assert!(doc["o"].is_object() && doc["l"].is_array());
+
+    assert_eq!(doc.len(), Some(6));
+    assert_eq!(doc["l"].is_empty(), Some(true));
+    assert_eq!(doc["s"].len(), None);
+}
+
+#[test]
+fn element_take_test() {
+    let mut doc = parse(r#"{"a": [1, 2]}"#);
+    let a = doc["a"].take();
+    assert_eq!(a, parse("[1, 2]"));
+    assert_eq!(doc, parse(r#"{"a": null}"#));
+}
+
+#[test]
+fn element_iter_test() {
+    let mut doc = parse(r#"{"a": 1, "b": 2, "l": [1, 2, 3]}"#);
+    let mut keys = doc.members().map(|(k, _)| k).collect::<Vec<_>>();
+    keys.sort();
+    assert_eq!(keys, ["a", "b", "l"]);
+    assert_eq!(
+        doc["l"].elements().filter_map(Element::as_i64).sum::<i64>(),
+        6
+    );
+    assert_eq!(doc["l"].members().count(), 0);
+    assert_eq!(doc.elements().count(), 0);
+
+    for e in doc["l"].elements_mut() {
+        *e = Element::JsonInt(e.as_i64().unwrap() * 2);
+    }
+    for (_, v) in doc.members_mut() {
+        if let Element::JsonInt(i) = v {
+            *i += 10;
+        }
+    }
+    assert_eq!(doc, parse(r#"{"a": 11, "b": 12, "l": [2, 4, 6]}"#));
+}
+
+#[test]
+fn element_display_test() {
+    let doc = parse(r#"{"a": [1, 2.5, {"b": null}], "c": {}, "d": []}"#);
+    assert_eq!(parse(&doc.to_string()), doc);
+    assert_eq!(parse("[1, \"é\"]").to_string(), "[1, \"\\u00e9\"]");
+    assert_eq!(
+        format!("{:#}", parse(r#"{"a": [1, {"b": null}, [], {}]}"#)),
+        "{\n  \"a\": [\n    1,\n    {\n      \"b\": null\n    },\n    [],\n    {}\n  ]\n}"
+    );
+    assert_eq!(parse(&format!("{doc:#}")), doc);
+    assert_eq!(format!("{:#}", Element::JsonInt(1)), "1");
+}
+
+#[test]
+fn element_display_round_trip_test() {
+    let floats = Element::JsonList(vec![
+        Element::JsonFloat(1.0),
+        Element::JsonFloat(-0.0),
+        Element::JsonFloat(-1e15),
+        Element::JsonFloat(0.25),
+    ]);
+    assert_eq!(floats.to_string(), "[1.0, -0.0, -1000000000000000.0, 0.25]");
+    let doc = Element::JsonObject(HashMap::from([
+        ("floats".to_owned(), floats),
+        ("int".to_owned(), Element::JsonInt(1)),
+    ]));
+    assert_eq!(doc.to_string().parse::<Element>(), Ok(doc.clone()));
+    assert_eq!(format!("{doc:#}").parse::<Element>(), Ok(doc));
+
+    let non_finite = Element::JsonList(vec![
+        Element::JsonFloat(f64::NAN),
+        Element::JsonFloat(f64::INFINITY),
+        Element::JsonFloat(f64::NEG_INFINITY),
+    ]);
+    assert_eq!(non_finite.to_string(), "[null, null, null]");
+    assert_eq!(
+        non_finite.to_string().parse::<Element>(),
+        Ok(Element::from(vec![
+            f64::NAN,
+            f64::INFINITY,
+            f64::NEG_INFINITY
+        ]))
+    );
+}
+
+#[test]
+fn element_from_str_test() {
+    assert_eq!("[1, true]".parse::<Element>(), Ok(parse("[1, true]")));
+    assert_eq!(
+        "[1,]".parse::<Element>(),
+        Err(UnmarshalError::UnexpectedChar {
+            c: ']',
+            row: 1,
+            col: 4
+        })
+    );
+}
+
+#[test]
+fn element_debug_bounded_test() {
+    let limits = DebugLimits {
+        string_chars: 3,
+        items: 2,
+        depth: 2,
+    };
+    let debug = |json: &str| format!("{:?}", parse(json).debug_bounded(limits));
+    assert_eq!(debug(r#""abc""#), r#""abc""#);
+    assert_eq!(debug(r#""abcd""#), r#""abc…""#);
+    assert_eq!(debug(r#""éééé""#), r#""\u00e9\u00e9\u00e9…""#);
+    assert_eq!(debug("[1, 2]"), "[1, 2]");
+    assert_eq!(debug("[1, 2, 3, 4]"), "[1, 2, …]");
+    assert_eq!(
+        debug(r#"{"c": 3, "b": 2, "a": 1}"#),
+        r#"{"a": 1, "b": 2, …}"#
+    );
+    assert_eq!(debug(r#"{"long key": 1}"#), r#"{"lon…": 1}"#);
+    assert_eq!(debug("[[[1]], [{}], [[]]]"), "[[[…]], [{}], …]");
+    assert_eq!(debug(r#"[{"a": {"b": 1}}]"#), r#"[{"a": {…}}]"#);
+
+    let big = Element::JsonList(vec![Element::JsonString("x".repeat(1000)); 1000]);
+    let default = format!("{:?}", big.debug_bounded(DebugLimits::default()));
+    assert!(default.len() < 16 * 80);
+    assert!(default.ends_with("…\", …]"));
+}
+
+#[test]
+fn element_eq_test() {
+    assert_eq!(Element::JsonFloat(f64::NAN), Element::JsonFloat(f64::NAN));
+    assert_ne!(Element::JsonFloat(0.0), Element::JsonFloat(-0.0));
+    assert_ne!(Element::JsonInt(1), Element::JsonFloat(1.0));
+    assert_eq!(
+        parse(r#"{"a": 1, "b": [2.5]}"#),
+        parse(r#"{"b": [2.5], "a": 1}"#)
+    );
+
+    // Numeric equality compares ints and floats by value
+    assert!(Element::JsonInt(1).eq_numeric(&Element::JsonFloat(1.0)));
+    assert!(Element::JsonFloat(0.0).eq_numeric(&Element::JsonFloat(-0.0)));
+    assert!(!Element::JsonFloat(f64::NAN).eq_numeric(&Element::JsonFloat(f64::NAN)));
+    assert!(!Element::JsonInt(i64::MAX).eq_numeric(&Element::JsonFloat(i64::MAX as f64)));
+    assert!(parse(r#"{"a": [1, {"b": 2.0}]}"#).eq_numeric(&parse(r#"{"a": [1.0, {"b": 2}]}"#)));
+    assert!(!parse("[1]").eq_numeric(&parse("[1, 2]")));
+}
+
+#[test]
+fn element_ord_test() {
+    let mut elements = vec![
+        parse(r#"{"a": 1}"#),
+        parse("[1, 2]"),
+        parse(r#""b""#),
+        Element::JsonFloat(f64::NAN),
+        Element::JsonFloat(1.5),
+        Element::JsonFloat(1.0),
+        Element::JsonInt(1),
+        Element::JsonFloat(f64::NEG_INFINITY),
+        Element::JsonInt(i64::MIN),
+        Element::JsonBool(true),
+        Element::JsonNull,
+        parse("[1]"),
+        parse(r#""a""#),
+        parse("{}"),
+        Element::JsonBool(false),
+        Element::JsonFloat(-0.0),
+        Element::JsonInt(0),
+        Element::JsonFloat(0.0),
+        Element::JsonFloat(-0.5),
+    ];
+    elements.sort();
+    let expect = vec![
+        Element::JsonNull,
+        Element::JsonBool(false),
+        Element::JsonBool(true),
+        Element::JsonFloat(f64::NEG_INFINITY),
+        Element::JsonInt(i64::MIN),
+        Element::JsonFloat(-0.5),
+        Element::JsonInt(0),
+        Element::JsonFloat(-0.0),
+        Element::JsonFloat(0.0),
+        Element::JsonInt(1),
+        Element::JsonFloat(1.0),
+        Element::JsonFloat(1.5),
+        Element::JsonFloat(f64::NAN),
+        parse(r#""a""#),
+        parse(r#""b""#),
+        parse("[1]"),
+        parse("[1, 2]"),
+        parse("{}"),
+        parse(r#"{"a": 1}"#),
+    ];
+    assert_eq!(elements, expect);
+
+    // Large ints are compared with floats exactly
+    assert!(Element::JsonInt(i64::MAX) < Element::JsonFloat(i64::MAX as f64));
+    assert!(Element::JsonInt(1 << 53) < Element::JsonFloat((1u64 << 53) as f64));
+    assert!(Element::JsonInt((1 << 53) + 1) > Element::JsonFloat((1u64 << 53) as f64));
+
+    // Objects are compared by their members sorted by key
+    assert!(parse(r#"{"b": 1, "a": 2}"#) > parse(r#"{"a": 1, "b": 2}"#));
+    assert!(parse(r#"{"a": 1}"#) < parse(r#"{"a": 1, "b": 0}"#));
+    assert!(parse(r#"{"b": 0}"#) > parse(r#"{"a": 1, "b": 0}"#));
+}
+
+#[test]
+fn element_hash_test() {
+    use std::collections::{BTreeSet, HashSet};
+
+    let docs = [
+        r#"{"a": 1, "b": [true, null], "c": {"d": 1.5, "e": "f"}}"#,
+        r#"{"c": {"e": "f", "d": 1.5}, "b": [true, null], "a": 1}"#,
+        r#"{"a": 1.0, "b": [true, null], "c": {"d": 1.5, "e": "f"}}"#,
+        r#"[1, 2]"#,
+        r#"[2, 1]"#,
+    ];
+    let set = docs.iter().map(|d| parse(d)).collect::<HashSet<_>>();
+    assert_eq!(set.len(), 4);
+    assert!(set.contains(&parse(
+        r#"{"b": [true, null], "a": 1, "c": {"e": "f", "d": 1.5}}"#
+    )));
+    assert!(set.contains(&Element::JsonList(vec![
+        Element::JsonInt(1),
+        Element::JsonInt(2)
+    ])));
+    assert!(!set.contains(&parse("[1.0, 2]")));
+
+    let tree = docs.iter().map(|d| parse(d)).collect::<BTreeSet<_>>();
+    assert_eq!(tree.len(), 4);
+    let mut nans = HashSet::new();
+    nans.insert(Element::JsonFloat(f64::NAN));
+    assert!(nans.contains(&Element::JsonFloat(f64::NAN)));
+}
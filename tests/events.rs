@@ -0,0 +1,125 @@
+use json::{
+    unmarshal::{EventIter, EventKind, EventKind::*, Number},
+    UnmarshalError,
+};
+
+fn kinds(s: &str) -> Result<Vec<EventKind>, UnmarshalError> {
+    EventIter::new(s.chars())
+        .map(|e| e.map(|e| e.kind))
+        .collect()
+}
+
+#[test]
+fn events_scalar_test() {
+    assert_eq!(kinds("1").unwrap(), vec![Number(Number::Int(1))]);
+    assert_eq!(kinds("-2.5").unwrap(), vec![Number(Number::Float(-2.5))]);
+    assert_eq!(kinds(r#" "a" "#).unwrap(), vec![String("a".to_owned())]);
+    assert_eq!(kinds("true").unwrap(), vec![Bool(true)]);
+    assert_eq!(kinds("null").unwrap(), vec![Null]);
+}
+
+#[test]
+fn events_nested_test() {
+    assert_eq!(
+        kinds(r#"{"a": [1, {}], "b": []}"#).unwrap(),
+        vec![
+            StartObject,
+            Key("a".to_owned()),
+            StartArray,
+            Number(Number::Int(1)),
+            StartObject,
+            EndObject,
+            EndArray,
+            Key("b".to_owned()),
+            StartArray,
+            EndArray,
+            EndObject,
+        ]
+    );
+}
+
+#[test]
+fn events_position_test() {
+    let positions: Vec<_> = EventIter::new("[\n  true,\n  null]".chars())
+        .map(|e| {
+            let e = e.unwrap();
+            (e.position.row, e.position.col)
+        })
+        .collect();
+    assert_eq!(positions, vec![(1, 1), (2, 3), (3, 3), (3, 7)]);
+}
+
+#[test]
+fn events_skip_value_test() {
+    let mut events = EventIter::new(r#"{"skip": {"a": [1, "]"]}, "keep": 2}"#.chars());
+    assert_eq!(events.next().unwrap().unwrap().kind, StartObject);
+    assert_eq!(events.next().unwrap().unwrap().kind, Key("skip".to_owned()));
+    events.skip_value().unwrap();
+    assert_eq!(events.next().unwrap().unwrap().kind, Key("keep".to_owned()));
+    assert_eq!(events.next().unwrap().unwrap().kind, Number(Number::Int(2)));
+    assert_eq!(events.next().unwrap().unwrap().kind, EndObject);
+    assert!(events.next().is_none());
+}
+
+#[test]
+fn events_skip_value_without_value_test() {
+    // A key is next, so nothing is skipped
+    let mut events = EventIter::new(r#"{"a": [1, 2], "b": []}"#.chars());
+    assert_eq!(events.next().unwrap().unwrap().kind, StartObject);
+    assert_eq!(
+        events.skip_value(),
+        Err(UnmarshalError::UnexpectedChar {
+            c: '"',
+            row: 1,
+            col: 2
+        })
+    );
+    assert_eq!(events.next().unwrap().unwrap().kind, Key("a".to_owned()));
+    assert_eq!(events.next().unwrap().unwrap().kind, StartArray);
+    assert_eq!(events.next().unwrap().unwrap().kind, Number(Number::Int(1)));
+
+    // A comma is next
+    assert_eq!(
+        events.skip_value(),
+        Err(UnmarshalError::UnexpectedChar {
+            c: ',',
+            row: 1,
+            col: 9
+        })
+    );
+    assert_eq!(events.next().unwrap().unwrap().kind, Number(Number::Int(2)));
+    assert_eq!(events.next().unwrap().unwrap().kind, EndArray);
+    assert_eq!(events.next().unwrap().unwrap().kind, Key("b".to_owned()));
+    assert_eq!(events.next().unwrap().unwrap().kind, StartArray);
+
+    // The end of an empty array is next
+    assert!(events.skip_value().is_err());
+    assert_eq!(events.next().unwrap().unwrap().kind, EndArray);
+    assert_eq!(events.next().unwrap().unwrap().kind, EndObject);
+    assert!(events.next().is_none());
+}
+
+#[test]
+fn events_mismatched_end_test() {
+    assert_eq!(
+        kinds("[1}").unwrap_err(),
+        UnmarshalError::UnexpectedChar {
+            c: '}',
+            row: 1,
+            col: 3
+        }
+    );
+}
+
+#[test]
+fn events_trailing_chars_test() {
+    assert_eq!(
+        kinds("[] 1").unwrap_err(),
+        UnmarshalError::UnexpectedChar {
+            c: '1',
+            row: 1,
+            col: 4
+        }
+    );
+    assert_eq!(kinds("[1,").unwrap_err(), UnmarshalError::EndOfChars);
+}
@@ -0,0 +1,130 @@
+use json::{
+    jmespath::{Expression, JmesError},
+    Element, Unmarshalable,
+};
+
+/// Cases written for this crate in the format of the JMESPath compliance
+/// suite: each group has a `given` document and `cases` with an
+/// `expression` and either a `result` or the kind of `error`.
+const CASES: [(&str, &str); 4] = [
+    ("basic", include_str!("jmespath/basic.json")),
+    ("projections", include_str!("jmespath/projections.json")),
+    ("functions", include_str!("jmespath/functions.json")),
+    ("syntax", include_str!("jmespath/syntax.json")),
+];
+
+fn member<'a>(e: &'a Element, key: &str) -> Option<&'a Element> {
+    match e {
+        Element::JsonObject(o) => o.get(key),
+        _ => None,
+    }
+}
+
+fn list(e: &Element) -> &[Element] {
+    match e {
+        Element::JsonList(l) => l,
+        _ => panic!("expected a list: {e:?}"),
+    }
+}
+
+fn error_kind(e: &JmesError) -> &'static str {
+    match e {
+        JmesError::Syntax { .. } => "syntax",
+        JmesError::UnknownFunction { .. } => "unknown-function",
+        JmesError::InvalidArity { .. } => "invalid-arity",
+        JmesError::InvalidType { .. } => "invalid-type",
+        JmesError::InvalidValue => "invalid-value",
+    }
+}
+
+/// Runs the cases in a file, returning the number of cases run
+fn run_suite(name: &str, suite: &str) -> usize {
+    let mut count = 0;
+    let suite = Element::unmarshal_json(suite.chars()).unwrap();
+    for group in list(&suite) {
+        let given = member(group, "given").unwrap();
+        for case in list(member(group, "cases").unwrap()) {
+            let Some(Element::JsonString(expression)) = member(case, "expression") else {
+                panic!("{name}: case without an expression");
+            };
+            let result = Expression::compile(expression).and_then(|e| e.search(given));
+            match (member(case, "result"), member(case, "error"), result) {
+                (Some(expected), _, Ok(actual)) => assert!(
+                    expected.eq_numeric(&actual),
+                    "{name}: {expression:?} gave {actual:?}, expected {expected:?}"
+                ),
+                (_, Some(Element::JsonString(kind)), Err(e)) => assert_eq!(
+                    error_kind(&e),
+                    kind,
+                    "{name}: {expression:?} gave the wrong error"
+                ),
+                (_, _, result) => panic!("{name}: {expression:?} gave {result:?}"),
+            }
+            count += 1;
+        }
+    }
+    count
+}
+
+#[test]
+fn jmespath_cases_test() {
+    let count: usize = CASES
+        .iter()
+        .map(|(name, suite)| run_suite(name, suite))
+        .sum();
+    assert!(count > 200);
+}
+
+#[test]
+fn jmespath_overflow_test() {
+    let search = |expression, doc: &Element| Expression::compile(expression).unwrap().search(doc);
+    let doc = Element::unmarshal_json("[9223372036854775807, 1]".chars()).unwrap();
+    assert_eq!(
+        search("sum(@)", &doc),
+        Ok(Element::JsonFloat(9223372036854775808.0))
+    );
+    let doc = Element::unmarshal_json("[1, 2, 3]".chars()).unwrap();
+    assert_eq!(
+        search("[1::9223372036854775807]", &doc),
+        Ok(Element::JsonList(vec![Element::JsonInt(2)]))
+    );
+    assert_eq!(
+        search("[::-9223372036854775807]", &doc),
+        Ok(Element::JsonList(vec![Element::JsonInt(3)]))
+    );
+    assert_eq!(
+        search("abs(@)", &Element::JsonInt(i64::MIN)),
+        Ok(Element::JsonFloat(9223372036854775808.0))
+    );
+}
+
+#[test]
+fn jmespath_error_test() {
+    let error = |expression| Expression::compile(expression).unwrap_err();
+    assert_eq!(error("foo.bar."), JmesError::Syntax { offset: 8 });
+    assert_eq!(error("foo[?a ="), JmesError::Syntax { offset: 7 });
+    assert_eq!(error("foo ^"), JmesError::Syntax { offset: 4 });
+    assert_eq!(
+        error("length(a, b)"),
+        JmesError::InvalidArity {
+            name: "length".to_owned()
+        }
+    );
+    assert_eq!(
+        error("foo(a)"),
+        JmesError::UnknownFunction {
+            name: "foo".to_owned()
+        }
+    );
+    let expression = Expression::compile("sum(@)").unwrap();
+    let doc = Element::unmarshal_json("[1, \"2\"]".chars()).unwrap();
+    assert_eq!(
+        expression.search(&doc),
+        Err(JmesError::InvalidType {
+            name: "sum".to_owned()
+        })
+    );
+    // A compiled expression can be reused
+    let doc = Element::unmarshal_json("[1, 2]".chars()).unwrap();
+    assert_eq!(expression.search(&doc), Ok(Element::JsonInt(3)));
+}
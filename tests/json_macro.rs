@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+
+use json::{json, Element, RawJson, Unmarshalable};
+
+fn parse(json: &str) -> Element {
+    Element::unmarshal_json(json.chars()).unwrap()
+}
+
+#[test]
+fn json_macro_literal_test() {
+    assert_eq!(json!(null), Element::JsonNull);
+    assert_eq!(json!(true), Element::JsonBool(true));
+    assert_eq!(json!(false), Element::JsonBool(false));
+    assert_eq!(json!(1), Element::JsonInt(1));
+    assert_eq!(json!(-1.5), Element::JsonFloat(-1.5));
+    assert_eq!(json!("a"), Element::JsonString("a".to_owned()));
+    assert_eq!(json!([]), Element::JsonList(vec![]));
+    assert_eq!(json!({}), Element::JsonObject(HashMap::new()));
+}
+
+#[test]
+fn json_macro_nested_test() {
+    let e = json!({
+        "a": [1, 2.5, null, true, false, "s", [], {}],
+        "b": {"c": {"d": [[null]]}},
+        "e": null,
+    });
+    assert_eq!(
+        e,
+        parse(
+            r#"{"a": [1, 2.5, null, true, false, "s", [], {}],
+                "b": {"c": {"d": [[null]]}}, "e": null}"#
+        )
+    );
+    // Trailing commas
+    assert_eq!(json!([1, 2,]), parse("[1, 2]"));
+    assert_eq!(json!({"a": 1,}), parse(r#"{"a": 1}"#));
+    assert_eq!(json!([[1,], {"a": [],},]), parse(r#"[[1], {"a": []}]"#));
+}
+
+#[test]
+fn json_macro_interpolation_test() {
+    let n: i64 = 3;
+    let s = "string".to_owned();
+    let list = vec![1, 2];
+    let map = HashMap::from([("k".to_owned(), true)]);
+    let inner = json!({"x": 1});
+    let e = json!({
+        "n": n + 1,
+        "s": s.as_str(),
+        "owned": s,
+        "list": list,
+        "map": map,
+        "inner": inner,
+        "option": Option::<i64>::None,
+        "raw": RawJson::new(r#"{"r": [1]}"#).unwrap(),
+        "nested": [n, {"m": n * 2}],
+    });
+    assert_eq!(
+        e,
+        parse(
+            r#"{"n": 4, "s": "string", "owned": "string", "list": [1, 2],
+                "map": {"k": true}, "inner": {"x": 1}, "option": null,
+                "raw": {"r": [1]}, "nested": [3, {"m": 6}]}"#
+        )
+    );
+    assert_eq!(json!(f64::NAN), Element::JsonNull);
+}
+
+#[test]
+fn json_macro_computed_key_test() {
+    let key = "dynamic";
+    let e = json!({
+        (key): 1,
+        (format!("{key}-{}", 2)): [2],
+        ("lit".to_owned()): {"x": null},
+    });
+    assert_eq!(
+        e,
+        parse(r#"{"dynamic": 1, "dynamic-2": [2], "lit": {"x": null}}"#)
+    );
+}
@@ -0,0 +1,306 @@
+use json::{
+    jsonpath::{JsonPath, NormalizedPath, PathError, PathSegment},
+    Element, Unmarshalable,
+};
+
+const STORE: &str = r#"{ "store": {
+    "book": [
+      { "category": "reference",
+        "author": "Nigel Rees",
+        "title": "Sayings of the Century",
+        "price": 8.95
+      },
+      { "category": "fiction",
+        "author": "Evelyn Waugh",
+        "title": "Sword of Honour",
+        "price": 12.99
+      },
+      { "category": "fiction",
+        "author": "Herman Melville",
+        "title": "Moby Dick",
+        "isbn": "0-553-21311-3",
+        "price": 8.99
+      },
+      { "category": "fiction",
+        "author": "J. R. R. Tolkien",
+        "title": "The Lord of the Rings",
+        "isbn": "0-395-19395-8",
+        "price": 22.99
+      }
+    ],
+    "bicycle": {
+      "color": "red",
+      "price": 399
+    }
+  }
+}"#;
+
+/// Returns the sorted normalized paths selected by a query, since object
+/// members are visited in an unspecified order
+fn paths(query: &str, json: &str) -> Vec<String> {
+    let doc = Element::unmarshal_json(json.chars()).unwrap();
+    let mut paths = JsonPath::compile(query)
+        .unwrap()
+        .query(&doc)
+        .into_iter()
+        .map(|result| result.path.to_string())
+        .collect::<Vec<_>>();
+    paths.sort();
+    paths
+}
+
+/// Returns the values selected by a query in order, as JSON
+fn values(query: &str, json: &str) -> Vec<String> {
+    use json::Marshalable;
+    let doc = Element::unmarshal_json(json.chars()).unwrap();
+    JsonPath::compile(query)
+        .unwrap()
+        .query(&doc)
+        .into_iter()
+        .map(|result| result.value.marshal_json())
+        .collect()
+}
+
+#[test]
+fn jsonpath_store_test() {
+    assert_eq!(
+        values("$.store.book[*].author", STORE),
+        vec![
+            "\"Nigel Rees\"",
+            "\"Evelyn Waugh\"",
+            "\"Herman Melville\"",
+            "\"J. R. R. Tolkien\""
+        ]
+    );
+    assert_eq!(paths("$..author", STORE).len(), 4);
+    assert_eq!(
+        paths("$.store.*", STORE),
+        vec!["$['store']['bicycle']", "$['store']['book']"]
+    );
+    assert_eq!(
+        paths("$.store..price", STORE),
+        vec![
+            "$['store']['bicycle']['price']",
+            "$['store']['book'][0]['price']",
+            "$['store']['book'][1]['price']",
+            "$['store']['book'][2]['price']",
+            "$['store']['book'][3]['price']",
+        ]
+    );
+    assert_eq!(paths("$..book[2]", STORE), vec!["$['store']['book'][2]"]);
+    assert_eq!(paths("$..book[-1]", STORE), vec!["$['store']['book'][3]"]);
+    assert_eq!(
+        paths("$..book[0,1]", STORE),
+        vec!["$['store']['book'][0]", "$['store']['book'][1]"]
+    );
+    assert_eq!(
+        paths("$..book[:2]", STORE),
+        vec!["$['store']['book'][0]", "$['store']['book'][1]"]
+    );
+    assert_eq!(
+        paths("$..book[?@.isbn]", STORE),
+        vec!["$['store']['book'][2]", "$['store']['book'][3]"]
+    );
+    assert_eq!(
+        values("$.store.book[?@.price < 10].title", STORE),
+        vec!["\"Sayings of the Century\"", "\"Moby Dick\""]
+    );
+    assert_eq!(
+        values("$..book[?@.price<10 && @.category=='fiction'].title", STORE),
+        vec!["\"Moby Dick\""]
+    );
+    assert_eq!(
+        values(
+            "$..book[?!(@.price < 10) || @.author == 'Nigel Rees'].price",
+            STORE
+        ),
+        vec!["8.95", "12.99", "22.99"]
+    );
+    // Every member and element
+    assert_eq!(paths("$..*", STORE).len(), 27);
+}
+
+#[test]
+fn jsonpath_slice_test() {
+    let json = r#"["a", "b", "c", "d", "e", "f", "g"]"#;
+    assert_eq!(values("$[1:3]", json), vec!["\"b\"", "\"c\""]);
+    assert_eq!(values("$[5:]", json), vec!["\"f\"", "\"g\""]);
+    assert_eq!(values("$[1:5:2]", json), vec!["\"b\"", "\"d\""]);
+    assert_eq!(values("$[5:1:-2]", json), vec!["\"f\"", "\"d\""]);
+    assert_eq!(
+        values("$[::-1]", json),
+        vec!["\"g\"", "\"f\"", "\"e\"", "\"d\"", "\"c\"", "\"b\"", "\"a\""]
+    );
+    assert_eq!(values("$[-2:]", json), vec!["\"f\"", "\"g\""]);
+    assert!(values("$[::0]", json).is_empty());
+    assert!(values("$[7]", json).is_empty());
+    assert!(values("$[-8]", json).is_empty());
+    // Slices and indices only apply to arrays
+    assert!(values("$[0]", r#"{"0": 1}"#).is_empty());
+}
+
+#[test]
+fn jsonpath_comparison_test() {
+    let json = r#"[{"a": 1}, {"a": 1.0}, {"a": "1"}, {"a": [1, {"b": 2}]}, {"a": null}, {}]"#;
+    assert_eq!(paths("$[?@.a == 1]", json), vec!["$[0]", "$[1]"]);
+    assert_eq!(
+        values("$[?@.a == $[3].a]", json),
+        vec!["{\"a\": [1, {\"b\": 2}]}"]
+    );
+    assert_eq!(values("$[?@.a == null]", json), vec!["{\"a\": null}"]);
+    // Missing values are only equal to each other
+    assert_eq!(values("$[?@.missing == @.other]", json).len(), 6);
+    assert_eq!(values("$[?@.a != 1]", json).len(), 4);
+    assert_eq!(paths("$[?@.a >= 1]", json), vec!["$[0]", "$[1]"]);
+    assert_eq!(values("$[?@.a < 'b'].a", json), vec!["\"1\""]);
+    assert!(values("$[?@.a < true]", json).is_empty());
+}
+
+#[test]
+fn jsonpath_function_test() {
+    let json = r#"[{"s": "abc", "l": [1, 2, 3]}, {"s": "ab", "l": []}, {"s": "x12"}]"#;
+    assert_eq!(
+        values("$[?length(@.s) == 3].s", json),
+        vec!["\"abc\"", "\"x12\""]
+    );
+    assert_eq!(values("$[?length(@.l) == 0].s", json), vec!["\"ab\""]);
+    assert_eq!(values("$[?count(@.l[*]) > 1].s", json), vec!["\"abc\""]);
+    assert_eq!(values("$[?count(@.*) == 1].s", json), vec!["\"x12\""]);
+    assert_eq!(values("$[?match(@.s, 'a.')].s", json), vec!["\"ab\""]);
+    assert_eq!(
+        values("$[?search(@.s, '[a-c]')].s", json),
+        vec!["\"abc\"", "\"ab\""]
+    );
+    assert_eq!(values("$[?match(@.s, '\\\\p{L}\\\\d+')].s", json).len(), 0);
+    assert_eq!(
+        values("$[?match(@.s, '\\\\p{L}[0-9]+')].s", json),
+        vec!["\"x12\""]
+    );
+    assert_eq!(values("$[?value(@.l[0]) == 1].s", json), vec!["\"abc\""]);
+    assert_eq!(values("$[?!match(@.s, 'ab|x.*')].s", json), vec!["\"abc\""]);
+}
+
+#[test]
+fn jsonpath_dynamic_regex_test() {
+    // Patterns from the document are compiled as they're found, while
+    // literal patterns are compiled with the query
+    let json = r#"[{"s": "ab", "re": "a."}, {"s": "ab", "re": "x"}, {"s": "ab", "re": "("}]"#;
+    assert_eq!(values("$[?match(@.s, @.re)].re", json), vec!["\"a.\""]);
+    assert_eq!(values("$[?search(@.s, @.re)].re", json), vec!["\"a.\""]);
+    assert_eq!(values("$[?match(@.s, '(')].re", json).len(), 0);
+    assert_eq!(values("$[?match(@.s, @.missing)].re", json).len(), 0);
+}
+
+#[test]
+fn jsonpath_regex_test() {
+    let json = r#"["aaa", "ab", "a-b", "", "é", "a\nb", "A1"]"#;
+    let matches = |re: &str| values(&format!("$[?match(@, '{re}')]"), json);
+    assert_eq!(matches("a{2,3}"), vec!["\"aaa\""]);
+    assert_eq!(matches("a(b|-b)?"), vec!["\"ab\"", "\"a-b\""]);
+    assert_eq!(matches("a*"), vec!["\"aaa\"", "\"\""]);
+    assert_eq!(matches("[^a-z]"), vec!["\"\\u00e9\""]);
+    assert_eq!(matches("\\\\p{Lu}\\\\P{L}"), vec!["\"A1\""]);
+    // Dots don't match line breaks
+    assert!(matches("a.b").contains(&"\"a-b\"".to_owned()));
+    assert!(!matches("a.b").contains(&"\"a\\nb\"".to_owned()));
+    // Invalid expressions never match
+    assert!(matches("a{3,2}").is_empty());
+    assert!(matches("(a").is_empty());
+    assert!(matches("\\\\d").is_empty());
+    // Matching takes linear time
+    let long = format!("[\"{}\"]", "a".repeat(5000));
+    assert!(values("$[?match(@, '(a*)*b')]", &long).is_empty());
+}
+
+#[test]
+fn jsonpath_normalized_path_test() {
+    let json = r#"{"a'b\\": {"\n\u0001": [0, 1]}}"#;
+    assert_eq!(
+        paths("$.*.*[1]", json),
+        vec!["$['a\\'b\\\\']['\\n\\u0001'][1]"]
+    );
+    let path = NormalizedPath(vec![
+        PathSegment::Name("a/b".to_owned()),
+        PathSegment::Index(1),
+    ]);
+    assert_eq!(path.to_pointer(), "/a~1b/1");
+}
+
+#[test]
+fn jsonpath_syntax_test() {
+    let json = r#"{"a b": 1, "é": 2, "_x1": 3, "a": {"b": 4}}"#;
+    assert_eq!(values("$['a b']", json), vec!["1"]);
+    assert_eq!(values("$[\"a b\"]", json), vec!["1"]);
+    assert_eq!(values("$.é", json), vec!["2"]);
+    assert_eq!(values("$._x1", json), vec!["3"]);
+    assert_eq!(values("$ .a [ 'b' ]", json), vec!["4"]);
+    assert_eq!(values("$['\\u00e9']", json), vec!["2"]);
+    assert_eq!(values("$[?@.b == 4 ].b", json), vec!["4"]);
+}
+
+#[test]
+fn jsonpath_invalid_test() {
+    let error = |query| JsonPath::compile(query).unwrap_err();
+    assert_eq!(error("a"), PathError::UnexpectedChar { c: 'a', offset: 0 });
+    assert_eq!(error("$."), PathError::UnexpectedEnd);
+    assert_eq!(
+        error("$.a "),
+        PathError::UnexpectedChar { c: ' ', offset: 3 }
+    );
+    assert_eq!(
+        error("$. a"),
+        PathError::UnexpectedChar { c: ' ', offset: 2 }
+    );
+    assert_eq!(error("$[01]"), PathError::InvalidInteger { offset: 2 });
+    assert_eq!(error("$[-0]"), PathError::InvalidInteger { offset: 2 });
+    assert_eq!(
+        error("$[9007199254740992]"),
+        PathError::InvalidInteger { offset: 2 }
+    );
+    assert_eq!(error("$['\\a']"), PathError::InvalidEscape { offset: 3 });
+    assert_eq!(error("$['\\\"']"), PathError::InvalidEscape { offset: 3 });
+    assert_eq!(
+        error("$['\\ud800']"),
+        PathError::InvalidEscape { offset: 3 }
+    );
+    assert_eq!(
+        error("$[?foo(@)]"),
+        PathError::UnknownFunction {
+            name: "foo".to_owned(),
+            offset: 3
+        }
+    );
+    // Queries which can select many nodes can't be compared
+    assert_eq!(error("$[?@.* == 1]"), PathError::TypeMismatch { offset: 3 });
+    assert_eq!(
+        error("$[?1 == $..a]"),
+        PathError::TypeMismatch { offset: 8 }
+    );
+    // Literals and values aren't tests
+    assert_eq!(error("$[?1]"), PathError::TypeMismatch { offset: 3 });
+    assert_eq!(
+        error("$[?length(@.a)]"),
+        PathError::TypeMismatch { offset: 3 }
+    );
+    assert_eq!(
+        error("$[?(@.a) == 1]"),
+        PathError::UnexpectedChar { c: '=', offset: 9 }
+    );
+    // Function arguments are type checked
+    assert_eq!(
+        error("$[?length(@.*) == 1]"),
+        PathError::InvalidArgument { offset: 10 }
+    );
+    assert_eq!(
+        error("$[?count(1) == 1]"),
+        PathError::InvalidArgument { offset: 9 }
+    );
+    assert_eq!(
+        error("$[?match(@.a)]"),
+        PathError::InvalidArgument { offset: 3 }
+    );
+    assert_eq!(
+        error("$[?length (@.a) == 1]"),
+        PathError::UnexpectedChar { c: 'l', offset: 3 }
+    );
+}
@@ -0,0 +1,165 @@
+mod test_data;
+
+use std::collections::HashMap;
+
+use json::{lazy::LazyValue, tape::TapeDocument, Element, UnmarshalError, Unmarshalable};
+
+#[test]
+fn lazy_navigation_test() {
+    let doc = LazyValue::new(test_data::WIDGET);
+    let window = doc
+        .get("widget")
+        .unwrap()
+        .unwrap()
+        .get("window")
+        .unwrap()
+        .unwrap();
+    assert!(window.is_object());
+    assert_eq!(
+        window
+            .get("title")
+            .unwrap()
+            .unwrap()
+            .parse::<String>()
+            .unwrap(),
+        "Sample Konfabulator Widget"
+    );
+    assert_eq!(
+        window
+            .get("width")
+            .unwrap()
+            .unwrap()
+            .parse::<i64>()
+            .unwrap(),
+        500
+    );
+    assert!(window.get("missing").unwrap().is_none());
+    // Navigating into something that isn't an object finds nothing
+    assert!(window
+        .get("width")
+        .unwrap()
+        .unwrap()
+        .get("x")
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn lazy_elements_test() {
+    let doc = LazyValue::new(r#"  [{"a": [1, {"b": "]"}]}, "two", 3.5]"#);
+    assert!(doc.is_array());
+    assert_eq!(doc.elements().count(), 3);
+    assert_eq!(
+        doc.index(1)
+            .unwrap()
+            .unwrap()
+            .parse_borrowed::<&str>()
+            .unwrap(),
+        "two"
+    );
+    assert_eq!(doc.index(2).unwrap().unwrap().parse::<f64>().unwrap(), 3.5);
+    assert!(doc.index(3).unwrap().is_none());
+    assert_eq!(
+        doc.index(0).unwrap().unwrap().raw().unwrap(),
+        r#"{"a": [1, {"b": "]"}]}"#
+    );
+}
+
+#[test]
+fn lazy_members_test() {
+    let doc = LazyValue::new(r#"{"x": [1, 2], "y\n": null}"#);
+    let members = doc
+        .members()
+        .map(|m| m.map(|(k, v)| (k.into_owned(), v.parse::<Element>().unwrap())))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(
+        members,
+        vec![
+            (
+                "x".to_owned(),
+                Element::JsonList(vec![Element::JsonInt(1), Element::JsonInt(2)])
+            ),
+            ("y\n".to_owned(), Element::JsonNull),
+        ]
+    );
+}
+
+#[test]
+fn lazy_skipped_subtree_test() {
+    // Untouched subtrees are only checked for balanced brackets
+    let doc = LazyValue::new(r#"{"skip": [nonsense, 1 2], "keep": true}"#);
+    assert!(doc.get("keep").unwrap().unwrap().parse::<bool>().unwrap());
+    assert!(LazyValue::new_checked(r#"{"skip": [nonsense, 1 2], "keep": true}"#).is_ok());
+}
+
+#[test]
+fn lazy_error_test() {
+    assert_eq!(
+        LazyValue::new("{\"a\": [1, 2}, \"b\": 1}")
+            .get("b")
+            .unwrap_err(),
+        UnmarshalError::UnexpectedChar {
+            c: '}',
+            row: 1,
+            col: 12
+        }
+    );
+    // Positions are relative to the whole input
+    let value = LazyValue::new("{\n  \"a\": tru}")
+        .get("a")
+        .unwrap()
+        .unwrap();
+    assert_eq!(value.position().row, 2);
+    assert_eq!(
+        value.parse::<bool>().unwrap_err(),
+        UnmarshalError::UnexpectedChar {
+            c: '}',
+            row: 2,
+            col: 11
+        }
+    );
+    assert_eq!(
+        LazyValue::new_checked("[1] 2").unwrap_err(),
+        UnmarshalError::UnexpectedChar {
+            c: '2',
+            row: 1,
+            col: 5
+        }
+    );
+    assert_eq!(
+        LazyValue::new_checked("[\"1]").unwrap_err(),
+        UnmarshalError::EndOfChars
+    );
+}
+
+#[test]
+fn lazy_duplicate_key_test() {
+    // The last value wins, the same as a TapeDocument and a HashMap
+    let s = r#"{"a": 1, "b": true, "a": 2}"#;
+    let value = LazyValue::new_checked(s).unwrap();
+    assert_eq!(value.get("a").unwrap().unwrap().parse::<i64>(), Ok(2));
+    let doc = TapeDocument::unmarshal_json(s.chars()).unwrap();
+    assert_eq!(doc.root().get("a").and_then(|c| c.as_i64()), Some(2));
+    let map = HashMap::<String, Element>::unmarshal_json(s.chars()).unwrap();
+    assert_eq!(map["a"], Element::JsonInt(2));
+}
+
+#[test]
+fn lazy_matches_element_test() {
+    for s in [
+        test_data::GLOSSARY,
+        test_data::MENU1,
+        test_data::WIDGET,
+        test_data::WEB_APP,
+        test_data::MENU2,
+    ] {
+        assert_eq!(
+            LazyValue::new_checked(s)
+                .unwrap()
+                .parse::<Element>()
+                .unwrap(),
+            Element::unmarshal_json(s.chars()).unwrap()
+        );
+    }
+}
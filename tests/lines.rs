@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+
+use json::{
+    marshal::LinesWriter,
+    unmarshal::{LineError, LinesIter, OnError},
+    Element, UnmarshalError,
+};
+
+#[test]
+fn lines_read_test() {
+    let input = "{\"a\": 1}\r\n\n  \r\n{\"a\": 2}\n{\"a\": 3}";
+    let values: Vec<HashMap<String, i64>> = LinesIter::new(input.as_bytes())
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(
+        values,
+        (1..=3)
+            .map(|i| HashMap::from([("a".to_owned(), i)]))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn lines_stop_on_error_test() {
+    let mut iter = LinesIter::<_, i64>::new("1\n\n2 3\n4\n".as_bytes());
+    assert_eq!(iter.next().unwrap().unwrap(), 1);
+    assert_eq!(
+        iter.next().unwrap().unwrap_err(),
+        LineError {
+            line: 3,
+            error: UnmarshalError::UnexpectedChar {
+                c: '3',
+                row: 3,
+                col: 3
+            }
+        }
+    );
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn lines_skip_errors_test() {
+    let iter = LinesIter::<_, i64>::new("1\n[\n3\n".as_bytes()).on_error(OnError::Skip);
+    assert_eq!(iter.collect::<Result<Vec<_>, _>>().unwrap(), vec![1, 3]);
+}
+
+#[test]
+fn lines_collect_errors_test() {
+    let mut iter =
+        LinesIter::<_, bool>::new("true\nnope\n\"x\nfalse".as_bytes()).on_error(OnError::Collect);
+    assert_eq!(
+        iter.by_ref().collect::<Result<Vec<_>, _>>().unwrap(),
+        vec![true, false]
+    );
+    assert_eq!(
+        iter.errors(),
+        &[
+            LineError {
+                line: 2,
+                error: UnmarshalError::UnexpectedChar {
+                    c: 'n',
+                    row: 2,
+                    col: 1
+                }
+            },
+            LineError {
+                line: 3,
+                error: UnmarshalError::UnexpectedChar {
+                    c: '"',
+                    row: 3,
+                    col: 1
+                }
+            },
+        ]
+    );
+}
+
+#[test]
+fn lines_write_test() {
+    let mut w = LinesWriter::new(Vec::new());
+    w.write(&Element::JsonList(vec![
+        Element::JsonInt(1),
+        Element::JsonNull,
+    ]))
+    .unwrap();
+    w.write("multi\nline").unwrap();
+    w.write(&1.5).unwrap();
+    let out = w.into_inner().unwrap();
+    assert_eq!(out, b"[1, null]\n\"multi\\nline\"\n1.5\n");
+
+    let values: Vec<Element> = LinesIter::new(out.as_slice())
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(values[1], Element::JsonString("multi\nline".to_owned()));
+}
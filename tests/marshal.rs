@@ -1,6 +1,10 @@
 use std::collections::HashMap;
 
-use json::{Element::*, Marshalable};
+use json::{
+    marshal::{Escaping, HexCase, MarshalOptions},
+    Element::*,
+    Marshalable,
+};
 
 #[test]
 fn marshal_int_test() {
@@ -215,3 +219,83 @@ fn marshal_mixed_list_element_test() {
         r#"[{}, [], "", 1, 2.5, true, false, null]"#
     );
 }
+
+#[test]
+fn marshal_escaped_string_test() {
+    assert_eq!(
+        "a/\\\x08\x0c\n\r\t\x01é中🦀z".to_owned().marshal_json(),
+        "\"a\\/\\\\\\b\\f\\n\\r\\t\x01\\u00e9\\u4e2d\\ud83e\\udd80z\""
+    );
+}
+
+#[test]
+fn marshal_minimal_escaping_test() {
+    let options = MarshalOptions {
+        escaping: Escaping::Minimal,
+        ..Default::default()
+    };
+    assert_eq!(
+        "</a>\"\\\n\x01é🦀\u{2028}".marshal_json_with(&options),
+        "\"</a>\\\"\\\\\\n\\u0001é🦀\u{2028}\""
+    );
+}
+
+#[test]
+fn marshal_html_safe_escaping_test() {
+    let options = MarshalOptions {
+        escaping: Escaping::HtmlSafe,
+        ..Default::default()
+    };
+    assert_eq!(
+        "</script>&é\u{2028}\u{2029}".marshal_json_with(&options),
+        r#""\u003c/script\u003e\u0026é\u2028\u2029""#
+    );
+}
+
+#[test]
+fn marshal_upper_hex_escaping_test() {
+    let options = MarshalOptions {
+        hex_case: HexCase::Upper,
+        ..Default::default()
+    };
+    assert_eq!("❤🦀".marshal_json_with(&options), r#""\u2764\uD83E\uDD80""#);
+    let options = MarshalOptions {
+        escaping: Escaping::Minimal,
+        ..options
+    };
+    assert_eq!("\x1f".marshal_json_with(&options), r#""\u001F""#);
+}
+
+#[test]
+fn marshal_control_character_escaping_test() {
+    // The default escaping outputs control characters without a short
+    // escape raw, which the other policies escape
+    assert_eq!("\x01\x1f".marshal_json(), "\"\x01\x1f\"");
+    for escaping in [Escaping::Minimal, Escaping::HtmlSafe] {
+        let options = MarshalOptions {
+            escaping,
+            ..Default::default()
+        };
+        assert_eq!("\x01\x1f".marshal_json_with(&options), r#""\u0001\u001f""#);
+    }
+}
+
+#[test]
+fn marshal_nested_escaping_test() {
+    let options = MarshalOptions {
+        escaping: Escaping::Minimal,
+        ..Default::default()
+    };
+    assert_eq!(
+        JsonList(vec![JsonObject(HashMap::from([(
+            "🦀".to_owned(),
+            JsonString("❤/".to_owned())
+        )]))])
+        .marshal_json_with(&options),
+        r#"[{"🦀": "❤/"}]"#
+    );
+    assert_eq!(
+        vec![Some("é".to_owned()), None].marshal_json_with(&options),
+        r#"["é", null]"#
+    );
+}
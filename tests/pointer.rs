@@ -0,0 +1,165 @@
+mod test_data;
+
+use std::collections::HashMap;
+
+use json::{pointer::PointerError, Element, Unmarshalable};
+
+fn doc() -> Element {
+    Element::unmarshal_json(
+        r#"{"foo": ["bar", "baz"], "": 0, "a/b": 1, "m~n": 8, " ": 7, "nested": {"x": [{"y": true}]}}"#
+            .chars(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn pointer_lookup_test() {
+    // Examples from RFC 6901
+    let doc = doc();
+    assert_eq!(doc.pointer(""), Some(&doc));
+    assert_eq!(
+        doc.pointer("/foo"),
+        Some(&Element::JsonList(vec![
+            Element::JsonString("bar".to_owned()),
+            Element::JsonString("baz".to_owned())
+        ]))
+    );
+    assert_eq!(
+        doc.pointer("/foo/0"),
+        Some(&Element::JsonString("bar".to_owned()))
+    );
+    assert_eq!(doc.pointer("/"), Some(&Element::JsonInt(0)));
+    assert_eq!(doc.pointer("/a~1b"), Some(&Element::JsonInt(1)));
+    assert_eq!(doc.pointer("/m~0n"), Some(&Element::JsonInt(8)));
+    assert_eq!(doc.pointer("/ "), Some(&Element::JsonInt(7)));
+    assert_eq!(doc.pointer("/nested/x/0/y"), Some(&Element::JsonBool(true)));
+    assert_eq!(doc.pointer("/foo/2"), None);
+    assert_eq!(doc.pointer("/foo/-"), None);
+    assert_eq!(doc.pointer("foo"), None);
+}
+
+#[test]
+fn pointer_error_test() {
+    let doc = doc();
+    assert_eq!(
+        doc.try_pointer("foo").unwrap_err(),
+        PointerError::Malformed { offset: 0 }
+    );
+    assert_eq!(
+        doc.try_pointer("/foo/~2").unwrap_err(),
+        PointerError::Malformed { offset: 5 }
+    );
+    assert_eq!(
+        doc.try_pointer("/missing/0").unwrap_err(),
+        PointerError::NotFound {
+            pointer: "/missing".to_owned()
+        }
+    );
+    assert_eq!(
+        doc.try_pointer("/foo/01").unwrap_err(),
+        PointerError::InvalidIndex {
+            pointer: "/foo/01".to_owned()
+        }
+    );
+    assert_eq!(
+        doc.try_pointer("/foo/0/x").unwrap_err(),
+        PointerError::NotContainer {
+            pointer: "/foo/0/x".to_owned()
+        }
+    );
+}
+
+#[test]
+fn pointer_mutation_test() {
+    let mut doc = doc();
+    *doc.pointer_mut("/nested/x/0/y").unwrap() = Element::JsonNull;
+    assert_eq!(doc.pointer("/nested/x/0/y"), Some(&Element::JsonNull));
+
+    // Inserting into arrays
+    assert_eq!(doc.pointer_insert("/foo/-", Element::JsonInt(3)), Ok(None));
+    assert_eq!(doc.pointer_insert("/foo/0", Element::JsonInt(1)), Ok(None));
+    assert_eq!(
+        doc.pointer_insert("/foo/5", Element::JsonInt(0)),
+        Err(PointerError::NotFound {
+            pointer: "/foo/5".to_owned()
+        })
+    );
+    assert_eq!(
+        doc.pointer("/foo"),
+        Some(&Element::JsonList(vec![
+            Element::JsonInt(1),
+            Element::JsonString("bar".to_owned()),
+            Element::JsonString("baz".to_owned()),
+            Element::JsonInt(3),
+        ]))
+    );
+
+    // Inserting into objects replaces existing members
+    assert_eq!(
+        doc.pointer_insert("/new~1key", Element::JsonBool(false)),
+        Ok(None)
+    );
+    assert_eq!(doc.pointer("/new~1key"), Some(&Element::JsonBool(false)));
+    assert_eq!(
+        doc.pointer_insert("/ ", Element::JsonNull),
+        Ok(Some(Element::JsonInt(7)))
+    );
+    assert_eq!(
+        doc.pointer_insert("/foo/0/x", Element::JsonNull),
+        Err(PointerError::NotContainer {
+            pointer: "/foo/0/x".to_owned()
+        })
+    );
+
+    // Replacing only works on existing values
+    assert_eq!(
+        doc.pointer_replace("/foo/1", Element::JsonInt(2)),
+        Ok(Element::JsonString("bar".to_owned()))
+    );
+    assert_eq!(
+        doc.pointer_replace("/missing", Element::JsonInt(2)),
+        Err(PointerError::NotFound {
+            pointer: "/missing".to_owned()
+        })
+    );
+
+    // Removing
+    assert_eq!(doc.pointer_remove("/foo/0"), Ok(Element::JsonInt(1)));
+    assert_eq!(doc.pointer("/foo/0"), Some(&Element::JsonInt(2)));
+    assert_eq!(doc.pointer_remove("/m~0n"), Ok(Element::JsonInt(8)));
+    assert_eq!(doc.pointer("/m~0n"), None);
+    assert_eq!(
+        doc.pointer_remove("/foo/-"),
+        Err(PointerError::NotFound {
+            pointer: "/foo/-".to_owned()
+        })
+    );
+    assert_eq!(doc.pointer_remove(""), Err(PointerError::Root));
+}
+
+#[test]
+fn pointer_root_insert_test() {
+    let mut doc = Element::JsonObject(HashMap::new());
+    assert_eq!(
+        doc.pointer_insert("", Element::JsonInt(1)),
+        Ok(Some(Element::JsonObject(HashMap::new())))
+    );
+    assert_eq!(doc, Element::JsonInt(1));
+}
+
+#[test]
+fn pointer_matches_spans_test() {
+    // Every pointer recorded by a spanned parse finds a value
+    for s in [
+        test_data::GLOSSARY,
+        test_data::MENU1,
+        test_data::WIDGET,
+        test_data::WEB_APP,
+        test_data::MENU2,
+    ] {
+        let (element, spans) = Element::unmarshal_json_spanned(s.chars()).unwrap();
+        for pointer in spans.pointers() {
+            assert!(element.pointer(pointer).is_some(), "{pointer}");
+        }
+    }
+}
@@ -0,0 +1,115 @@
+mod test_data;
+
+use json::{
+    unmarshal::{PushParser, Status},
+    Element, UnmarshalError, Unmarshalable,
+};
+
+fn parse_in_chunks(input: &[u8], size: usize) -> Element {
+    let mut p = PushParser::new();
+    for chunk in input.chunks(size) {
+        if let Status::Complete(e) = p.feed(chunk).unwrap() {
+            assert_eq!(p.finish().unwrap(), None);
+            return e;
+        }
+    }
+    let e = p.finish().unwrap().unwrap();
+    assert_eq!(p.finish().unwrap(), None);
+    e
+}
+
+#[test]
+fn push_parser_test_data_test() {
+    for data in [
+        test_data::GLOSSARY,
+        test_data::MENU1,
+        test_data::WIDGET,
+        test_data::WEB_APP,
+        test_data::MENU2,
+    ] {
+        let expect = Element::unmarshal_json(data.chars()).unwrap();
+        for size in [1, 2, 3, 7, 64, data.len()] {
+            assert_eq!(parse_in_chunks(data.as_bytes(), size), expect);
+        }
+    }
+}
+
+#[test]
+fn push_parser_split_escapes_test() {
+    let data = r#"{"🦀 🦀": ["❤\n\"", -0.5e1, true, null]}"#;
+    let expect = Element::unmarshal_json(data.chars()).unwrap();
+    for size in 1..=data.len() {
+        assert_eq!(parse_in_chunks(data.as_bytes(), size), expect);
+    }
+}
+
+#[test]
+fn push_parser_root_number_test() {
+    let mut p = PushParser::new();
+    assert_eq!(p.feed(b"12").unwrap(), Status::NeedMoreInput);
+    assert_eq!(p.feed(b"34").unwrap(), Status::NeedMoreInput);
+    assert_eq!(p.finish().unwrap(), Some(Element::JsonInt(1234)));
+    assert_eq!(p.finish().unwrap(), None);
+}
+
+#[test]
+fn push_parser_multiple_values_test() {
+    let mut p = PushParser::new();
+    assert_eq!(
+        p.feed(b"[1] {\"a\"").unwrap(),
+        Status::Complete(Element::JsonList(vec![Element::JsonInt(1)]))
+    );
+    assert_eq!(p.feed(b": 2").unwrap(), Status::NeedMoreInput);
+    assert!(matches!(
+        p.feed(b"} 3").unwrap(),
+        Status::Complete(Element::JsonObject(_))
+    ));
+    assert_eq!(p.finish().unwrap(), Some(Element::JsonInt(3)));
+}
+
+#[test]
+fn push_parser_many_values_in_chunk_test() {
+    // Values left over from a chunk are parsed in place, so draining a chunk
+    // of many values doesn't copy the rest of it for each one
+    let chunk = "[1] ".repeat(100_000);
+    let mut p = PushParser::new();
+    let mut values = vec![];
+    let mut status = p.feed(chunk.as_bytes()).unwrap();
+    while let Status::Complete(element) = status {
+        values.push(element);
+        // Feeding more input keeps the values which haven't been parsed yet
+        let more: &[u8] = if values.len() == 50_000 { b"[2]" } else { b"" };
+        status = p.feed(more).unwrap();
+    }
+    assert_eq!(values.len(), 100_001);
+    assert_eq!(values[99_999], Element::JsonList(vec![Element::JsonInt(1)]));
+    assert_eq!(
+        values[100_000],
+        Element::JsonList(vec![Element::JsonInt(2)])
+    );
+    assert_eq!(p.finish().unwrap(), None);
+}
+
+#[test]
+fn push_parser_errors_test() {
+    let mut p = PushParser::new();
+    assert_eq!(p.feed(b"[1,\n").unwrap(), Status::NeedMoreInput);
+    assert_eq!(
+        p.feed(b" ]").unwrap_err(),
+        UnmarshalError::UnexpectedChar {
+            c: ']',
+            row: 2,
+            col: 2
+        }
+    );
+
+    let mut p = PushParser::new();
+    assert_eq!(p.feed(b"{\"a\": tru").unwrap(), Status::NeedMoreInput);
+    assert_eq!(p.finish().unwrap_err(), UnmarshalError::EndOfChars);
+
+    let mut p = PushParser::new();
+    assert_eq!(
+        p.feed(b"\"a\xff\"").unwrap_err(),
+        UnmarshalError::InvalidUtf8 { row: 1, col: 4 }
+    );
+}
@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+
+use json::{Element, Marshalable, RawJson, UnmarshalError, Unmarshalable};
+
+#[test]
+fn raw_json_capture_test() {
+    let envelope = HashMap::<String, RawJson>::unmarshal_json(
+        r#"{"kind": "event", "payload":  {"b" :[1,2.50, "é"],"a":null} }"#.chars(),
+    )
+    .unwrap();
+    assert_eq!(envelope["kind"].as_str(), r#""event""#);
+    // The exact text is kept, including whitespace and escapes
+    assert_eq!(
+        envelope["payload"].as_str(),
+        r#"{"b" :[1,2.50, "é"],"a":null}"#
+    );
+}
+
+#[test]
+fn raw_json_marshal_test() {
+    let raw = RawJson::new(" [1,  \"\u{e9}\"] ").unwrap();
+    assert_eq!(raw.as_str(), "[1,  \"\u{e9}\"]");
+    let list = vec![raw.clone(), RawJson::new("{}").unwrap()];
+    assert_eq!(list.marshal_json(), "[[1,  \"\u{e9}\"], {}]");
+}
+
+#[test]
+fn raw_json_parse_test() {
+    let raw = RawJson::new(r#"{"a": [true]}"#).unwrap();
+    assert_eq!(
+        raw.parse::<HashMap<String, Vec<bool>>>().unwrap(),
+        HashMap::from([("a".to_owned(), vec![true])])
+    );
+    assert_eq!(
+        raw.parse::<Element>().unwrap(),
+        Element::unmarshal_json(raw.as_str().chars()).unwrap()
+    );
+    assert!(raw.parse::<Vec<i64>>().is_err());
+}
+
+#[test]
+fn raw_json_invalid_test() {
+    assert_eq!(
+        Vec::<RawJson>::unmarshal_json(r#"[{"a": 1,}]"#.chars()).unwrap_err(),
+        UnmarshalError::UnexpectedChar {
+            c: '}',
+            row: 1,
+            col: 10
+        }
+    );
+    assert_eq!(
+        RawJson::new("[1] [2]").unwrap_err(),
+        UnmarshalError::UnexpectedChar {
+            c: '[',
+            row: 1,
+            col: 5
+        }
+    );
+    assert_eq!(
+        RawJson::unmarshal_json_bytes(b"[\"\xff\"]").unwrap_err(),
+        UnmarshalError::InvalidUtf8 { row: 1, col: 3 }
+    );
+}
+
+#[test]
+fn raw_json_matches_element_errors_test() {
+    for json in ["[1, x]", "{\"a\": }", "{\"a\" 1}", "[nul]", "[1,]", "{,}", ""] {
+        assert_eq!(
+            RawJson::new(json).unwrap_err(),
+            Element::unmarshal_json(json.chars()).unwrap_err(),
+            "{json}"
+        );
+    }
+}
@@ -0,0 +1,79 @@
+mod test_data;
+
+use json::unmarshal::scan::Backend;
+
+fn inputs() -> Vec<Vec<u8>> {
+    let mut inputs: Vec<Vec<u8>> = [
+        test_data::GLOSSARY,
+        test_data::MENU1,
+        test_data::WIDGET,
+        test_data::WEB_APP,
+        test_data::MENU2,
+    ]
+    .iter()
+    .map(|s| s.as_bytes().to_vec())
+    .collect();
+    inputs.push("  \t\r\n  \n".repeat(20).into_bytes());
+    inputs.push("🦀❤ ascii \x01 text \\ \"quoted\" ".repeat(10).into_bytes());
+    let mut invalid = "a".repeat(70).into_bytes();
+    invalid.extend_from_slice(b"\xe2\x9d");
+    invalid.extend(b"b".repeat(40));
+    inputs.push(invalid);
+    // Invalid sequences after non-ASCII text, including overlong encodings,
+    // surrogates, code points past U+10FFFF and a truncated final sequence
+    for bad in [
+        &b"\xc0\xaf"[..],
+        b"\xe0\x80\xaf",
+        b"\xed\xa0\x80",
+        b"\xf0\x80\x80\xaf",
+        b"\xf4\x90\x80\x80",
+        b"\xf5\x80\x80\x80",
+        b"\xe2\x28\xa1",
+        b"\xbf",
+    ] {
+        let mut invalid = "é🦀 ascii ❤".repeat(6).into_bytes();
+        invalid.extend_from_slice(bad);
+        invalid.extend("🦀 text".repeat(6).into_bytes());
+        inputs.push(invalid);
+    }
+    let mut truncated = "é🦀 ascii ❤".repeat(6).into_bytes();
+    truncated.extend_from_slice(b"\xf0\x9f\xa6");
+    inputs.push(truncated);
+    inputs
+}
+
+#[test]
+fn scan_backends_match_scalar_test() {
+    for input in inputs() {
+        for start in 0..input.len() {
+            let bytes = &input[start..];
+            for backend in Backend::available() {
+                assert_eq!(
+                    backend.find_string_special(bytes),
+                    Backend::Scalar.find_string_special(bytes),
+                    "{backend:?} find_string_special at {start}"
+                );
+                assert_eq!(
+                    backend.skip_whitespace(bytes),
+                    Backend::Scalar.skip_whitespace(bytes),
+                    "{backend:?} skip_whitespace at {start}"
+                );
+                assert_eq!(
+                    backend.validate_utf8(bytes),
+                    Backend::Scalar.validate_utf8(bytes),
+                    "{backend:?} validate_utf8 at {start}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn scan_scalar_test() {
+    assert_eq!(Backend::Scalar.find_string_special(b"abc\"d"), 3);
+    assert_eq!(Backend::Scalar.find_string_special(b"a\nb"), 1);
+    assert_eq!(Backend::Scalar.find_string_special(b"abc"), 3);
+    assert_eq!(Backend::Scalar.skip_whitespace(b" \t\r\nx "), 4);
+    assert_eq!(Backend::Scalar.validate_utf8(b"ab\xffc"), Err(2));
+    assert_eq!(Backend::Scalar.validate_utf8("🦀".as_bytes()), Ok(()));
+}
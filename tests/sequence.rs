@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+
+use json::{
+    marshal::SequenceWriter,
+    unmarshal::{Document, Framing, SequenceIter},
+    Element, UnmarshalError,
+};
+
+#[test]
+fn concatenated_test() {
+    let input = r#"{"a":1}{"b":2} [3]"x"4 5"#;
+    let documents: Vec<Document<Element>> =
+        SequenceIter::new(input.as_bytes(), Framing::Concatenated)
+            .collect::<Result<_, _>>()
+            .unwrap();
+    let offsets: Vec<usize> = documents.iter().map(|d| d.offset).collect();
+    assert_eq!(offsets, vec![0, 7, 15, 18, 21, 23]);
+    assert_eq!(
+        documents[1].value,
+        Element::JsonObject(HashMap::from([("b".to_owned(), Element::JsonInt(2))]))
+    );
+    assert_eq!(documents[5].value, Element::JsonInt(5));
+}
+
+#[test]
+fn concatenated_error_stops_test() {
+    let mut iter = SequenceIter::<_, i64>::new("1 x 3".as_bytes(), Framing::Concatenated);
+    assert_eq!(iter.next().unwrap().unwrap().value, 1);
+    assert_eq!(
+        iter.next().unwrap().unwrap_err(),
+        UnmarshalError::UnexpectedChar {
+            c: 'x',
+            row: 1,
+            col: 3
+        }
+    );
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn text_sequence_test() {
+    let input = "\x1e{\"a\": 1}\n\x1e\x1e 2\n\x1e[tru\n\x1e\"🦀\"\n";
+    let mut iter = SequenceIter::<_, Element>::new(input.as_bytes(), Framing::TextSequence);
+    assert_eq!(iter.next().unwrap().unwrap().offset, 1);
+    assert_eq!(
+        iter.next().unwrap().unwrap(),
+        Document {
+            offset: 13,
+            value: Element::JsonInt(2)
+        }
+    );
+    // A truncated record doesn't stop the sequence
+    assert_eq!(
+        iter.next().unwrap().unwrap_err(),
+        UnmarshalError::EndOfChars
+    );
+    assert_eq!(
+        iter.next().unwrap().unwrap(),
+        Document {
+            offset: 22,
+            value: Element::JsonString("🦀".to_owned())
+        }
+    );
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn text_sequence_round_trip_test() {
+    let mut w = SequenceWriter::new(Vec::new());
+    w.write(&vec![1, 2]).unwrap();
+    w.write("a").unwrap();
+    let out = w.into_inner().unwrap();
+    assert_eq!(out, b"\x1e[1, 2]\n\x1e\"a\"\n");
+
+    let values: Vec<Element> = SequenceIter::new(out.as_slice(), Framing::TextSequence)
+        .map(|d| d.map(|d| d.value))
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(
+        values,
+        vec![
+            Element::JsonList(vec![Element::JsonInt(1), Element::JsonInt(2)]),
+            Element::JsonString("a".to_owned())
+        ]
+    );
+}
@@ -0,0 +1,62 @@
+use json::{
+    unmarshal::{Position, Span},
+    Element, UnmarshalError,
+};
+
+fn pos(offset: usize, row: u32, col: u32, col_utf8: u32, col_utf16: u32) -> Position {
+    Position {
+        offset,
+        row,
+        col,
+        col_utf8,
+        col_utf16,
+    }
+}
+
+#[test]
+fn spanned_root_test() {
+    let (element, spans) = Element::unmarshal_json_spanned("  15 ".chars()).unwrap();
+    assert_eq!(element, Element::JsonInt(15));
+    assert_eq!(
+        spans.value("").unwrap(),
+        &Span {
+            start: pos(2, 1, 3, 3, 3),
+            end: pos(4, 1, 5, 5, 5),
+        }
+    );
+}
+
+#[test]
+fn spanned_nested_test() {
+    let input = "{\n  \"a\": [true, null],\n  \"b/c\": {\"~\": \"x\"}\n}";
+    let (_, spans) = Element::unmarshal_json_spanned(input.chars()).unwrap();
+
+    assert_eq!(spans.key("/a").unwrap().start, pos(4, 2, 3, 3, 3));
+    assert_eq!(spans.key("/a").unwrap().end, pos(7, 2, 6, 6, 6));
+    assert_eq!(spans.value("/a").unwrap().start, pos(9, 2, 8, 8, 8));
+    assert_eq!(spans.value("/a/1").unwrap().start, pos(16, 2, 15, 15, 15));
+    assert_eq!(spans.value("/a/1").unwrap().end, pos(20, 2, 19, 19, 19));
+    assert_eq!(spans.value("/b~1c/~0").unwrap().start.row, 3);
+    assert_eq!(spans.value("").unwrap().end, pos(input.len(), 4, 2, 2, 2));
+    assert!(spans.value("/c").is_none());
+    assert!(spans.key("").is_none());
+}
+
+#[test]
+fn spanned_unicode_columns_test() {
+    let (_, spans) = Element::unmarshal_json_spanned(r#"["🦀", "❤", 1]"#.chars()).unwrap();
+    assert_eq!(spans.value("/1").unwrap().start, pos(9, 1, 7, 10, 8));
+    assert_eq!(spans.value("/2").unwrap().start, pos(16, 1, 12, 17, 13));
+}
+
+#[test]
+fn spanned_error_test() {
+    assert_eq!(
+        Element::unmarshal_json_spanned("[1,\n 2,]".chars()).unwrap_err(),
+        UnmarshalError::UnexpectedChar {
+            c: ']',
+            row: 2,
+            col: 4
+        }
+    );
+}
@@ -0,0 +1,138 @@
+mod test_data;
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+use json::{tape::TapeDocument, Element, UnmarshalError, Unmarshalable};
+
+/// Counts the bytes allocated by each thread, so tests running in parallel
+/// don't affect each other
+struct CountingAlloc;
+
+thread_local! {
+    static ALLOCATED: Cell<isize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.with(|a| a.set(a.get() + layout.size() as isize));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.with(|a| a.set(a.get() - layout.size() as isize));
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// Returns the bytes still allocated by a value after building it
+fn retained_bytes<T>(f: impl FnOnce() -> T) -> (T, isize) {
+    let before = ALLOCATED.with(Cell::get);
+    let value = f();
+    (value, ALLOCATED.with(Cell::get) - before)
+}
+
+#[test]
+fn tape_navigation_test() {
+    let doc = TapeDocument::unmarshal_json(
+        r#"{"name": "tape", "tags": ["a", "b\n"], "n": {"i": -3, "f": 1.5, "ok": true, "none": null}}"#
+            .chars(),
+    )
+    .unwrap();
+    let root = doc.root();
+    assert!(root.is_object());
+    assert_eq!(root.len(), Some(3));
+    assert_eq!(root.get("name").and_then(|c| c.as_str()), Some("tape"));
+    assert_eq!(root.get("missing").map(|c| c.is_null()), None);
+
+    let tags = root.get("tags").unwrap();
+    assert!(tags.is_array());
+    assert_eq!(tags.index(1).and_then(|c| c.as_str()), Some("b\n"));
+    assert!(tags.index(2).is_none());
+    assert_eq!(
+        tags.elements()
+            .filter_map(|c| c.as_str())
+            .collect::<Vec<_>>(),
+        vec!["a", "b\n"]
+    );
+
+    let n = root.get("n").unwrap();
+    assert_eq!(n.get("i").and_then(|c| c.as_i64()), Some(-3));
+    assert_eq!(n.get("f").and_then(|c| c.as_f64()), Some(1.5));
+    assert_eq!(n.get("ok").and_then(|c| c.as_bool()), Some(true));
+    assert!(n.get("none").unwrap().is_null());
+    assert_eq!(
+        root.members().map(|(k, _)| k).collect::<Vec<_>>(),
+        vec!["name", "tags", "n"]
+    );
+}
+
+#[test]
+fn tape_duplicate_key_test() {
+    let doc = TapeDocument::unmarshal_json(r#"{"a": 1, "a": 2}"#.chars()).unwrap();
+    assert_eq!(doc.root().get("a").and_then(|c| c.as_i64()), Some(2));
+}
+
+#[test]
+fn tape_to_element_test() {
+    for s in [
+        test_data::GLOSSARY,
+        test_data::MENU1,
+        test_data::WIDGET,
+        test_data::WEB_APP,
+        test_data::MENU2,
+    ] {
+        let doc = TapeDocument::unmarshal_json(s.chars()).unwrap();
+        assert_eq!(
+            doc.to_element(),
+            Element::unmarshal_json(s.chars()).unwrap()
+        );
+    }
+}
+
+#[test]
+fn tape_error_test() {
+    assert_eq!(
+        TapeDocument::unmarshal_json("[1, 2}".chars()).unwrap_err(),
+        UnmarshalError::UnexpectedChar {
+            c: '}',
+            row: 1,
+            col: 6
+        }
+    );
+    assert_eq!(
+        TapeDocument::unmarshal_json("{\"a\": 1".chars()).unwrap_err(),
+        UnmarshalError::EndOfChars
+    );
+    // Values which can't start are rejected like they are for elements
+    for json in ["[1, x]", "{\"a\": }", "[nul]"] {
+        assert_eq!(
+            TapeDocument::unmarshal_json(json.chars()).unwrap_err(),
+            Element::unmarshal_json(json.chars()).unwrap_err(),
+            "{json}"
+        );
+    }
+}
+
+#[test]
+fn tape_memory_test() {
+    for s in [
+        test_data::GLOSSARY,
+        test_data::MENU1,
+        test_data::WIDGET,
+        test_data::WEB_APP,
+        test_data::MENU2,
+    ] {
+        let (element, element_bytes) =
+            retained_bytes(|| Element::unmarshal_json(s.chars()).unwrap());
+        let (tape, tape_bytes) =
+            retained_bytes(|| TapeDocument::unmarshal_json(s.chars()).unwrap());
+        assert!(tape_bytes < element_bytes);
+        assert_eq!(tape.to_element(), element);
+    }
+}
@@ -108,6 +108,7 @@ fn unmarshal_float_trailing_exponent_test() {
 }
 
 #[test]
+#[allow(clippy::bool_assert_comparison)]
 fn unmarshal_bool_test() {
     assert_eq!(bool::unmarshal_json("true".chars()).unwrap(), true);
     assert_eq!(bool::unmarshal_json("false".chars()).unwrap(), false);
@@ -437,3 +438,36 @@ fn unmarshal_trailing_comma_list_test() {
         }
     );
 }
+
+#[test]
+fn unmarshal_bytes_test() {
+    assert_eq!(
+        Vec::<String>::unmarshal_json_bytes(r#"["a", "🦀\n"]"#.as_bytes()).unwrap(),
+        vec!["a".to_owned(), "🦀\n".to_owned()]
+    );
+}
+
+#[test]
+fn unmarshal_bytes_invalid_utf8_test() {
+    assert_eq!(
+        Vec::<String>::unmarshal_json_bytes(b"[\"a\",\n \"b\xc3\"]").unwrap_err(),
+        UnmarshalError::InvalidUtf8 { row: 2, col: 4 }
+    );
+    assert_eq!(
+        Vec::<String>::unmarshal_json_bytes(b"[\"a\"]\xff").unwrap_err(),
+        UnmarshalError::InvalidUtf8 { row: 1, col: 6 }
+    );
+}
+
+#[test]
+fn unmarshal_multiline_error_test() {
+    assert_eq!(
+        HashMap::<String, i64>::unmarshal_json("{\n  \"a\": 1,\n  \"b\": x\n}".chars())
+            .unwrap_err(),
+        UnmarshalError::UnexpectedChar {
+            c: 'x',
+            row: 3,
+            col: 8
+        }
+    );
+}
@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+
+use json::{
+    marshal::{JsonWriter, WriteError},
+    Element, Marshalable, Unmarshalable,
+};
+
+#[test]
+fn writer_nested_test() {
+    let mut w = JsonWriter::new(Vec::new());
+    w.begin_object().unwrap();
+    w.key("a").unwrap();
+    w.begin_array().unwrap();
+    for i in 1..=3 {
+        w.value(&i).unwrap();
+    }
+    w.end_array().unwrap();
+    w.key("b/c").unwrap();
+    w.value(&Some(vec!["x".to_owned()])).unwrap();
+    w.key("d").unwrap();
+    w.begin_object().unwrap();
+    w.end_object().unwrap();
+    w.end_object().unwrap();
+
+    let out = String::from_utf8(w.finish().unwrap()).unwrap();
+    assert_eq!(out, r#"{"a": [1, 2, 3], "b\/c": ["x"], "d": {}}"#);
+}
+
+#[test]
+fn writer_matches_marshal_test() {
+    let element = Element::JsonObject(HashMap::from([(
+        "list".to_owned(),
+        Element::JsonList(vec![Element::JsonNull, Element::JsonFloat(1.5)]),
+    )]));
+    let mut w = JsonWriter::new(Vec::new());
+    w.value(&element).unwrap();
+    let out = w.finish().unwrap();
+    assert_eq!(out, element.marshal_json().into_bytes());
+    assert_eq!(
+        Element::unmarshal_json(std::str::from_utf8(&out).unwrap().chars()).unwrap(),
+        element
+    );
+}
+
+#[test]
+fn writer_value_without_key_test() {
+    let mut w = JsonWriter::new(Vec::new());
+    w.begin_object().unwrap();
+    assert!(matches!(w.value(&1), Err(WriteError::ValueWithoutKey)));
+    w.key("a").unwrap();
+    assert!(matches!(w.key("b"), Err(WriteError::UnexpectedKey)));
+    assert!(matches!(w.end_object(), Err(WriteError::UnbalancedEnd)));
+}
+
+#[test]
+fn writer_unbalanced_test() {
+    let mut w = JsonWriter::new(Vec::new());
+    w.begin_array().unwrap();
+    assert!(matches!(w.key("a"), Err(WriteError::UnexpectedKey)));
+    assert!(matches!(w.end_object(), Err(WriteError::UnbalancedEnd)));
+    assert!(matches!(w.finish(), Err(WriteError::Unfinished)));
+
+    let mut w = JsonWriter::new(Vec::new());
+    assert!(matches!(w.end_array(), Err(WriteError::UnbalancedEnd)));
+    w.value("root").unwrap();
+    assert!(matches!(w.value("again"), Err(WriteError::MultipleRoots)));
+}